@@ -5,18 +5,21 @@ use crate::state::{
     AppConfig, AppResult, AppError
 };
 use crate::services::{ChatService, MessageService, ProfileService, ImageService};
-use crate::services::image::{ImageCache, ImageCacheStats};
+use crate::services::image::{ImageCache, ImageCacheConfig, ImageCacheStats};
 use crate::model::ChatMessageWithMeta;
 use crate::ui::backgrounds::BackgroundManager;
 use crate::ui::themes::ThemeManager;
 use tokio::sync::mpsc;
 use std::sync::Arc;
 use crate::desktop_notifications::DesktopNotificationService;
+use crate::unread_store::UnreadStore;
 
 /// Main application state and controller
 pub struct App<'a> {
     // Network
     pub to_server: mpsc::UnboundedSender<ClientMessage>,
+    /// Messages waiting for a connection, sent in order on reconnect
+    pub outbox: std::collections::VecDeque<ClientMessage>,
     
     // State modules
     pub auth: AuthState,
@@ -40,25 +43,36 @@ pub struct App<'a> {
     pub prefs: crate::global_prefs::GlobalPrefs,
     pub prefs_dirty: bool,
     pub prefs_dirty_last_update: Option<std::time::Instant>,
+    // Custom keybindings
+    pub keymap: crate::keymap::KeyMap,
 }
 
 impl<'a> App<'a> {
     pub fn new(to_server: mpsc::UnboundedSender<ClientMessage>, sound_manager: &'a SoundManager) -> Self {
-        let image_cache = Arc::new(ImageCache::with_default_config());
+        let image_cache = Arc::new(ImageCache::new(ImageCacheConfig {
+            disk_dir: Some(crate::services::image::default_disk_cache_dir()),
+            ..ImageCacheConfig::default()
+        }));
         let chat_service = ChatService::with_image_cache(image_cache.clone());
         let prefs = crate::global_prefs::GlobalPrefs::load();
         let mut theme_manager = ThemeManager::new();
         theme_manager.set_theme_by_name(&prefs.theme_name);
         let mut background_manager = BackgroundManager::new();
         background_manager.set_background_by_name(&prefs.background_name);
-        Self {
+        let mut profile = ProfileState::default();
+        profile.apply_image_protocol(prefs.image_protocol.forced());
+        let mut ui = UiState::default();
+        ui.on_battery = crate::power::on_battery();
+        let (keymap, keymap_problems) = crate::keymap::KeyMap::load();
+        let mut app = Self {
             to_server,
+            outbox: std::collections::VecDeque::new(),
             auth: AuthState::default(),
             chat: ChatState::default(),
             forum: ForumState::default(),
-            profile: ProfileState::default(),
+            profile,
             notifications: NotificationState::default(),
-            ui: UiState::default(),
+            ui,
             sound_manager,
             image_cache,
             chat_service,
@@ -68,28 +82,389 @@ impl<'a> App<'a> {
             prefs,
             prefs_dirty: false,
             prefs_dirty_last_update: None,
+            keymap,
+        };
+        if !keymap_problems.is_empty() {
+            for problem in &keymap_problems {
+                tracing::warn!("Keybindings: {}", problem);
+            }
+            let more = match keymap_problems.len() {
+                1 => String::new(),
+                n => format!(" (+{} more)", n - 1),
+            };
+            app.set_notification(format!("Keybindings: {}{}", keymap_problems[0], more), Some(8000), false);
         }
+        app.prefill_login();
+        app.apply_sound_settings();
+        app
     }
 
     // --- Core App Methods ---
     
+    /// Send to the server. Chat messages are queued while the connection is down or not
+    /// yet signed back in, behind anything already queued so the order is kept. Other
+    /// requests are dropped when offline; they're asked again after logging back in.
     pub fn send_to_server(&mut self, msg: ClientMessage) {
-        if let Err(e) = self.to_server.send(msg) {
-            self.set_notification(format!("Failed to send message: {}", e), Some(3000), true);
+        let is_chat = matches!(msg, ClientMessage::SendChannelMessage { .. } | ClientMessage::SendDirectMessage { .. });
+        if is_chat && (self.auth.reauthenticating || !self.outbox.is_empty()) {
+            self.queue_outgoing(msg);
+        } else if let Err(mpsc::error::SendError(msg)) = self.to_server.send(msg) {
+            if is_chat {
+                self.queue_outgoing(msg);
+            }
+        }
+    }
+
+    fn queue_outgoing(&mut self, msg: ClientMessage) {
+        let (target_id, content) = match &msg {
+            ClientMessage::SendChannelMessage { channel_id, content } => (*channel_id, content.clone()),
+            ClientMessage::SendDirectMessage { to, content } => (*to, content.clone()),
+            _ => return,
+        };
+        self.chat.pending_sends.push(crate::state::PendingSend {
+            id: uuid::Uuid::new_v4(),
+            target_id,
+            content,
+            queued_at: chrono::Utc::now().timestamp(),
+        });
+        self.chat.invalidate_message_cache();
+        self.set_notification(format!("Offline: message queued ({} waiting)", self.chat.pending_sends.len()), Some(2000), true);
+        self.outbox.push_back(msg);
+        if self.outbox.len() > self.config.outbox_capacity {
+            if let Some(ClientMessage::SendChannelMessage { channel_id: target_id, content }
+            | ClientMessage::SendDirectMessage { to: target_id, content }) = self.outbox.pop_front()
+            {
+                self.chat.remove_pending(target_id, &content);
+            }
+            self.set_notification("Offline queue full: dropped the oldest queued message", Some(3000), false);
+        }
+    }
+
+    /// A new connection is up but not logged in. Sign back in with this session's
+    /// credentials; queued messages wait for the server to accept them.
+    pub fn on_reconnected(&mut self) {
+        if self.auth.current_user.is_none() {
+            return;
+        }
+        if let Some((username, password)) = self.auth.session_credentials.clone() {
+            self.auth.reauthenticating = true;
+            self.send_to_server(ClientMessage::Login { username, password });
+        }
+    }
+
+    /// Signed back in after a reconnect: send what was queued, then ask again for
+    /// whatever the old connection may not have answered.
+    fn on_reauthenticated(&mut self, user: nexus_tui_common::User) {
+        self.auth.reauthenticating = false;
+        self.auth.current_user = Some(user);
+        self.chat.invalidate_message_cache();
+        self.flush_outbox();
+        self.chat.catch_up_channels = true;
+        self.chat.catch_up_dms = true;
+        self.send_to_server(ClientMessage::GetServers);
+        self.send_to_server(ClientMessage::GetDMUserList);
+        if let Some(target) = self.chat.current_chat_target.clone() {
+            self.queue_chat_fetch(target);
+            self.flush_chat_fetch();
+        }
+    }
+
+    /// Fetch the newest page of each conversation (channel or DM partner id) read
+    /// before, other than the open one, so what arrived meanwhile counts as unread.
+    fn request_catch_up(&mut self, conversations: Vec<uuid::Uuid>) {
+        let open = match &self.chat.current_chat_target {
+            Some(crate::state::ChatTarget::Channel { channel_id, .. }) => Some(*channel_id),
+            Some(crate::state::ChatTarget::DM { user_id }) => Some(*user_id),
+            None => None,
+        };
+        let channels: std::collections::HashSet<uuid::Uuid> = self.chat.servers.iter().flat_map(|s| s.channels.iter().map(|c| c.id)).collect();
+        for id in conversations {
+            if Some(id) == open || !self.chat.last_read_message.contains_key(&id) || !self.chat.awaiting_catch_up.insert(id) {
+                continue;
+            }
+            if channels.contains(&id) {
+                self.send_to_server(ClientMessage::GetChannelMessages { channel_id: id, before: None });
+            } else {
+                self.send_to_server(ClientMessage::GetDirectMessages { user_id: id, before: None });
+            }
+        }
+    }
+
+    /// Send everything queued while offline, in order, once logged back in.
+    pub fn flush_outbox(&mut self) {
+        while let Some(msg) = self.outbox.pop_front() {
+            if let Err(mpsc::error::SendError(msg)) = self.to_server.send(msg) {
+                self.outbox.push_front(msg);
+                break;
+            }
+        }
+    }
+
+    /// Point the image picker at the protocol chosen on the command line or in preferences
+    pub fn apply_image_protocol(&mut self) {
+        let protocol = self.ui.image_protocol_override.unwrap_or(self.prefs.image_protocol);
+        self.profile.apply_image_protocol(protocol.forced());
+    }
+
+    /// Push the sound on/off, mute and volume preferences into the sound manager
+    pub fn apply_sound_settings(&mut self) {
+        let volume = if self.prefs.sound_effects_enabled && !self.prefs.sound_muted && !self.ui.sound_disabled {
+            self.prefs.sound_volume
+        } else {
+            0
+        };
+        self.sound_manager.set_volume(volume);
+        self.sound_manager.set_dnd(self.prefs.dnd_enabled);
+    }
+
+    pub fn open_server_picker(&mut self) {
+        self.ui.set_mode(crate::state::AppMode::ServerPicker);
+        self.ui.server_picker_state.select(Some(self.ui.active_profile.unwrap_or(0)));
+    }
+
+    /// Go back to the server picker, dropping the current connection (done in the main loop)
+    pub fn request_server_switch(&mut self) {
+        if self.ui.server_profiles.is_empty() {
+            let path = crate::server_profiles::config_path();
+            self.set_notification(format!("No servers configured in {}", path.display()), Some(3000), false);
+        } else if self.ui.mode != crate::state::AppMode::ServerPicker {
+            self.ui.should_disconnect = true;
+        }
+    }
+
+    /// Forget everything from the current connection before connecting somewhere else
+    pub fn end_session(&mut self) {
+        if self.auth.current_user.is_some() {
+            self.save_unread_state();
+        }
+        self.auth.logout();
+        self.chat = ChatState::default();
+        self.forum = ForumState::default();
+        self.notifications = NotificationState::default();
+        self.outbox.clear();
+        self.ui.hide_server_error();
+        self.ui.reset_reconnect();
+        self.ui.active_profile = None;
+        self.apply_appearance();
+        self.prefill_login();
+    }
+
+    /// Schedule the next automatic reconnect with backoff; false when out of attempts
+    pub fn schedule_reconnect(&mut self) -> bool {
+        self.ui.schedule_reconnect(
+            self.config.reconnect_base_delay_ticks,
+            self.config.reconnect_max_delay_ticks,
+            self.prefs.reconnect_max_attempts,
+        )
+    }
+
+    /// Step Do Not Disturb through off -> on -> on for an hour -> off
+    pub fn cycle_dnd(&mut self) {
+        let (enabled, expires, message) = match (self.prefs.dnd_enabled, self.ui.dnd_expires_tick) {
+            (false, _) => (true, None, "Do Not Disturb on".to_string()),
+            (true, None) => (
+                true,
+                Some(self.ui.tick_count + self.config.timed_dnd_ticks),
+                format!("Do Not Disturb for {}", Self::format_dnd_remaining(self.config.timed_dnd_ticks)),
+            ),
+            (true, Some(_)) => (false, None, "Do Not Disturb off".to_string()),
+        };
+        self.set_dnd(enabled, expires);
+        self.set_notification(message, Some(1500), true);
+    }
+
+    fn set_dnd(&mut self, enabled: bool, expires_tick: Option<u64>) {
+        self.prefs.dnd_enabled = enabled;
+        self.ui.dnd_expires_tick = expires_tick;
+        self.apply_sound_settings();
+        *crate::global_prefs::global_prefs_mut() = self.prefs.clone();
+        self.prefs_dirty = true;
+        self.prefs_dirty_last_update = Some(std::time::Instant::now());
+    }
+
+    /// Time left on a timed Do Not Disturb, e.g. "45m"
+    pub fn dnd_remaining(&self) -> Option<String> {
+        let expires = self.ui.dnd_expires_tick.filter(|_| self.prefs.dnd_enabled)?;
+        Some(Self::format_dnd_remaining(expires.saturating_sub(self.ui.tick_count)))
+    }
+
+    fn format_dnd_remaining(ticks: u64) -> String {
+        let minutes = (ticks / 20).div_ceil(60);
+        if minutes >= 60 {
+            format!("{}h{:02}m", minutes / 60, minutes % 60)
+        } else {
+            format!("{}m", minutes)
+        }
+    }
+
+    /// Put the remembered username in the login form and start on the password field
+    pub fn prefill_login(&mut self) {
+        if !self.prefs.remember_username {
+            return;
+        }
+        if let Some(username) = &self.prefs.last_username {
+            self.auth.current_input = username.clone();
+            self.auth.input_mode = Some(crate::state::InputMode::LoginPassword);
+        }
+    }
+
+    /// Key for the logged-in account's appearance, e.g. `alice@chat.example.org:8080`
+    pub fn account_key(&self) -> Option<String> {
+        self.auth.current_user.as_ref().map(|u| format!("{}@{}", u.username, self.ui.server_address))
+    }
+
+    /// Apply the command-line or logged-in account's theme/background, falling back to the global ones
+    pub fn apply_appearance(&mut self) {
+        let account = self.account_key().and_then(|key| self.prefs.account_appearance.get(&key).cloned()).unwrap_or_default();
+        let theme = self.ui.theme_override.clone()
+            .or(account.theme_name)
+            .unwrap_or_else(|| self.prefs.theme_name.clone());
+        let background = self.ui.background_override.clone()
+            .or(account.background_name)
+            .unwrap_or_else(|| self.prefs.background_name.clone());
+        self.theme_manager.set_theme_by_name(&theme);
+        self.background_manager.set_background_by_name(&background);
+    }
+
+    /// Save the current theme as the account's (or, logged out, the global) choice.
+    /// Returns the scope for the confirmation message.
+    pub fn remember_theme(&mut self) -> &'static str {
+        self.ui.theme_override = None;
+        let theme_name = self.theme_manager.get_theme_name().to_string();
+        let scope = match self.account_key() {
+            Some(key) => {
+                self.prefs.account_appearance.entry(key).or_default().theme_name = Some(theme_name);
+                " for this account"
+            }
+            None => {
+                self.prefs.theme_name = theme_name;
+                ""
+            }
+        };
+        self.prefs_dirty = true;
+        self.prefs_dirty_last_update = Some(std::time::Instant::now());
+        scope
+    }
+
+    /// Save the current background, like `remember_theme`
+    pub fn remember_background(&mut self) -> &'static str {
+        self.ui.background_override = None;
+        let bg_name = self.background_manager.get_background_name().to_string();
+        let scope = match self.account_key() {
+            Some(key) => {
+                self.prefs.account_appearance.entry(key).or_default().background_name = Some(bg_name);
+                " for this account"
+            }
+            None => {
+                self.prefs.background_name = bg_name;
+                ""
+            }
+        };
+        self.prefs_dirty = true;
+        self.prefs_dirty_last_update = Some(std::time::Instant::now());
+        scope
+    }
+
+    // --- Appearance Picker ---
+
+    pub fn open_appearance_picker(&mut self) {
+        if self.ui.mode == crate::state::AppMode::AppearancePicker {
+            return;
+        }
+        let theme = self.theme_manager.get_theme_name().to_string();
+        let background = self.background_manager.get_background_name().to_string();
+        let bg_index = self.background_manager.names().iter().position(|name| *name == background);
+        let theme_index = self.theme_manager.names().iter().position(|name| *name == theme);
+        self.ui.appearance_backgrounds.select(bg_index.or(Some(0)));
+        self.ui.appearance_themes.select(theme_index.or(Some(0)));
+        self.ui.appearance_themes_focused = false;
+        self.ui.appearance_original = Some((theme, background));
+        self.ui.appearance_return_mode = Some(self.ui.mode);
+        self.ui.set_mode(crate::state::AppMode::AppearancePicker);
+    }
+
+    /// Show the highlighted theme and background
+    pub fn preview_appearance(&mut self) {
+        let background = self.ui.appearance_backgrounds.selected()
+            .and_then(|i| self.background_manager.names().get(i).map(|name| name.to_string()));
+        let theme = self.ui.appearance_themes.selected()
+            .and_then(|i| self.theme_manager.names().get(i).map(|name| name.to_string()));
+        if let Some(background) = background {
+            self.background_manager.set_background_by_name(&background);
+        }
+        if let Some(theme) = theme {
+            self.theme_manager.set_theme_by_name(&theme);
         }
     }
 
+    /// Keep the previewed theme and background
+    pub fn commit_appearance(&mut self) {
+        let scope = self.remember_theme();
+        self.remember_background();
+        self.set_notification(
+            format!(
+                "Appearance set to {} / {}{}",
+                self.theme_manager.get_theme_name().to_uppercase(),
+                self.background_manager.get_background_name().to_uppercase(),
+                scope,
+            ),
+            Some(2000),
+            true,
+        );
+        self.close_appearance_picker();
+    }
+
+    /// Leave the picker, restoring the appearance from before it was opened
+    pub fn cancel_appearance_picker(&mut self) {
+        if let Some((theme, background)) = self.ui.appearance_original.clone() {
+            self.theme_manager.set_theme_by_name(&theme);
+            self.background_manager.set_background_by_name(&background);
+        }
+        self.close_appearance_picker();
+    }
+
+    fn close_appearance_picker(&mut self) {
+        self.ui.appearance_original = None;
+        let mode = self.ui.appearance_return_mode.take().unwrap_or(crate::state::AppMode::MainMenu);
+        self.ui.set_mode(mode);
+    }
+
     pub fn set_notification(&mut self, message: impl Into<String>, ms: Option<u64>, minimal: bool) {
         self.notifications.set_notification(message.into(), ms, minimal, self.ui.tick_count);
     }
 
     pub fn on_tick(&mut self) {
+        let shown_before = self.tick_visible_state();
         self.ui.tick();
         if self.notifications.should_close_notification(self.ui.tick_count) {
             self.notifications.clear_notification();
         }
-        // Debounced prefs save
+        self.update_profile_hover();
+        self.chat.step_smooth_scroll();
+        if self.profile.avatar_decoder.collect_ready() {
+            self.ui.needs_redraw = true;
+        }
+        self.update_pending_mark_read();
+        self.update_pending_fetch();
+        if self.ui.dnd_expires_tick.is_some_and(|tick| self.ui.tick_count >= tick) && self.prefs.dnd_enabled {
+            self.set_dnd(false, None);
+            self.set_notification("Do Not Disturb ended", Some(2000), true);
+        }
+        if self.ui.tick_count.is_multiple_of(self.config.power_check_interval_ticks) {
+            self.ui.on_battery = crate::power::on_battery();
+        }
+        if !self.animation_paused() {
+            self.ui.background_tick += 1;
+        }
+        self.ui.poll_reconnect();
+        if self.profile.is_profile_request_timed_out(self.ui.tick_count, self.config.profile_request_timeout_ticks) {
+            self.profile.finish_profile_request();
+            self.set_notification("Failed to load profile (request timed out)", Some(3000), false);
+            self.sound_manager.play(SoundType::Error);
+        }
+        // Debounced prefs save; the global copy (desktop notifications, banners) follows right away
         if self.prefs_dirty {
+            *crate::global_prefs::global_prefs_mut() = self.prefs.clone();
             if let Some(last) = self.prefs_dirty_last_update {
                 if last.elapsed().as_millis() > 1000 {
                     self.prefs.save();
@@ -105,20 +480,173 @@ impl<'a> App<'a> {
                 }
             }
         }
+        if self.tick_visible_state() != shown_before {
+            self.ui.needs_redraw = true;
+        }
+    }
+
+    /// Backgrounds and banner glitches hold still while the terminal is in the background,
+    /// and while idle or on battery if preferred
+    fn animation_paused(&self) -> bool {
+        !self.ui.has_focus
+            || (self.prefs.pause_background_when_idle
+                && (self.ui.on_battery || self.ui.is_idle(self.config.idle_pause_ticks)))
+    }
+
+    /// What a tick can show or hide on its own: the notification popup, the hover tooltip, DND
+    fn tick_visible_state(&self) -> (bool, bool, bool) {
+        (
+            self.notifications.current_notification.is_some(),
+            self.profile.is_hover_tooltip_ready(self.ui.tick_count, self.config.profile_tooltip_delay_ticks),
+            self.prefs.dnd_enabled,
+        )
+    }
+
+    /// Whether the current screen changes from frame to frame, so it has to be redrawn
+    /// at the frame rate rather than only when something happens
+    pub fn is_animating(&self) -> bool {
+        use crate::state::AppMode;
+        if self.chat.scroll_target.is_some() || self.history_fetch_pending() {
+            return true;
+        }
+        match self.ui.mode {
+            // Full banner
+            AppMode::Login | AppMode::Register | AppMode::ServerPicker => self.ui.has_focus,
+            AppMode::MainMenu | AppMode::Settings | AppMode::AppearancePicker => self.background_manager.is_animated() && !self.animation_paused(),
+            _ => self.prefs.minimal_banner_glitch_enabled && !self.animation_paused(),
+        }
+    }
+
+    /// Refresh the hovered user for the profile tooltip. Only a user highlighted in the
+    /// focused user list counts as hovered, and never while another popup is open.
+    fn update_profile_hover(&mut self) {
+        let popup_open = self.profile.show_profile_view_popup
+            || self.profile.show_user_actions
+            || self.ui.show_server_actions
+            || self.ui.show_server_invite_selection
+            || self.ui.show_quit_confirm;
+        let in_chat = self.ui.mode == crate::state::AppMode::Chat && !popup_open;
+        let hovered = if in_chat && self.chat.show_user_list && self.chat.chat_focus == crate::state::ChatFocus::Users {
+            self.chat.selected_user_list_index(self.prefs.offline_users)
+                .and_then(|idx| self.chat.channel_userlist.get(idx))
+                .map(|u| u.id)
+        } else if in_chat && self.chat.chat_focus == crate::state::ChatFocus::Messages {
+            // The author of the selected message
+            self.chat.selected_message
+                .and_then(|idx| self.get_current_message(idx))
+                .filter(|msg| !msg.pending)
+                .map(|msg| msg.author_id)
+        } else {
+            None
+        };
+        self.profile.update_hover(hovered, self.ui.tick_count);
+    }
+
+    /// Re-apply the user list sort/offline preferences after the list or a preference changes.
+    pub fn resort_user_list(&mut self) {
+        self.chat.sort_user_list(self.prefs.user_list_sort, self.prefs.offline_users);
     }
 
     // --- Input Management ---
     
     pub fn enter_input_mode(&mut self, mode: crate::state::InputMode) {
         self.auth.set_input_mode(mode);
+        if self.ui.mode != crate::state::AppMode::Input {
+            self.ui.input_return_mode = Some(self.ui.mode);
+        }
         self.ui.set_mode(crate::state::AppMode::Input);
         self.notifications.clear_notification();
     }
 
+    /// Actions offered for the selected server; the invite code only when it has one
+    pub fn server_actions(&self) -> Vec<crate::state::ui::ServerAction> {
+        use crate::state::ui::ServerAction;
+        let server = self.chat.selected_server.and_then(|s| self.chat.servers.get(s));
+        let mut actions = vec![ServerAction::ViewUsers, ServerAction::InviteUser];
+        if server.is_some_and(|srv| srv.invite_code.as_deref().is_some_and(|code| !code.is_empty())) {
+            actions.push(ServerAction::CopyInviteCode);
+        }
+        actions
+    }
+    
+    /// Servers `user_id` could be invited to (indices into `chat.servers`): the ones
+    /// they aren't in yet. Whether we may invite is up to the server.
+    pub fn invite_servers(&self, user_id: uuid::Uuid) -> Vec<usize> {
+        self.chat.servers.iter().enumerate()
+            .filter(|(_, server)| !server.userlist.contains(&user_id))
+            .map(|(i, _)| i)
+            .collect()
+    }
+    
+    // --- Unread Persistence ---
+    
+    /// Load the logged-in account's unread state saved by a previous session.
+    pub fn restore_unread_state(&mut self) {
+        if let Some(user) = &self.auth.current_user {
+            let mut snapshot = UnreadStore::load().accounts.remove(&user.id).unwrap_or_default();
+            self.forum.thread_last_read = std::mem::take(&mut snapshot.threads);
+            self.chat.restore_unread(snapshot);
+        }
+    }
+    
+    /// Save the logged-in account's unread state for the next session.
+    pub fn save_unread_state(&self) {
+        if let Some(user) = &self.auth.current_user {
+            let mut store = UnreadStore::load();
+            let mut snapshot = self.chat.unread_snapshot();
+            snapshot.threads = self.forum.thread_read_snapshot();
+            store.accounts.insert(user.id, snapshot);
+            store.save();
+        }
+    }
+    
     // --- Chat Methods ---
     
+    /// Switch to Chat, request its data and focus the pane chosen in preferences.
+    pub fn enter_chat(&mut self) {
+        self.ui.set_mode(crate::state::AppMode::Chat);
+        self.apply_chat_entry_focus();
+        self.send_to_server(ClientMessage::GetServers);
+        self.send_to_server(ClientMessage::GetDMUserList);
+    }
+    
+    pub fn apply_chat_entry_focus(&mut self) {
+        use crate::global_prefs::ChatEntryFocus;
+        match self.prefs.chat_entry_focus {
+            ChatEntryFocus::Sidebar => self.chat.chat_focus = ChatFocus::Sidebar,
+            ChatEntryFocus::Input => self.chat.chat_focus = ChatFocus::Messages,
+            ChatEntryFocus::LastFocused => {}
+        }
+    }
+    
     pub fn get_current_message_list(&self) -> Vec<ChatMessageWithMeta> {
-        ChatService::build_message_list(&self.chat, self.auth.current_user.as_ref())
+        ChatService::with_message_list(&self.chat, self.auth.current_user.as_ref(), |messages| messages.to_vec())
+    }
+
+    /// Who wrote a message in the current conversation, looked up by id
+    pub fn message_author(&self, author_id: uuid::Uuid) -> Option<&nexus_tui_common::User> {
+        match &self.chat.current_chat_target {
+            Some(crate::state::ChatTarget::Channel { .. }) => ChatService::find_author(&self.chat.channel_userlist, author_id),
+            Some(crate::state::ChatTarget::DM { .. }) => {
+                ChatService::find_author(self.chat.dm_user_list.iter().chain(self.auth.current_user.iter()), author_id)
+            }
+            None => None,
+        }
+    }
+
+    /// Just messages `range` of the current conversation, from the cached list
+    pub fn get_current_message_range(&self, range: std::ops::Range<usize>) -> Vec<ChatMessageWithMeta> {
+        ChatService::with_message_list(&self.chat, self.auth.current_user.as_ref(), |messages| {
+            messages[range.start.min(messages.len())..range.end.min(messages.len())].to_vec()
+        })
+    }
+
+    pub fn get_current_message(&self, idx: usize) -> Option<ChatMessageWithMeta> {
+        self.get_current_message_range(idx..idx + 1).pop()
+    }
+
+    pub fn current_message_count(&self) -> usize {
+        ChatService::message_count(&self.chat, self.auth.current_user.as_ref())
     }
 
     pub fn get_current_input(&self) -> &str {
@@ -134,22 +662,92 @@ impl<'a> App<'a> {
     }
 
     pub fn set_current_chat_target(&mut self, target: crate::state::ChatTarget) {
+        if self.prefs.mark_read_mode == crate::global_prefs::MarkReadMode::OnOpen {
+            self.chat.mark_read(&target);
+            self.chat.pending_mark_read = None;
+        } else {
+            self.chat.pending_mark_read = Some((target.clone(), self.ui.tick_count));
+        }
         self.chat.set_current_chat_target(target.clone());
         
         // Preload images for the new conversation
         self.chat_service.preload_conversation_images(&self.chat);
     }
 
+    /// Clear the open conversation's unread state once the mark-read preference is met
+    fn update_pending_mark_read(&mut self) {
+        use crate::global_prefs::MarkReadMode;
+
+        let Some((target, opened_tick)) = self.chat.pending_mark_read.clone() else { return };
+        if self.chat.current_chat_target.as_ref() != Some(&target) {
+            self.chat.pending_mark_read = None;
+            return;
+        }
+        if self.ui.mode != crate::state::AppMode::Chat {
+            return;
+        }
+        let read = match self.prefs.mark_read_mode {
+            MarkReadMode::OnOpen => true,
+            MarkReadMode::AtBottom => {
+                matches!(self.chat.chat_focus, crate::state::ChatFocus::Messages)
+                    && self.chat.final_scroll_offset() == 0
+                    && self.current_message_count() > 0
+            }
+            MarkReadMode::AfterDelay => {
+                // 20 ticks per second
+                self.ui.tick_count.saturating_sub(opened_tick) >= self.prefs.mark_read_delay_secs * 20
+            }
+        };
+        if read {
+            self.chat.mark_read(&target);
+            self.chat.pending_mark_read = None;
+        }
+    }
+
     // --- Server Message Handling ---
     
     pub fn handle_server_message(&mut self, msg: ServerMessage) {
         use chrono::prelude::*;
         match msg {
+            ServerMessage::AuthSuccess(user) if self.auth.reauthenticating => {
+                self.on_reauthenticated(user);
+            }
             ServerMessage::AuthSuccess(user) => {
+                if self.prefs.remember_username && self.prefs.last_username.as_ref() != Some(&user.username) {
+                    self.prefs.last_username = Some(user.username.clone());
+                    self.prefs_dirty = true;
+                    self.prefs_dirty_last_update = Some(std::time::Instant::now());
+                }
                 self.auth.login(user);
+                self.chat.invalidate_message_cache();
+                self.restore_unread_state();
+                self.chat.catch_up_channels = true;
+                self.chat.catch_up_dms = true;
+                self.apply_appearance();
                 self.ui.set_mode(crate::state::AppMode::MainMenu);
                 self.ui.reset_selections();
                 self.sound_manager.play(SoundType::LoginSuccess);
+                // Reopen the last conversation once the lists it lives in arrive
+                self.chat.sidebar_tab = self.prefs.last_sidebar_tab;
+                if let Some(target) = self.prefs.last_chat_target.clone() {
+                    self.chat.pending_restore = Some(target);
+                    self.send_to_server(ClientMessage::GetServers);
+                    self.send_to_server(ClientMessage::GetDMUserList);
+                }
+            }
+            ServerMessage::AuthFailure(reason) if self.auth.reauthenticating => {
+                // The session can't be resumed, so nothing queued can be sent for it
+                let dropped = self.chat.pending_sends.len();
+                self.save_unread_state();
+                self.outbox.clear();
+                self.chat.pending_sends.clear();
+                self.chat.invalidate_message_cache();
+                self.auth.logout();
+                self.apply_appearance();
+                self.ui.set_mode(crate::state::AppMode::Login);
+                let queued = if dropped > 0 { format!(" ({} queued messages not sent)", dropped) } else { String::new() };
+                self.set_notification(format!("Signed out after reconnecting: {}{}", reason, queued), None, false);
+                self.sound_manager.play(SoundType::LoginFailure);
             }
             ServerMessage::AuthFailure(reason) => {
                 self.set_notification(format!("Error: {}", reason), None, false);
@@ -157,6 +755,9 @@ impl<'a> App<'a> {
             }
             ServerMessage::Forums(forums) => {
                 self.forum.forums = forums;
+                if self.ui.mode == crate::state::AppMode::PostView {
+                    self.forum.mark_current_thread_read();
+                }
                 // Handle pending thread selection
                 if let (Some(_forum_id), Some(ref title)) = (self.forum.current_forum_id, &self.forum.pending_new_thread_title.clone()) {
                     if let Some(forum) = self.forum.get_current_forum() {
@@ -219,6 +820,9 @@ impl<'a> App<'a> {
                 }).collect();
                 
                 self.forum.forums = forums;
+                if self.ui.mode == crate::state::AppMode::PostView {
+                    self.forum.mark_current_thread_read();
+                }
                 
                 // Handle pending thread selection (same logic as regular Forums)
                 if let (Some(_forum_id), Some(ref title)) = (self.forum.current_forum_id, &self.forum.pending_new_thread_title.clone()) {
@@ -235,50 +839,70 @@ impl<'a> App<'a> {
             }
             ServerMessage::Profile(profile) => {
                 if self.profile.profile_requested_by_user {
+                    self.notifications.clear_notification();
                     self.profile.set_profile_for_viewing(profile);
                 } else {
                     self.profile.load_profile_for_editing(&profile);
                 }
-                self.profile.profile_requested_by_user = false;
+                self.profile.finish_profile_request();
             }
             ServerMessage::UserUpdated(user) => {
                 // Update user in channel userlist
                 if let Some(existing) = self.chat.channel_userlist.iter_mut().find(|u| u.id == user.id) {
                     *existing = user.clone();
                 }
+                self.resort_user_list();
                 // Update current user if it's this user
                 if let Some(current) = &mut self.auth.current_user {
                     if current.id == user.id {
                         *current = user.clone();
                     }
                 }
-                // Invalidate avatar cache
+                // Names, colors and avatars shown on their messages may have changed
+                self.chat.invalidate_message_cache();
+                self.chat_service.cache_avatar(user.id, user.profile_pic.as_deref());
                 self.profile.invalidate_avatar_cache(user.id);
             }
             ServerMessage::Servers(servers) => {
-                self.chat.servers = servers;
-                if self.ui.mode == crate::state::AppMode::Chat && self.chat.sidebar_tab == crate::state::SidebarTab::Servers {
+                let previous = std::mem::replace(&mut self.chat.servers, servers);
+                self.chat.prune_unread_channels();
+                self.reconcile_servers(previous);
+                if std::mem::take(&mut self.chat.catch_up_channels) {
+                    let channels: Vec<uuid::Uuid> = self.chat.servers.iter().flat_map(|s| s.channels.iter().map(|c| c.id)).collect();
+                    self.request_catch_up(channels);
+                }
+                if self.restore_last_conversation(crate::state::SidebarTab::Servers) {
+                    // Opened the saved channel (or the first one if it's gone)
+                } else if self.ui.mode == crate::state::AppMode::Chat && self.chat.sidebar_tab == crate::state::SidebarTab::Servers {
                     self.select_and_load_first_chat();
                 }
             }
-            ServerMessage::ChannelUserList { channel_id: _, users } => {
-                let mut sorted_users = users;
-                sorted_users.sort_by(|a, b| a.username.to_lowercase().cmp(&b.username.to_lowercase()));
-                sorted_users.reverse();
-                self.chat.channel_userlist = sorted_users;
-                
-                if !self.chat.channel_userlist.is_empty() {
-                    self.chat.user_list_state.select(Some(0));
-                } else {
-                    self.chat.user_list_state.select(None);
+            ServerMessage::ChannelUserList { channel_id, users } => {
+                // A late reply for a channel navigated past mustn't replace the open one's list
+                let is_current = matches!(&self.chat.current_chat_target,
+                    Some(crate::state::ChatTarget::Channel { channel_id: current, .. }) if *current == channel_id);
+                if !is_current {
+                    return;
                 }
+                self.chat.channel_userlist = users;
+                self.chat.invalidate_message_cache();
+                self.chat.user_list_state.select(Some(0));
+                self.resort_user_list();
                 
                 // Request missing avatars for users that don't have profile pictures
                 self.chat_service.request_missing_avatars(&self.chat, &self.to_server);
             }
             ServerMessage::DMUserList(users) => {
                 self.chat.dm_user_list = users;
-                if self.ui.mode == crate::state::AppMode::Chat && self.chat.sidebar_tab == crate::state::SidebarTab::DMs {
+                self.chat.invalidate_message_cache();
+                self.chat.prune_unread_dms();
+                if std::mem::take(&mut self.chat.catch_up_dms) {
+                    let partners: Vec<uuid::Uuid> = self.chat.dm_user_list.iter().map(|u| u.id).collect();
+                    self.request_catch_up(partners);
+                }
+                if self.restore_last_conversation(crate::state::SidebarTab::DMs) {
+                    // Opened the saved DM (or the first one if it's gone)
+                } else if self.ui.mode == crate::state::AppMode::Chat && self.chat.sidebar_tab == crate::state::SidebarTab::DMs {
                     self.select_and_load_first_chat();
                 }
                 
@@ -311,13 +935,22 @@ impl<'a> App<'a> {
                 let dm_from = dm.from;
                 let dm_to = dm.to;
                 let dm_content = dm.content.clone();
+                if current_user_id == Some(dm_from) {
+                    self.chat.remove_pending(dm_to, &dm_content);
+                }
                 
                 if is_current {
+                    let partner = if current_user_id == Some(dm_from) { dm_to } else { dm_from };
+                    self.chat.record_read(partner, dm.id);
                     self.chat.dm_messages.push(dm);
-                    self.chat.reset_scroll_offset();
+                    self.chat.invalidate_message_cache();
+                    self.chat.on_message_appended();
+                    self.refresh_message_search();
                 } else if let Some(my_id) = current_user_id {
-                    if dm_to == my_id {
-                        self.chat.unread_dm_conversations.insert(dm_from);
+                    // Only the first unread DM in a burst is announced
+                    let first_unread = dm_to == my_id
+                        && self.chat.note_unread(&crate::state::ChatTarget::DM { user_id: dm_from });
+                    if first_unread {
                         self.set_notification(
                             format!("DM from {}: {}", dm_author_username, dm_content),
                             Some(4000),
@@ -332,20 +965,15 @@ impl<'a> App<'a> {
                         );
                     }
                 }
-                
-                // Update unread count for sender if not currently viewing their DM
-                if let Some(my_id) = current_user_id {
-                    if dm_to == my_id && !is_current {
-                        self.chat.unread_dm_conversations.insert(dm_from);
-                    }
-                }
             }
             ServerMessage::MentionNotification { from, content } => {
-                self.set_notification(
-                    format!("Mentioned by {}: {}", from.username, content),
-                    Some(4000),
-                    true,
-                );
+                // A group mention (@everyone, @admins, ...) says who it went to
+                let role = self.auth.current_user.as_ref().map(|u| u.role).unwrap_or(nexus_tui_common::UserRole::User);
+                let text = match ChatService::group_mention_for(&content, role) {
+                    Some(group) => format!("@{} from {}: {}", group, from.username, content),
+                    None => format!("Mentioned by {}: {}", from.username, content),
+                };
+                self.set_notification(text, Some(4000), true);
                 
                 // Show desktop notification for mentions with profile picture
                 DesktopNotificationService::show_mention_notification(&from.username, &content, from.profile_pic.as_deref());
@@ -377,6 +1005,24 @@ impl<'a> App<'a> {
                 }
             }
             ServerMessage::Notification(text, is_error) => {
+                if std::mem::take(&mut self.profile.profile_save_pending)
+                    && is_error
+                    && self.offer_image_downscale(&text)
+                {
+                    return;
+                }
+                // The server rejected the profile request; don't leave it hanging. Errors
+                // about anything else while it's pending go through the normal path.
+                let about_profile = {
+                    let lower = text.to_lowercase();
+                    lower.contains("profile") || lower.contains("user not found")
+                };
+                if is_error && about_profile && self.profile.pending_profile_request.is_some() {
+                    self.profile.finish_profile_request();
+                    self.set_notification(format!("Failed to load profile: {}", text), Some(3000), false);
+                    self.sound_manager.play(SoundType::Error);
+                    return;
+                }
                 let prefix = if is_error { "Error: " } else { "Info: " };
                 self.set_notification(format!("{}{}", prefix, text), Some(2000), false);
                 
@@ -399,8 +1045,10 @@ impl<'a> App<'a> {
                 }
             }
             ServerMessage::Notifications { notifications, history_complete } => {
-                self.notifications.notifications = notifications.clone();
-                self.notifications.notification_history_complete = history_complete;
+                self.notifications.receive_page(notifications, history_complete);
+                if self.ui.mode == crate::state::AppMode::NotificationCenter {
+                    self.mark_selected_notification_read();
+                }
                 
                 // Only show desktop notifications for truly new notifications
                 // (not when loading notification history)
@@ -424,7 +1072,9 @@ impl<'a> App<'a> {
                     existing.status = user.status.clone();
                 } else {
                     self.chat.channel_userlist.push(user.clone());
+                    self.chat.invalidate_message_cache();
                 }
+                self.resort_user_list();
                 
                 // Also update in DM user list if present
                 if let Some(existing_dm) = self.chat.dm_user_list.iter_mut().find(|u| u.id == user.id) {
@@ -436,6 +1086,7 @@ impl<'a> App<'a> {
                 if let Some(existing) = self.chat.channel_userlist.iter_mut().find(|u| u.id == user_id) {
                     existing.status = nexus_tui_common::UserStatus::Offline;
                 }
+                self.resort_user_list();
                 
                 // Also update in DM user list if present
                 if let Some(existing_dm) = self.chat.dm_user_list.iter_mut().find(|u| u.id == user_id) {
@@ -443,23 +1094,48 @@ impl<'a> App<'a> {
                 }
             }
             ServerMessage::NewChannelMessage(msg) => {
+                if self.auth.current_user.as_ref().is_some_and(|u| u.id == msg.sent_by) {
+                    self.chat.remove_pending(msg.channel_id, &msg.content);
+                }
                 let current_target = &self.chat.current_chat_target;
                 let is_current_channel = if let Some(crate::state::ChatTarget::Channel { channel_id, .. }) = current_target {
                     *channel_id == msg.channel_id
                 } else { false };
                 
                 if is_current_channel {
+                    self.chat.record_read(msg.channel_id, msg.id);
                     self.chat.chat_messages.push(msg);
-                    self.chat.reset_scroll_offset();
+                    self.chat.invalidate_message_cache();
+                    self.chat.on_message_appended();
+                    self.refresh_message_search();
                     self.sound_manager.play(SoundType::ReceiveChannelMessage);
                 } else {
-                    self.chat.unread_channels.insert(msg.channel_id);
+                    let server_id = self.chat.servers.iter()
+                        .find(|s| s.channels.iter().any(|c| c.id == msg.channel_id))
+                        .map(|s| s.id)
+                        .unwrap_or_default();
+                    let target = crate::state::ChatTarget::Channel { server_id, channel_id: msg.channel_id };
+                    // Only the first unread message in a burst makes a sound
+                    if self.chat.note_unread(&target) {
+                        self.sound_manager.play(SoundType::ReceiveChannelMessage);
+                    }
                 }
             }
             ServerMessage::ChannelMessages { channel_id, messages, history_complete } => {
+                let caught_up = self.chat.awaiting_catch_up.remove(&channel_id);
+                let is_current = matches!(&self.chat.current_chat_target,
+                    Some(crate::state::ChatTarget::Channel { channel_id: current, .. }) if *current == channel_id);
+                if caught_up && !is_current {
+                    let ids: Vec<uuid::Uuid> = messages.iter().map(|m| m.id).collect();
+                    self.chat.catch_up_unread(channel_id, false, &ids);
+                }
                 if let Some(crate::state::ChatTarget::Channel { channel_id: current_channel_id, .. }) = &self.chat.current_chat_target {
                     if *current_channel_id == channel_id {
                         if self.chat.chat_messages.is_empty() {
+                            // Fresh load of the conversation being viewed: it's read up to here
+                            if let Some(newest) = messages.last() {
+                                self.chat.record_read(channel_id, newest.id);
+                            }
                             self.chat.chat_messages = messages;
                         } else {
                             // Prepend new messages for history loading
@@ -467,15 +1143,29 @@ impl<'a> App<'a> {
                             all_messages.extend(self.chat.chat_messages.drain(..));
                             self.chat.chat_messages = all_messages;
                         }
+                        self.chat.invalidate_message_cache();
+                        self.chat.resync_message_selection();
+                        self.refresh_message_search();
                         
                         self.chat.channel_history_complete.insert(channel_id, history_complete);
+                        self.chat.history_fetch_started = None;
                     }
                 }
             }
             ServerMessage::DirectMessages { user_id, messages, history_complete } => {
+                let caught_up = self.chat.awaiting_catch_up.remove(&user_id);
+                let is_current = matches!(&self.chat.current_chat_target,
+                    Some(crate::state::ChatTarget::DM { user_id: current }) if *current == user_id);
+                if caught_up && !is_current {
+                    let ids: Vec<uuid::Uuid> = messages.iter().map(|m| m.id).collect();
+                    self.chat.catch_up_unread(user_id, true, &ids);
+                }
                 if let Some(crate::state::ChatTarget::DM { user_id: current_user_id }) = &self.chat.current_chat_target {
                     if *current_user_id == user_id {
                         if self.chat.dm_messages.is_empty() {
+                            if let Some(newest) = messages.last() {
+                                self.chat.record_read(user_id, newest.id);
+                            }
                             self.chat.dm_messages = messages;
                         } else {
                             // Prepend new messages for history loading
@@ -483,8 +1173,12 @@ impl<'a> App<'a> {
                             all_messages.extend(self.chat.dm_messages.drain(..));
                             self.chat.dm_messages = all_messages;
                         }
+                        self.chat.invalidate_message_cache();
+                        self.chat.resync_message_selection();
+                        self.refresh_message_search();
                         
                         self.chat.dm_history_complete = history_complete;
+                        self.chat.history_fetch_started = None;
                     }
                 }
             }
@@ -506,8 +1200,12 @@ impl<'a> App<'a> {
                             all_messages.extend(self.chat.chat_messages.drain(..));
                             self.chat.chat_messages = all_messages;
                         }
+                        self.chat.invalidate_message_cache();
+                        self.chat.resync_message_selection();
+                        self.refresh_message_search();
                         
                         self.chat.channel_history_complete.insert(channel_id, !has_more);
+                        self.chat.history_fetch_started = None;
                         
                         // Preload avatars for new messages
                         self.chat_service.preload_conversation_images(&self.chat);
@@ -532,8 +1230,12 @@ impl<'a> App<'a> {
                             all_messages.extend(self.chat.dm_messages.drain(..));
                             self.chat.dm_messages = all_messages;
                         }
+                        self.chat.invalidate_message_cache();
+                        self.chat.resync_message_selection();
+                        self.refresh_message_search();
                         
                         self.chat.dm_history_complete = !has_more;
+                        self.chat.history_fetch_started = None;
                         
                         // Preload avatars for new messages
                         self.chat_service.preload_conversation_images(&self.chat);
@@ -541,9 +1243,9 @@ impl<'a> App<'a> {
                 }
             }
             ServerMessage::CacheStats { total_entries, total_size_mb, hit_ratio, expired_entries } => {
-                // Handle cache statistics - could display in debug UI
                 tracing::debug!("Cache stats: {} entries, {:.1}MB, {:.1}% hit ratio, {} expired", 
                     total_entries, total_size_mb, hit_ratio * 100.0, expired_entries);
+                self.ui.server_metrics.cache = Some((total_entries, total_size_mb, hit_ratio, expired_entries));
             }
             ServerMessage::ImageCacheInvalidated { keys } => {
                 // Remove invalidated images from cache
@@ -557,6 +1259,9 @@ impl<'a> App<'a> {
                 // Log performance metrics for monitoring
                 tracing::debug!("Query performance: {}ms, cache hit rate: {:.1}%, {} messages", 
                     query_time_ms, cache_hit_rate * 100.0, message_count);
+                self.ui.server_metrics.query_time_ms = Some(query_time_ms);
+                self.ui.server_metrics.query_cache_hit_rate = Some(cache_hit_rate);
+                self.ui.server_metrics.message_count = Some(message_count);
                 
                 // Could trigger UI indicators for slow queries
                 if query_time_ms > 1000 {
@@ -574,9 +1279,11 @@ impl<'a> App<'a> {
                     if let Some(user) = self.chat.dm_user_list.iter_mut().find(|u| u.id == user_id) {
                         user.profile_pic = profile_pic.clone();
                     }
-                    // Invalidate any cached avatar protocols to force reload
+                    // Keep it for next time, and drop decoded protocols to force a reload
+                    self.chat_service.cache_avatar(user_id, profile_pic.as_deref());
                     self.profile.invalidate_avatar_cache(user_id);
                 }
+                self.chat.invalidate_message_cache();
             }
             
             // Handle any unmatched server messages
@@ -592,6 +1299,108 @@ impl<'a> App<'a> {
         // This would contain all the existing match arms from the original handle_server_message
     }
 
+    // --- Notification Center ---
+
+    pub fn open_notification_center(&mut self) {
+        if self.ui.mode != crate::state::AppMode::NotificationCenter {
+            self.notifications.return_mode = Some(self.ui.mode);
+            self.ui.set_mode(crate::state::AppMode::NotificationCenter);
+        }
+        if self.notifications.list_state.selected().is_none() && !self.notifications.notifications.is_empty() {
+            self.notifications.list_state.select(Some(0));
+        }
+        self.notifications.fetching_older = false;
+        self.send_to_server(ClientMessage::GetNotifications { before: None });
+        // Thread notifications open into the forums, so have them at hand
+        if self.forum.forums.is_empty() {
+            self.send_to_server(ClientMessage::GetForums);
+        }
+        self.mark_selected_notification_read();
+    }
+
+    pub fn close_notification_center(&mut self) {
+        let mode = self.notifications.return_mode.take().unwrap_or(crate::state::AppMode::MainMenu);
+        self.ui.set_mode(mode);
+    }
+
+    /// Fetch the page before the oldest notification we have
+    pub fn request_older_notifications(&mut self) {
+        if self.notifications.notification_history_complete || self.notifications.fetching_older {
+            return;
+        }
+        if let Some(oldest) = self.notifications.notifications.last() {
+            let before = Some(oldest.created_at);
+            self.notifications.fetching_older = true;
+            self.send_to_server(ClientMessage::GetNotifications { before });
+        }
+    }
+
+    /// Viewing a notification marks it read
+    pub fn mark_selected_notification_read(&mut self) {
+        let Some(idx) = self.notifications.list_state.selected() else { return };
+        let Some(notification) = self.notifications.notifications.get_mut(idx) else { return };
+        if !notification.read {
+            notification.read = true;
+            let notification_id = notification.id;
+            self.send_to_server(ClientMessage::MarkNotificationRead { notification_id });
+        }
+    }
+
+    pub fn mark_all_notifications_read(&mut self) {
+        let unread: Vec<uuid::Uuid> = self.notifications.notifications.iter_mut()
+            .filter(|n| !n.read)
+            .map(|n| {
+                n.read = true;
+                n.id
+            })
+            .collect();
+        for notification_id in unread {
+            self.send_to_server(ClientMessage::MarkNotificationRead { notification_id });
+        }
+    }
+
+    /// Jump to the thread, DM or channel the selected notification is about
+    pub fn open_selected_notification(&mut self) {
+        use crate::state::{AppMode, SidebarTab};
+        let Some(related_id) = self.notifications.selected_notification().map(|n| n.related_id) else { return };
+
+        if let Some(forum_id) = self.forum.forums.iter()
+            .find(|forum| forum.threads.iter().any(|t| t.id == related_id))
+            .map(|forum| forum.id)
+        {
+            self.forum.select_forum(forum_id);
+            self.forum.select_thread(related_id);
+            self.ui.set_mode(AppMode::PostView);
+        } else if let Some(idx) = self.chat.dm_user_list.iter().position(|u| u.id == related_id) {
+            self.chat.sidebar_tab = SidebarTab::DMs;
+            self.chat.selected_dm_user = Some(idx);
+            self.chat.dm_messages.clear();
+            self.chat.invalidate_message_cache();
+            self.ui.set_mode(AppMode::Chat);
+            self.apply_chat_entry_focus();
+            self.select_and_load_first_chat();
+        } else if let Some((si, ci)) = self.chat.servers.iter().enumerate()
+            .find_map(|(si, server)| server.channels.iter().position(|c| c.id == related_id).map(|ci| (si, ci)))
+        {
+            self.chat.sidebar_tab = SidebarTab::Servers;
+            self.chat.selected_server = Some(si);
+            self.chat.selected_channel = Some(ci);
+            self.chat.chat_messages.clear();
+            self.chat.invalidate_message_cache();
+            self.ui.set_mode(AppMode::Chat);
+            self.apply_chat_entry_focus();
+            self.select_and_load_first_chat();
+        } else {
+            self.set_notification("Nothing to open for this notification", Some(1500), true);
+            return;
+        }
+        self.notifications.return_mode = None;
+        if self.ui.mode == AppMode::Chat {
+            self.remember_chat_target();
+        }
+        self.sound_manager.play(SoundType::ChangeChannel);
+    }
+
     // --- Cache Management ---
     
     /// Get image cache statistics for debugging
@@ -604,9 +1413,10 @@ impl<'a> App<'a> {
         self.chat_service.cleanup_cache().unwrap_or(0)
     }
     
-    /// Clear all cached images
+    /// Clear all cached images, in memory and on disk
     pub fn clear_cache(&mut self) -> Result<(), String> {
-        self.image_cache.clear()
+        self.image_cache.clear()?;
+        self.image_cache.clear_disk()
     }
 
     // --- Chat Navigation ---
@@ -644,10 +1454,8 @@ impl<'a> App<'a> {
                             server_id: server.id, 
                             channel_id: channel.id 
                         };
-                        let channel_id = channel.id;
-                        self.set_current_chat_target(target);
-                        self.send_to_server(ClientMessage::GetChannelUserList { channel_id });
-                        self.send_to_server(ClientMessage::GetChannelMessages { channel_id, before: None });
+                        self.set_current_chat_target(target.clone());
+                        self.queue_chat_fetch(target);
                         self.chat.reset_scroll_offset();
                     }
                     server_id
@@ -670,9 +1478,8 @@ impl<'a> App<'a> {
                     let _user_id = if let Some(user) = self.chat.dm_user_list.get(idx) {
                         let target = crate::state::ChatTarget::DM { user_id: user.id };
                         let user_id = user.id;
-                        self.set_current_chat_target(target);
-                        self.send_to_server(ClientMessage::GetDirectMessages { user_id, before: None });
-                        self.chat.unread_dm_conversations.remove(&user_id);
+                        self.set_current_chat_target(target.clone());
+                        self.queue_chat_fetch(target);
                         self.chat.reset_scroll_offset();
                         user_id
                     } else {
@@ -683,10 +1490,132 @@ impl<'a> App<'a> {
         }
     }
 
+    /// Load `target` once the sidebar selection has stayed on it for
+    /// `chat_fetch_debounce_ticks`. A newer selection replaces it, so conversations
+    /// skipped past with the arrow keys are never requested.
+    pub fn queue_chat_fetch(&mut self, target: crate::state::ChatTarget) {
+        self.chat.pending_fetch = Some((target, self.ui.tick_count));
+    }
+
+    /// Send the queued conversation fetch now, if it's still the open conversation
+    pub fn flush_chat_fetch(&mut self) {
+        let Some((target, _)) = self.chat.pending_fetch.take() else { return };
+        if self.chat.current_chat_target.as_ref() != Some(&target) {
+            return;
+        }
+        match target {
+            crate::state::ChatTarget::Channel { channel_id, .. } => {
+                self.send_to_server(ClientMessage::GetChannelMessages { channel_id, before: None });
+                self.send_to_server(ClientMessage::GetChannelUserList { channel_id });
+            }
+            crate::state::ChatTarget::DM { user_id } => {
+                self.send_to_server(ClientMessage::GetDirectMessages { user_id, before: None });
+            }
+        }
+    }
+
+    fn update_pending_fetch(&mut self) {
+        let settled = self.chat.pending_fetch.as_ref()
+            .is_some_and(|(_, tick)| self.ui.tick_count.saturating_sub(*tick) >= self.config.chat_fetch_debounce_ticks);
+        if settled {
+            self.flush_chat_fetch();
+        }
+    }
+
+    /// Save the open conversation so the next session can reopen it
+    pub fn remember_chat_target(&mut self) {
+        self.prefs.last_chat_target = self.chat.current_chat_target.clone();
+        self.prefs.last_sidebar_tab = self.chat.sidebar_tab;
+        self.prefs_dirty = true;
+        self.prefs_dirty_last_update = Some(std::time::Instant::now());
+    }
+
+    /// Keep the sidebar selection on the same server after a new server list, and
+    /// announce servers joined or left since the last one. A server joined through
+    /// an accepted invite is opened.
+    fn reconcile_servers(&mut self, previous: Vec<nexus_tui_common::Server>) {
+        let selected_id = self.chat.selected_server.and_then(|i| previous.get(i)).map(|s| s.id);
+        let selected = selected_id.and_then(|id| self.chat.servers.iter().position(|s| s.id == id));
+        if selected != self.chat.selected_server {
+            if selected.is_none() {
+                self.chat.selected_channel = None;
+            }
+            self.chat.selected_server = selected;
+        }
+        // The first list after login isn't news
+        if previous.is_empty() && !self.chat.awaiting_invite_join {
+            return;
+        }
+        let joined: Vec<usize> = self.chat.servers.iter().enumerate()
+            .filter(|(_, s)| !previous.iter().any(|p| p.id == s.id))
+            .map(|(i, _)| i)
+            .collect();
+        let left: Vec<&str> = previous.iter()
+            .filter(|p| !self.chat.servers.iter().any(|s| s.id == p.id))
+            .map(|p| p.name.as_str())
+            .collect();
+        if !left.is_empty() {
+            self.set_notification(format!("No longer a member of {}", left.join(", ")), Some(3000), false);
+            self.sound_manager.play(SoundType::PopupClose);
+        }
+        if let Some(&first) = joined.first() {
+            let names: Vec<&str> = joined.iter().map(|&i| self.chat.servers[i].name.as_str()).collect();
+            self.set_notification(format!("Joined {}", names.join(", ")), Some(3000), false);
+            self.sound_manager.play(SoundType::Notify);
+            if self.chat.awaiting_invite_join {
+                self.chat.awaiting_invite_join = false;
+                self.chat.selected_server = Some(first);
+                self.chat.selected_channel = None;
+                if self.ui.mode == crate::state::AppMode::Chat {
+                    self.chat.sidebar_tab = crate::state::SidebarTab::Servers;
+                }
+            }
+        }
+    }
+    
+    /// Open the conversation saved from the last session, if it lives in the list that
+    /// just arrived for `tab`. Falls back to the first conversation when it no longer
+    /// exists. Returns false when nothing was waiting on this list.
+    fn restore_last_conversation(&mut self, tab: crate::state::SidebarTab) -> bool {
+        use crate::state::{ChatTarget, SidebarTab};
+        let target = match &self.chat.pending_restore {
+            Some(target @ ChatTarget::Channel { .. }) if tab == SidebarTab::Servers => target.clone(),
+            Some(target @ ChatTarget::DM { .. }) if tab == SidebarTab::DMs => target.clone(),
+            _ => return false,
+        };
+        self.chat.pending_restore = None;
+        // The user already went somewhere else while the lists were loading
+        if self.ui.mode != crate::state::AppMode::MainMenu {
+            return false;
+        }
+        match target {
+            ChatTarget::Channel { server_id, channel_id } => {
+                let found = self.chat.servers.iter().enumerate()
+                    .find(|(_, server)| server.id == server_id)
+                    .and_then(|(si, server)| server.channels.iter().position(|c| c.id == channel_id).map(|ci| (si, ci)));
+                self.chat.selected_server = found.map(|(si, _)| si);
+                self.chat.selected_channel = found.map(|(_, ci)| ci);
+            }
+            ChatTarget::DM { user_id } => {
+                self.chat.selected_dm_user = self.chat.dm_user_list.iter().position(|u| u.id == user_id);
+            }
+        }
+        self.chat.sidebar_tab = tab;
+        self.ui.set_mode(crate::state::AppMode::Chat);
+        self.apply_chat_entry_focus();
+        self.select_and_load_first_chat();
+        true
+    }
+
     // --- Message Sending ---
     
     pub fn send_message(&mut self) -> AppResult<()> {
-        let content = self.get_current_input().to_string();
+        let mut content = self.get_current_input().to_string();
+        if let Some(quoted) = self.reply_target_message() {
+            if !content.trim().is_empty() {
+                content = MessageService::quote_reply(&quoted.author, &quoted.content, &content);
+            }
+        }
         let validated_content = MessageService::validate_message(&content)
             .map_err(|e| AppError::Validation(e))?;
         
@@ -705,6 +1634,9 @@ impl<'a> App<'a> {
                         match command.as_str() {
                             "accept" => {
                                 self.send_to_server(ClientMessage::AcceptServerInviteFromUser { from_user_id: *user_id });
+                                // Fetch the server list so the new server appears without a refresh
+                                self.send_to_server(ClientMessage::GetServers);
+                                self.chat.awaiting_invite_join = true;
                                 self.set_notification("Server invite accepted!", Some(2000), false);
                                 self.sound_manager.play(SoundType::Select);
                                 self.clear_current_input();
@@ -729,14 +1661,108 @@ impl<'a> App<'a> {
                 }
             }
             self.clear_current_input();
+            self.chat.reply_target = None;
+            // Sending jumps back to the latest messages so the echo is visible
+            self.chat.reset_scroll_offset();
         }
         
         Ok(())
     }
+    
+    /// Recompute search matches for the current query and show the newest one.
+    pub fn update_message_search(&mut self) {
+        let messages = self.get_current_message_list();
+        self.chat.search_matches = ChatService::search_messages(&messages, &self.chat.search_query);
+        self.chat.search_index = self.chat.search_matches.len().saturating_sub(1);
+        self.show_search_match();
+    }
+    
+    /// Recompute matches after the message list changed, keeping the shown match.
+    fn refresh_message_search(&mut self) {
+        if self.chat.search_query.is_empty() {
+            return;
+        }
+        let messages = self.get_current_message_list();
+        self.chat.search_matches = ChatService::search_messages(&messages, &self.chat.search_query);
+        if let Some(pos) = self.chat.selected_message.and_then(|sel| self.chat.search_matches.iter().position(|&m| m == sel)) {
+            self.chat.search_index = pos;
+        }
+    }
+    
+    /// Move to the next (`1`) or previous (`-1`) search match, wrapping around.
+    pub fn cycle_search_match(&mut self, delta: isize) {
+        let count = self.chat.search_matches.len();
+        if count == 0 {
+            return;
+        }
+        self.chat.search_index = (self.chat.search_index as isize + delta).rem_euclid(count as isize) as usize;
+        self.show_search_match();
+    }
+    
+    /// Select the current match so it's highlighted and scrolled into view.
+    fn show_search_match(&mut self) {
+        let ids = self.chat.current_message_ids();
+        match self.chat.search_matches.get(self.chat.search_index) {
+            Some(&idx) => {
+                self.chat.selected_message = Some(idx);
+                self.chat.selected_message_id = ids.get(idx).copied();
+                self.chat.ensure_selected_message_visible();
+            }
+            None => self.chat.clear_message_selection(),
+        }
+    }
+    
+    /// Whether an older-history request is still waiting for its page
+    pub fn history_fetch_pending(&self) -> bool {
+        self.chat.history_fetch_started
+            .is_some_and(|tick| self.ui.tick_count.saturating_sub(tick) < self.config.history_fetch_timeout_ticks)
+    }
+
+    /// Ask for the page of messages before the oldest one loaded, unless that page is
+    /// already on its way or there is nothing older
+    pub fn request_older_history(&mut self) {
+        if self.history_fetch_pending() || self.chat.history_complete() {
+            return;
+        }
+        let request = match &self.chat.current_chat_target {
+            Some(crate::state::ChatTarget::Channel { channel_id, .. }) => self.chat.chat_messages.first()
+                .map(|oldest| ClientMessage::GetChannelMessages { channel_id: *channel_id, before: Some(oldest.timestamp) }),
+            Some(crate::state::ChatTarget::DM { user_id }) => self.chat.dm_messages.first()
+                .map(|oldest| ClientMessage::GetDirectMessages { user_id: *user_id, before: Some(oldest.timestamp) }),
+            None => None,
+        };
+        if let Some(request) = request {
+            self.send_to_server(request);
+            self.chat.history_fetch_started = Some(self.ui.tick_count);
+        }
+    }
+
+    /// The message being replied to, if it's still in the current conversation
+    pub fn reply_target_message(&self) -> Option<ChatMessageWithMeta> {
+        let id = self.chat.reply_target?;
+        // Drawn every frame while replying, so only the one message is copied
+        match self.chat.current_message_ids().iter().position(|&m| m == id) {
+            Some(idx) => self.get_current_message(idx),
+            None => self.get_current_message_list().into_iter().find(|m| m.id == id),
+        }
+    }
 
     // --- Profile Management ---
     
+    /// Request another user's profile for the view popup, tracking it so a
+    /// missing or failed response surfaces an error instead of nothing.
+    pub fn request_profile_view(&mut self, user_id: uuid::Uuid) {
+        self.profile.begin_profile_request(user_id, self.ui.tick_count);
+        self.send_to_server(ClientMessage::GetProfile { user_id });
+        self.set_notification("Loading profile...", None, true);
+    }
+    
     pub fn save_profile(&mut self) -> AppResult<()> {
+        // Auto-prefix missing schemes so "example.com" is saved as a working link
+        self.profile.edit_url1 = ProfileService::normalize_url(&self.profile.edit_url1);
+        self.profile.edit_url2 = ProfileService::normalize_url(&self.profile.edit_url2);
+        self.profile.edit_url3 = ProfileService::normalize_url(&self.profile.edit_url3);
+
         // Validate profile data
         ProfileService::validate_profile_data(
             &self.profile.edit_bio,
@@ -779,6 +1805,7 @@ impl<'a> App<'a> {
             profile_pic,
             cover_banner,
         });
+        self.profile.profile_save_pending = true;
         
         // Don't play success sound or change mode here - wait for server response
         // The server will send a success notification when the profile is actually saved
@@ -786,47 +1813,86 @@ impl<'a> App<'a> {
         Ok(())
     }
 
+    /// If the server turned the profile down because an image is too large, open the
+    /// downscale dialog for that image. The edited fields are left as they are.
+    fn offer_image_downscale(&mut self, reason: &str) -> bool {
+        use crate::state::{DownscalePrompt, ProfileImageField};
+
+        if self.ui.mode != crate::state::AppMode::EditProfile || !ImageService::is_image_size_rejection(reason) {
+            return false;
+        }
+        // When the reason doesn't say which image, blame the bigger one
+        let field = ImageService::rejected_image_field(reason).unwrap_or_else(|| {
+            let pic = ImageService::approx_image_bytes(&self.profile.edit_profile_pic);
+            let banner = ImageService::approx_image_bytes(&self.profile.edit_cover_banner);
+            if banner > pic { ProfileImageField::CoverBanner } else { ProfileImageField::ProfilePic }
+        });
+        if self.profile.edit_image(field).trim().is_empty() {
+            return false;
+        }
+        let max_dimension = ImageService::rejected_max_dimension(reason)
+            .unwrap_or_else(|| field.default_max_dimension());
+        self.profile.downscale_prompt = Some(DownscalePrompt {
+            field,
+            max_dimension: max_dimension.to_string(),
+            reason: reason.to_string(),
+        });
+        self.profile.profile_edit_error = Some(format!("Server: {}", reason));
+        self.sound_manager.play(SoundType::Error);
+        true
+    }
+
+    /// Apply the downscale dialog: shrink the image in place and put focus on Save
+    pub fn apply_image_downscale(&mut self) {
+        let Some(prompt) = self.profile.downscale_prompt.clone() else { return };
+        let max_dimension = match prompt.max_dimension.parse::<u32>() {
+            Ok(n) if n >= 16 => n,
+            _ => {
+                self.set_notification("Enter a size of at least 16 pixels", Some(2000), true);
+                return;
+            }
+        };
+        match ImageService::downscale_to_data_url(self.profile.edit_image(prompt.field), max_dimension) {
+            Ok((data_url, (w, h))) => {
+                *self.profile.edit_image_mut(prompt.field) = data_url;
+                self.profile.downscale_prompt = None;
+                self.profile.profile_edit_error = None;
+                self.profile.profile_edit_focus = crate::state::ProfileEditFocus::Save;
+                self.set_notification(format!("Downscaled {} to {}x{}. Press Save to retry.", prompt.field.label(), w, h), Some(3000), false);
+            }
+            Err(e) => {
+                self.profile.downscale_prompt = None;
+                self.profile.profile_edit_error = Some(e.to_string());
+                self.sound_manager.play(SoundType::Error);
+            }
+        }
+    }
+
     // --- Mention System ---
     
     pub fn update_mention_suggestions(&mut self) {
         let input = self.get_current_input().to_string(); // Clone the input to avoid borrow issues
-        let suggestions = ChatService::get_mention_suggestions(&input, &self.chat.channel_userlist);
+        let cursor = self.chat.cursor_byte_index();
+        let role = self.auth.current_user.as_ref().map(|u| u.role);
+        let suggestions = ChatService::get_mention_suggestions(&input, cursor, &self.chat.channel_userlist, role);
         
         if !suggestions.is_empty() {
-            // Convert usernames back to indices for UI compatibility
-            let mut indices = Vec::new();
-            for suggestion in &suggestions {
-                if let Some((idx, _)) = self.chat.channel_userlist.iter().enumerate()
-                    .find(|(_, u)| &u.username == suggestion) {
-                    indices.push(idx);
-                }
-            }
-            self.chat.mention_suggestions = indices;
+            self.chat.mention_suggestions = suggestions;
             self.chat.mention_selected = 0;
-            
-            // Extract prefix from input
-            if let Some(idx) = input.rfind('@') {
-                let after_at = &input[(idx + 1)..];
-                if after_at.chars().all(|ch| ch.is_alphanumeric() || ch == '_') && !after_at.is_empty() {
-                    self.chat.mention_prefix = Some(after_at.to_string());
-                }
-            }
+            self.chat.mention_prefix = ChatService::mention_token_before(&input, cursor).map(|(_, prefix)| prefix.to_string());
         } else {
             self.chat.clear_mention_suggestions();
         }
     }
 
     pub fn apply_selected_mention(&mut self) {
-        if let (Some(prefix), Some(&user_idx)) = (
-            &self.chat.mention_prefix.clone(),
-            self.chat.mention_suggestions.get(self.chat.mention_selected)
-        ) {
-            if let Some(user) = self.chat.channel_userlist.get(user_idx) {
-                let input = self.get_current_input().to_string();
-                let new_input = ChatService::apply_mention_suggestion(&input, &user.username, prefix);
-                self.set_current_input(new_input);
-                self.chat.clear_mention_suggestions();
-            }
+        if let Some(name) = self.chat.mention_suggestions.get(self.chat.mention_selected).cloned() {
+            let input = self.get_current_input().to_string();
+            let (new_input, cursor) = ChatService::apply_mention_suggestion(&input, self.chat.cursor_byte_index(), &name);
+            let cursor = new_input[..cursor].chars().count();
+            self.chat.set_current_input(new_input);
+            self.chat.cursor_pos = cursor;
+            self.chat.clear_mention_suggestions();
         }
     }
 
@@ -839,13 +1905,14 @@ impl<'a> App<'a> {
         if let Some((emoji, start_pos, end_pos)) = ChatService::check_for_exact_emoji_match(&input) {
             let mut new_input = input.clone();
             new_input.replace_range(start_pos..end_pos, &emoji);
-            self.set_current_input(new_input);
+            self.chat.replace_current_input(new_input);
             self.chat.clear_emoji_suggestions();
+            self.record_emoji_use(&emoji);
             return;
         }
         
         // If no exact match, show suggestions
-        let suggestions = ChatService::get_emoji_suggestions(&input);
+        let suggestions = ChatService::get_emoji_suggestions(&input, &self.prefs.favorite_emoji, &self.prefs.recent_emoji);
         
         if !suggestions.is_empty() {
             self.chat.emoji_suggestions = suggestions;
@@ -870,10 +1937,37 @@ impl<'a> App<'a> {
         ) {
             let input = self.get_current_input().to_string();
             let new_input = ChatService::apply_emoji_suggestion(&input, emoji, prefix);
-            self.set_current_input(new_input);
+            let emoji = emoji.clone();
+            self.chat.replace_current_input(new_input);
             self.chat.clear_emoji_suggestions();
+            self.record_emoji_use(&emoji);
         }
     }
+    
+    /// Move `emoji` to the front of the recently used list
+    pub fn record_emoji_use(&mut self, emoji: &str) {
+        let recent = &mut self.prefs.recent_emoji;
+        recent.retain(|e| e != emoji);
+        recent.insert(0, emoji.to_string());
+        recent.truncate(crate::global_prefs::GlobalPrefs::RECENT_EMOJI_LIMIT);
+        self.prefs_dirty = true;
+        self.prefs_dirty_last_update = Some(std::time::Instant::now());
+    }
+    
+    /// Pin `emoji` as a favorite, or unpin it; returns whether it's now a favorite
+    pub fn toggle_favorite_emoji(&mut self, emoji: &str) -> bool {
+        let favorites = &mut self.prefs.favorite_emoji;
+        let pinned = if favorites.iter().any(|e| e == emoji) {
+            favorites.retain(|e| e != emoji);
+            false
+        } else {
+            favorites.push(emoji.to_string());
+            true
+        };
+        self.prefs_dirty = true;
+        self.prefs_dirty_last_update = Some(std::time::Instant::now());
+        pinned
+    }
 
     pub fn update_profile_banner_composite(&mut self, banner_area_width_cells: u16, banner_area_height_cells: u16) {
         // Create composite banner + profile pic image for profile view popup
@@ -1022,14 +2116,24 @@ impl<'a> App<'a> {
     pub fn get_current_chat_title(&self) -> String {
         match &self.chat.current_chat_target {
             Some(crate::state::ChatTarget::Channel { .. }) => {
-                let channel_name = self.chat.selected_server
+                let channel = self.chat.selected_server
                     .and_then(|server_idx| self.chat.servers.get(server_idx))
                     .and_then(|server| self.chat.selected_channel
-                        .and_then(|channel_idx| server.channels.get(channel_idx))
-                        .map(|channel| channel.name.as_str()))
-                    .unwrap_or("unknown");
+                        .and_then(|channel_idx| server.channels.get(channel_idx)));
+                let channel_name = channel.map(|channel| channel.name.as_str()).unwrap_or("unknown");
+                
+                // Presence comes from the live user list; membership from the channel itself
+                // when the server tracks it, otherwise from the user list.
+                let online = self.chat.channel_userlist.iter()
+                    .filter(|u| u.status != nexus_tui_common::UserStatus::Offline)
+                    .count();
+                let members = channel
+                    .map(|channel| channel.userlist.len())
+                    .filter(|&n| n > 0)
+                    .unwrap_or(self.chat.channel_userlist.len())
+                    .max(online);
                 
-                format!("Channel // #{}", channel_name)
+                format!("Channel // #{} — {} online / {} members", channel_name, online, members)
             }
             Some(crate::state::ChatTarget::DM { .. }) => {
                 let username = self.chat.selected_dm_user
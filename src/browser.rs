@@ -0,0 +1,33 @@
+// Opening links in the default browser through the platform's launcher command,
+// like the clipboard module, so nothing extra is linked in
+use std::process::{Command, Stdio};
+
+/// Launcher for the platform's default URL handler
+fn open_command(url: &str) -> (&'static str, Vec<&str>) {
+    if cfg!(target_os = "macos") {
+        ("open", vec![url])
+    } else if cfg!(target_os = "windows") {
+        // Not `cmd /C start`: cmd would treat `&`, `|` and `^` in the URL as commands
+        ("rundll32", vec!["url.dll,FileProtocolHandler", url])
+    } else {
+        ("xdg-open", vec![url])
+    }
+}
+
+/// Open a URL in the default browser. Fails when there is no launcher or display
+/// (e.g. over SSH), so callers can show the URL instead.
+pub fn open_url(url: &str) -> Result<(), String> {
+    let (program, args) = open_command(url);
+    let status = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|_| format!("No browser launcher available ({})", program))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} could not open the link", program))
+    }
+}
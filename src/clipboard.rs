@@ -0,0 +1,72 @@
+// Clipboard access through the platform's command-line tools, so no display
+// server libraries are needed at build time
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Commands that print the clipboard contents, in the order they're tried
+fn paste_commands() -> Vec<(&'static str, Vec<&'static str>)> {
+    if cfg!(target_os = "macos") {
+        vec![("pbpaste", vec![])]
+    } else if cfg!(target_os = "windows") {
+        vec![("powershell", vec!["-NoProfile", "-Command", "Get-Clipboard -Raw"])]
+    } else {
+        let mut commands = Vec::new();
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            commands.push(("wl-paste", vec!["--no-newline"]));
+        }
+        commands.push(("xclip", vec!["-selection", "clipboard", "-o"]));
+        commands.push(("xsel", vec!["--clipboard", "--output"]));
+        commands
+    }
+}
+
+/// Commands that replace the clipboard contents with their stdin
+fn copy_commands() -> Vec<(&'static str, Vec<&'static str>)> {
+    if cfg!(target_os = "macos") {
+        vec![("pbcopy", vec![])]
+    } else if cfg!(target_os = "windows") {
+        vec![("powershell", vec!["-NoProfile", "-Command", "$input | Set-Clipboard"])]
+    } else {
+        let mut commands = Vec::new();
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            commands.push(("wl-copy", vec![]));
+        }
+        commands.push(("xclip", vec!["-selection", "clipboard", "-i"]));
+        commands.push(("xsel", vec!["--clipboard", "--input"]));
+        commands
+    }
+}
+
+/// Read text from the system clipboard
+pub fn get_text() -> Result<String, String> {
+    for (program, args) in paste_commands() {
+        match Command::new(program).args(&args).output() {
+            Ok(output) if output.status.success() => {
+                return String::from_utf8(output.stdout)
+                    .map_err(|_| "Clipboard does not contain text".to_string());
+            }
+            _ => continue,
+        }
+    }
+    Err("Clipboard unavailable (install wl-clipboard, xclip or xsel)".to_string())
+}
+
+/// Write text to the system clipboard
+pub fn set_text(text: &str) -> Result<(), String> {
+    for (program, args) in copy_commands() {
+        let child = Command::new(program)
+            .args(&args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        let Ok(mut child) = child else { continue };
+        let written = child.stdin.take()
+            .map(|mut stdin| stdin.write_all(text.as_bytes()).is_ok())
+            .unwrap_or(false);
+        if written && child.wait().map(|s| s.success()).unwrap_or(false) {
+            return Ok(());
+        }
+    }
+    Err("Clipboard unavailable (install wl-clipboard, xclip or xsel)".to_string())
+}
@@ -21,6 +21,10 @@ impl DesktopNotificationService {
             debug!("Desktop notifications disabled in preferences");
             return;
         }
+        if prefs.dnd_enabled {
+            debug!("Desktop notification suppressed by Do Not Disturb");
+            return;
+        }
 
         // Log the notification attempt
         debug!("Attempting to show desktop notification: '{}' - '{}'", title, message);
@@ -1,17 +1,306 @@
 // Global preferences for the app (not user-specific)
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::fs;
 use serde::{Serialize, Deserialize};
 use once_cell::sync::OnceCell;
 use std::sync::RwLock;
+use ratatui_image::picker::ProtocolType;
+use crate::state::{ChatTarget, SidebarTab};
+
+/// How the channel user list is ordered and grouped
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserListSort {
+    /// Grouped by status (online first), then by name
+    Status,
+    /// Alphabetical by name, no grouping
+    Name,
+    /// Grouped by role (highest first), then by name
+    Role,
+}
+
+impl UserListSort {
+    pub fn label(&self) -> &'static str {
+        match self {
+            UserListSort::Status => "Status",
+            UserListSort::Name => "Name",
+            UserListSort::Role => "Role",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            UserListSort::Status => UserListSort::Name,
+            UserListSort::Name => UserListSort::Role,
+            UserListSort::Role => UserListSort::Status,
+        }
+    }
+}
+
+/// How offline users appear in the channel user list
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OfflineUsersMode {
+    /// Listed with everyone else, dimmed
+    Show,
+    /// Folded into a single "Offline — N" row at the bottom
+    Collapse,
+    /// Not listed at all
+    Hide,
+}
+
+impl OfflineUsersMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            OfflineUsersMode::Show => "Show (dimmed)",
+            OfflineUsersMode::Collapse => "Collapse",
+            OfflineUsersMode::Hide => "Hide",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            OfflineUsersMode::Show => OfflineUsersMode::Collapse,
+            OfflineUsersMode::Collapse => OfflineUsersMode::Hide,
+            OfflineUsersMode::Hide => OfflineUsersMode::Show,
+        }
+    }
+}
+
+/// Which chat pane gets focus when entering Chat
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChatEntryFocus {
+    Sidebar,
+    /// The message input, ready to type
+    Input,
+    /// Whatever pane was focused when Chat was last left
+    LastFocused,
+}
+
+impl ChatEntryFocus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ChatEntryFocus::Sidebar => "Sidebar",
+            ChatEntryFocus::Input => "Message Input",
+            ChatEntryFocus::LastFocused => "Last Focused",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            ChatEntryFocus::Sidebar => ChatEntryFocus::Input,
+            ChatEntryFocus::Input => ChatEntryFocus::LastFocused,
+            ChatEntryFocus::LastFocused => ChatEntryFocus::Sidebar,
+        }
+    }
+}
+
+/// How densely chat messages are laid out
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageDensity {
+    /// Avatars, a header per message and a blank line between messages
+    Cozy,
+    /// No avatars or blank lines; consecutive messages from one author are grouped
+    Compact,
+}
+
+impl MessageDensity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MessageDensity::Cozy => "Cozy",
+            MessageDensity::Compact => "Compact",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            MessageDensity::Cozy => MessageDensity::Compact,
+            MessageDensity::Compact => MessageDensity::Cozy,
+        }
+    }
+}
+
+/// When opening a conversation clears its unread state
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkReadMode {
+    /// As soon as it's selected
+    OnOpen,
+    /// Once the message pane has focus and is scrolled to the newest message, so
+    /// arrowing through the sidebar doesn't clear anything
+    AtBottom,
+    /// After it has been on screen for `mark_read_delay_secs`
+    AfterDelay,
+}
+
+impl MarkReadMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MarkReadMode::OnOpen => "On Open",
+            MarkReadMode::AtBottom => "When Scrolled to Bottom",
+            MarkReadMode::AfterDelay => "After Delay",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            MarkReadMode::OnOpen => MarkReadMode::AtBottom,
+            MarkReadMode::AtBottom => MarkReadMode::AfterDelay,
+            MarkReadMode::AfterDelay => MarkReadMode::OnOpen,
+        }
+    }
+}
+
+/// Where the timestamp goes in a chat message header
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampPosition {
+    AfterAuthor,
+    RightAligned,
+    Hidden,
+}
+
+impl TimestampPosition {
+    pub fn label(&self) -> &'static str {
+        match self {
+            TimestampPosition::AfterAuthor => "After Author",
+            TimestampPosition::RightAligned => "Right-Aligned",
+            TimestampPosition::Hidden => "Hidden",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            TimestampPosition::AfterAuthor => TimestampPosition::RightAligned,
+            TimestampPosition::RightAligned => TimestampPosition::Hidden,
+            TimestampPosition::Hidden => TimestampPosition::AfterAuthor,
+        }
+    }
+}
+
+/// Terminal graphics protocol used for avatars and profile images
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageProtocol {
+    /// Whatever the terminal query detects
+    Auto,
+    Kitty,
+    Sixel,
+    Iterm2,
+    /// Unicode half blocks; works in any color terminal
+    Halfblocks,
+}
+
+impl ImageProtocol {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImageProtocol::Auto => "Auto-detect",
+            ImageProtocol::Kitty => "Kitty",
+            ImageProtocol::Sixel => "Sixel",
+            ImageProtocol::Iterm2 => "iTerm2",
+            ImageProtocol::Halfblocks => "Halfblocks",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            ImageProtocol::Auto => ImageProtocol::Kitty,
+            ImageProtocol::Kitty => ImageProtocol::Sixel,
+            ImageProtocol::Sixel => ImageProtocol::Iterm2,
+            ImageProtocol::Iterm2 => ImageProtocol::Halfblocks,
+            ImageProtocol::Halfblocks => ImageProtocol::Auto,
+        }
+    }
+
+    /// Parse a command-line value (`auto`, `kitty`, `sixel`, `iterm`, `halfblocks`)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "auto" => Some(ImageProtocol::Auto),
+            "kitty" => Some(ImageProtocol::Kitty),
+            "sixel" => Some(ImageProtocol::Sixel),
+            "iterm" | "iterm2" => Some(ImageProtocol::Iterm2),
+            "halfblocks" | "halfblock" => Some(ImageProtocol::Halfblocks),
+            _ => None,
+        }
+    }
+
+    /// The forced picker protocol, or None to keep the detected one
+    pub fn forced(&self) -> Option<ProtocolType> {
+        match self {
+            ImageProtocol::Auto => None,
+            ImageProtocol::Kitty => Some(ProtocolType::Kitty),
+            ImageProtocol::Sixel => Some(ProtocolType::Sixel),
+            ImageProtocol::Iterm2 => Some(ProtocolType::Iterm2),
+            ImageProtocol::Halfblocks => Some(ProtocolType::Halfblocks),
+        }
+    }
+}
+
+/// Theme/background chosen while logged in to one account; unset parts use the global default
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AccountAppearance {
+    pub theme_name: Option<String>,
+    pub background_name: Option<String>,
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
 pub struct GlobalPrefs {
     pub sound_effects_enabled: bool,
     pub minimal_banner_glitch_enabled: bool,
     pub desktop_notifications_enabled: bool,
     pub theme_name: String,
     pub background_name: String,
+    pub user_list_sort: UserListSort,
+    pub offline_users: OfflineUsersMode,
+    pub auto_reconnect_on_startup: bool,
+    /// Automatic reconnect attempts before leaving it to a manual retry (0 = keep trying)
+    pub reconnect_max_attempts: u32,
+    pub chat_entry_focus: ChatEntryFocus,
+    /// Treat emoji and other wide glyphs as two cells when laying out text
+    pub wide_emoji: bool,
+    pub message_density: MessageDensity,
+    pub smooth_scrolling: bool,
+    /// Swap PageUp/PageDown, so PageDown moves toward older messages
+    pub reverse_scroll: bool,
+    /// Capture the mouse for scrolling and clicking (disables terminal text selection)
+    pub mouse_support: bool,
+    /// Force a terminal graphics protocol instead of trusting auto-detection
+    pub image_protocol: ImageProtocol,
+    /// Per-account theme/background, keyed by `user@server`
+    pub account_appearance: HashMap<String, AccountAppearance>,
+    /// Freeze animated backgrounds after a minute without input or while on battery
+    pub pause_background_when_idle: bool,
+    /// Conversation open when the app was last used, reopened after login
+    pub last_chat_target: Option<ChatTarget>,
+    pub last_sidebar_tab: SidebarTab,
+    pub timestamp_position: TimestampPosition,
+    /// Esc on the main menu, pressed again right away, asks to quit
+    pub double_esc_quit: bool,
+    /// Pre-fill the login form with the last username that logged in (never the password)
+    pub remember_username: bool,
+    pub last_username: Option<String>,
+    /// Sound effect volume, 0-100
+    pub sound_volume: u8,
+    /// Muted keeps `sound_volume`, so unmuting goes back to the same level
+    pub sound_muted: bool,
+    pub mark_read_mode: MarkReadMode,
+    /// Seconds a conversation must stay open before `MarkReadMode::AfterDelay` clears it
+    pub mark_read_delay_secs: u64,
+    /// Directory of custom sound files; unset means ~/.nexus_sounds
+    pub sounds_dir: Option<String>,
+    /// Do Not Disturb: no desktop notifications or alert sounds (in-app popups still show)
+    pub dnd_enabled: bool,
+    /// Redraw cap in frames per second; idle screens with nothing animating aren't redrawn at all
+    pub max_fps: u32,
+    /// Chat avatar size in pixels; the user list uses half
+    pub avatar_pixel_size: u32,
+    /// Skip avatar images entirely and show a plain marker instead
+    pub text_only_avatars: bool,
+    /// Emoji inserted lately, newest first (capped at `RECENT_EMOJI_LIMIT`)
+    pub recent_emoji: Vec<String>,
+    /// Pinned emoji, listed before everything else in suggestions and the picker
+    pub favorite_emoji: Vec<String>,
+    /// Show times as 20:30 instead of 8:30 PM
+    pub time_format_24h: bool,
+    /// strftime pattern for the time of day (e.g. "%H:%M:%S"); overrides `time_format_24h`
+    pub time_format: Option<String>,
 }
 
 impl Default for GlobalPrefs {
@@ -22,14 +311,63 @@ impl Default for GlobalPrefs {
             desktop_notifications_enabled: true,
             theme_name: "Cyberpunk".to_string(),
             background_name: "Minimal".to_string(),
+            user_list_sort: UserListSort::Status,
+            offline_users: OfflineUsersMode::Show,
+            auto_reconnect_on_startup: false,
+            reconnect_max_attempts: 10,
+            chat_entry_focus: ChatEntryFocus::LastFocused,
+            wide_emoji: true,
+            message_density: MessageDensity::Cozy,
+            smooth_scrolling: false,
+            reverse_scroll: false,
+            mouse_support: false,
+            image_protocol: ImageProtocol::Auto,
+            account_appearance: HashMap::new(),
+            pause_background_when_idle: true,
+            last_chat_target: None,
+            last_sidebar_tab: SidebarTab::Servers,
+            timestamp_position: TimestampPosition::AfterAuthor,
+            double_esc_quit: true,
+            remember_username: true,
+            last_username: None,
+            sound_volume: 80,
+            sound_muted: false,
+            mark_read_mode: MarkReadMode::OnOpen,
+            mark_read_delay_secs: 3,
+            sounds_dir: None,
+            dnd_enabled: false,
+            max_fps: 20,
+            avatar_pixel_size: 32,
+            text_only_avatars: false,
+            recent_emoji: Vec::new(),
+            favorite_emoji: Vec::new(),
+            time_format_24h: false,
+            time_format: None,
         }
     }
 }
 
 impl GlobalPrefs {
-    pub fn config_path() -> PathBuf {
+    pub const RECENT_EMOJI_LIMIT: usize = 24;
+
+    /// strftime pattern for times of day: the custom one when it's valid, else 24h or 12h
+    pub fn clock_format(&self) -> &str {
+        match self.time_format.as_deref() {
+            Some(custom) if !custom.is_empty()
+                && !chrono::format::StrftimeItems::new(custom).any(|item| matches!(item, chrono::format::Item::Error)) => custom,
+            _ if self.time_format_24h => "%H:%M",
+            _ => "%-I:%M %p",
+        }
+    }
+
+    /// `~/.nexus_<name>`: the preferences file and every other config file sit side by side
+    pub fn config_file(name: &str) -> PathBuf {
         let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        PathBuf::from(home).join(".nexus_prefs.json")
+        PathBuf::from(home).join(format!(".nexus_{}", name))
+    }
+
+    pub fn config_path() -> PathBuf {
+        Self::config_file("prefs.json")
     }
 
     pub fn load() -> Self {
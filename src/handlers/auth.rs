@@ -1,6 +1,8 @@
 use crate::app::App;
 use crate::sound::SoundType;
 use nexus_tui_common::ClientMessage;
+use crate::handlers::is_delete_word_key;
+use crate::services::MessageService;
 use crossterm::event::{KeyCode, KeyEvent};
 
 /// Handle authentication input (login/register)
@@ -8,6 +10,17 @@ pub fn handle_auth_input(key: KeyEvent, app: &mut App) {
     let is_login = app.ui.mode == crate::state::AppMode::Login;
     
     match key.code {
+        _ if is_delete_word_key(&key) => {
+            match app.auth.input_mode {
+                Some(crate::state::InputMode::LoginUsername) | Some(crate::state::InputMode::RegisterUsername) => {
+                    MessageService::delete_word_back(&mut app.auth.current_input);
+                }
+                Some(crate::state::InputMode::LoginPassword) | Some(crate::state::InputMode::RegisterPassword) => {
+                    MessageService::delete_word_back(&mut app.auth.password_input);
+                }
+                _ => {}
+            }
+        }
         KeyCode::Char(c) => {
             if let Some(im) = &app.auth.input_mode {
                 match im {
@@ -98,6 +111,7 @@ pub fn handle_auth_input(key: KeyEvent, app: &mut App) {
                         return;
                     }
                     
+                    app.auth.session_credentials = Some((username.clone(), password.clone()));
                     if is_login {
                         app.send_to_server(ClientMessage::Login { username, password });
                     } else {
@@ -115,6 +129,7 @@ pub fn handle_auth_input(key: KeyEvent, app: &mut App) {
                     } else {
                         app.ui.set_mode(crate::state::AppMode::Login);
                         app.auth.input_mode = Some(crate::state::InputMode::LoginUsername);
+                        app.prefill_login();
                     }
                 }
                 _ => {}
@@ -125,4 +140,18 @@ pub fn handle_auth_input(key: KeyEvent, app: &mut App) {
         }
         _ => {}
     }
-}
\ No newline at end of file
+}
+
+/// Paste into the focused username or password field; both are a single line
+pub fn paste_text(text: &str, app: &mut App) {
+    let text: String = MessageService::flatten_newlines(text).chars().filter(|c| !c.is_control()).collect();
+    match app.auth.input_mode {
+        Some(crate::state::InputMode::LoginUsername) | Some(crate::state::InputMode::RegisterUsername) => {
+            app.auth.current_input.push_str(&text);
+        }
+        Some(crate::state::InputMode::LoginPassword) | Some(crate::state::InputMode::RegisterPassword) => {
+            app.auth.password_input.push_str(&text);
+        }
+        _ => {}
+    }
+}
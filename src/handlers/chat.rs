@@ -1,6 +1,8 @@
 use crate::app::App;
 use crate::sound::SoundType;
 use nexus_tui_common::ClientMessage;
+use crate::handlers::{is_delete_word_key, is_paste_key, read_clipboard};
+use crate::services::MessageService;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 
 /// Handle chat-related input
@@ -21,7 +23,17 @@ pub fn handle_chat_input(key: KeyEvent, app: &mut App) {
 fn handle_chat_popups(key: KeyEvent, app: &mut App) -> bool {
     // Handle profile view popup
     if app.profile.show_profile_view_popup {
-        app.profile.close_profile_view();
+        // 1-3 open the profile's links; any other key closes it
+        let url = match key.code {
+            KeyCode::Char('1') => app.profile.profile_view.as_ref().and_then(|p| p.url1.clone()),
+            KeyCode::Char('2') => app.profile.profile_view.as_ref().and_then(|p| p.url2.clone()),
+            KeyCode::Char('3') => app.profile.profile_view.as_ref().and_then(|p| p.url3.clone()),
+            _ => None,
+        };
+        match url.filter(|u| !u.trim().is_empty()) {
+            Some(url) => open_link(app, url.trim()),
+            None => app.profile.close_profile_view(),
+        }
         return true;
     }
 
@@ -51,6 +63,42 @@ fn handle_chat_popups(key: KeyEvent, app: &mut App) -> bool {
         return true;
     }
 
+    if app.chat.emoji_picker.is_some() {
+        handle_emoji_picker_input(key, app);
+        return true;
+    }
+
+    // Handle server actions popup (F5)
+    if app.ui.show_server_actions {
+        let count = app.server_actions().len();
+        match key.code {
+            KeyCode::Up => {
+                app.sound_manager.play(SoundType::Scroll);
+                app.ui.server_actions_selected = app.ui.server_actions_selected.saturating_sub(1);
+            }
+            KeyCode::Down => {
+                app.sound_manager.play(SoundType::Scroll);
+                if app.ui.server_actions_selected + 1 < count {
+                    app.ui.server_actions_selected += 1;
+                }
+            }
+            KeyCode::Enter => {
+                if let Some(action) = app.server_actions().get(app.ui.server_actions_selected).copied() {
+                    handle_server_action(action, app);
+                }
+                app.ui.show_server_actions = false;
+                app.ui.server_actions_selected = 0;
+            }
+            KeyCode::Esc => {
+                app.sound_manager.play(SoundType::PopupClose);
+                app.ui.show_server_actions = false;
+                app.ui.server_actions_selected = 0;
+            }
+            _ => {}
+        }
+        return true;
+    }
+
     // Handle server invite selection popup
     if app.ui.show_server_invite_selection {
         match key.code {
@@ -60,16 +108,18 @@ fn handle_chat_popups(key: KeyEvent, app: &mut App) -> bool {
                 }
             }
             KeyCode::Down => {
-                if app.ui.server_invite_selected < app.chat.servers.len().saturating_sub(1) {
+                let count = app.ui.server_invite_target_user.map_or(0, |uid| app.invite_servers(uid).len());
+                if app.ui.server_invite_selected + 1 < count {
                     app.ui.server_invite_selected += 1;
                 }
             }
             KeyCode::Enter => {
                 if let Some(target_user_id) = app.ui.server_invite_target_user {
-                    if let Some(server) = app.chat.servers.get(app.ui.server_invite_selected) {
+                    let choice = app.invite_servers(target_user_id).get(app.ui.server_invite_selected).copied();
+                    if let Some((server_id, server_name)) = choice.and_then(|i| app.chat.servers.get(i)).map(|s| (s.id, s.name.clone())) {
                         app.send_to_server(ClientMessage::SendServerInvite {
                             to_user_id: target_user_id,
-                            server_id: server.id,
+                            server_id,
                         });
                         
                         let username = app.chat.channel_userlist.iter()
@@ -77,7 +127,7 @@ fn handle_chat_popups(key: KeyEvent, app: &mut App) -> bool {
                             .map(|u| u.username.clone())
                             .unwrap_or_else(|| "User".to_string());
                         
-                        app.set_notification(&format!("Sent server invite to {}!", username), Some(2000), false);
+                        app.set_notification(format!("Sent {} an invite to {}", username, server_name), Some(2000), false);
                     }
                 }
                 app.ui.show_server_invite_selection = false;
@@ -97,7 +147,34 @@ fn handle_chat_popups(key: KeyEvent, app: &mut App) -> bool {
     false
 }
 
+/// Paste into whichever chat field has focus; popups and the sidebar don't take text
+pub fn paste_text(text: &str, app: &mut App) {
+    if app.profile.show_profile_view_popup || app.profile.show_user_actions || app.chat.emoji_picker.is_some()
+        || app.ui.show_server_actions || app.ui.show_server_invite_selection
+    {
+        return;
+    }
+    match app.chat.chat_focus {
+        crate::state::ChatFocus::Messages => paste_into_message_input(text, app),
+        crate::state::ChatFocus::DMInput => app.chat.dm_input.push_str(&MessageService::flatten_newlines(text)),
+        crate::state::ChatFocus::Users => {
+            let Some(filter) = app.chat.user_filter.as_mut() else { return };
+            filter.query.extend(MessageService::flatten_newlines(text).chars().filter(|c| !c.is_control()));
+            let any = !app.chat.visible_user_indices(app.prefs.offline_users).is_empty();
+            app.chat.user_list_state.select(any.then_some(0));
+        }
+        crate::state::ChatFocus::Sidebar => {}
+    }
+}
+
+fn paste_into_message_input(text: &str, app: &mut App) {
+    app.chat.insert_str_at_cursor(&MessageService::flatten_newlines(text));
+    app.update_mention_suggestions();
+    app.update_emoji_suggestions();
+}
+
 fn handle_sidebar_input(key: KeyEvent, app: &mut App) {
+    let key = super::vim_list_key(key, app);
     match key.code {
         KeyCode::Tab => {
             if app.chat.show_user_list {
@@ -139,6 +216,10 @@ fn handle_sidebar_input(key: KeyEvent, app: &mut App) {
             }
             select_current_sidebar_target(app);
         }
+        KeyCode::Home | KeyCode::End => {
+            jump_sidebar_selection(app, key.code == KeyCode::End);
+            select_current_sidebar_target(app);
+        }
         KeyCode::Enter => {
             app.chat.chat_focus = crate::state::ChatFocus::Messages;
         }
@@ -161,13 +242,14 @@ fn handle_message_input(key: KeyEvent, app: &mut App) {
         KeyCode::BackTab => {
             app.chat.chat_focus = crate::state::ChatFocus::Sidebar;
         }
-        KeyCode::PageUp => {
-            app.sound_manager.play(SoundType::Scroll);
-            handle_scroll_up(app);
-        }
-        KeyCode::PageDown => {
+        KeyCode::PageUp | KeyCode::PageDown => {
             app.sound_manager.play(SoundType::Scroll);
-            handle_scroll_down(app);
+            // PageUp goes toward older messages unless the direction is reversed
+            if (key.code == KeyCode::PageUp) != app.prefs.reverse_scroll {
+                handle_scroll_up(app);
+            } else {
+                handle_scroll_down(app);
+            }
         }
         KeyCode::Down => {
             // Handle mention suggestions first, then emoji suggestions
@@ -197,7 +279,11 @@ fn handle_message_input(key: KeyEvent, app: &mut App) {
                         app.chat.emoji_selected = new_index.min(app.chat.emoji_suggestions.len() - 1);
                     }
                 }
+            } else if app.chat.selected_message.is_some() {
+                // Move the selection toward newer messages; past the newest clears it
+                app.chat.move_message_selection(1);
             } else if app.chat.chat_scroll_offset > 0 {
+                app.chat.scroll_target = None;
                 app.chat.chat_scroll_offset -= 1;
             }
         }
@@ -230,35 +316,15 @@ fn handle_message_input(key: KeyEvent, app: &mut App) {
                     app.chat.emoji_selected = new_index.min(app.chat.emoji_suggestions.len() - 1);
                 }
             } else {
-                // Scroll up one line and fetch more messages if needed
-                let max_rows = app.chat.last_chat_rows.unwrap_or(20);
-                let total_msgs = app.get_current_message_list().len();
-                let max_scroll = total_msgs.saturating_sub(max_rows);
+                // Select the previous message (scrolling it into view) and fetch more if needed
+                let max_rows = app.chat.last_visible_messages.unwrap_or(20);
+                let previous = app.chat.selected_message;
+                app.chat.move_message_selection(-1);
                 
-                if app.chat.chat_scroll_offset < max_scroll {
-                    app.chat.chat_scroll_offset += 1;
-                    
+                if app.chat.selected_message != previous {
                     // Check if we need to fetch more messages when scrolling up
                     if crate::services::ChatService::should_fetch_more_messages(&app.chat, max_rows) {
-                        match &app.chat.current_chat_target {
-                            Some(crate::state::ChatTarget::Channel { server_id: _, channel_id }) => {
-                                if let Some(oldest_msg) = app.chat.chat_messages.first() {
-                                    app.send_to_server(ClientMessage::GetChannelMessages {
-                                        channel_id: *channel_id,
-                                        before: Some(oldest_msg.timestamp),
-                                    });
-                                }
-                            }
-                            Some(crate::state::ChatTarget::DM { user_id }) => {
-                                if let Some(oldest) = app.chat.dm_messages.first() {
-                                    app.send_to_server(ClientMessage::GetDirectMessages {
-                                        user_id: *user_id,
-                                        before: Some(oldest.timestamp),
-                                    });
-                                }
-                            }
-                            None => {}
-                        }
+                        app.request_older_history();
                     }
                 }
             }
@@ -283,6 +349,8 @@ fn handle_message_input(key: KeyEvent, app: &mut App) {
                         app.chat.emoji_selected = new_index;
                     }
                 }
+            } else {
+                app.chat.move_cursor_left();
             }
         }
         KeyCode::Right => {
@@ -306,6 +374,22 @@ fn handle_message_input(key: KeyEvent, app: &mut App) {
                     let new_index = current_page * ITEMS_PER_PAGE + current_row * GRID_COLS;
                     app.chat.emoji_selected = new_index;
                 }
+            } else {
+                app.chat.move_cursor_right();
+            }
+        }
+        KeyCode::Home => {
+            app.chat.move_cursor_home();
+        }
+        KeyCode::End => {
+            // Jump to the latest messages when scrolled up (or always with Ctrl),
+            // otherwise move the input cursor to the end
+            let scrolled_up = app.chat.final_scroll_offset() > 0 || app.chat.selected_message.is_some();
+            if scrolled_up || key.modifiers.contains(KeyModifiers::CONTROL) {
+                app.chat.clear_message_selection();
+                app.chat.reset_scroll_offset();
+            } else {
+                app.chat.move_cursor_end();
             }
         }
         KeyCode::Enter => {
@@ -313,6 +397,12 @@ fn handle_message_input(key: KeyEvent, app: &mut App) {
                 app.apply_selected_mention();
             } else if !app.chat.emoji_suggestions.is_empty() {
                 app.apply_selected_emoji();
+            } else if app.chat.get_current_input().trim().is_empty()
+                && app.chat.history_top_visible
+                && !app.chat.history_complete()
+            {
+                // "▲ Load older messages" is showing at the top
+                app.request_older_history();
             } else if let Err(e) = app.send_message() {
                 app.set_notification(format!("Failed to send message: {}", e), Some(2000), false);
             }
@@ -320,31 +410,96 @@ fn handle_message_input(key: KeyEvent, app: &mut App) {
         KeyCode::Char('u') if key.modifiers == KeyModifiers::CONTROL => {
             app.chat.show_user_list = !app.chat.show_user_list;
         }
+        KeyCode::Char('e') if key.modifiers == KeyModifiers::CONTROL => {
+            app.chat.clear_mention_suggestions();
+            app.chat.clear_emoji_suggestions();
+            app.chat.emoji_picker = Some(crate::state::chat::EmojiPicker::new(&app.prefs.favorite_emoji, &app.prefs.recent_emoji));
+            app.sound_manager.play(SoundType::PopupOpen);
+        }
+        KeyCode::Char('y') if key.modifiers.is_empty() && app.chat.selected_message.is_some() => {
+            // Yank the selected message
+            let content = app.chat.selected_message
+                .and_then(|idx| app.get_current_message(idx).map(|m| m.content));
+            if let Some(content) = content {
+                match crate::clipboard::set_text(&MessageService::copyable_text(&content)) {
+                    Ok(()) => app.set_notification("Copied to clipboard", Some(1500), true),
+                    Err(e) => app.set_notification(e, Some(2000), true),
+                }
+            }
+        }
+        KeyCode::Char('o') if key.modifiers.is_empty() && app.chat.selected_message.is_some() => {
+            // Open the first link in the selected message
+            let url = app.chat.selected_message
+                .and_then(|idx| app.get_current_message(idx).map(|m| m.content))
+                .and_then(|content| MessageService::find_urls(&content).first().map(|&(start, end)| content[start..end].to_string()));
+            match url {
+                Some(url) => open_link(app, &url),
+                None => app.set_notification("No link in this message", Some(1500), true),
+            }
+        }
+        KeyCode::Char('/') if app.chat.get_current_input().is_empty() => {
+            // Search this conversation, starting from the previous query
+            let query = app.chat.search_query.clone();
+            app.enter_input_mode(crate::state::InputMode::SearchMessages);
+            app.auth.current_input = query;
+        }
+        KeyCode::Char('n') | KeyCode::Char('N')
+            if !app.chat.search_matches.is_empty() && app.chat.selected_message.is_some() =>
+        {
+            // While a match is selected, n goes to the next older match and N back toward
+            // newer ones (Esc drops the selection to type an n again)
+            let delta = if key.code == KeyCode::Char('n') { -1 } else { 1 };
+            app.cycle_search_match(delta);
+        }
+        KeyCode::Char('r') if key.modifiers.is_empty() && app.chat.selected_message.is_some() => {
+            // Reply to the selected message and go back to typing
+            app.chat.reply_target = app.chat.selected_message_id;
+            app.chat.clear_message_selection();
+            app.chat.reset_scroll_offset();
+        }
+        _ if is_paste_key(&key) => {
+            if let Some(text) = read_clipboard(app) {
+                paste_into_message_input(&text, app);
+            }
+        }
+        _ if is_delete_word_key(&key) => {
+            app.chat.delete_word_before_cursor();
+            app.update_mention_suggestions();
+            app.update_emoji_suggestions();
+        }
         KeyCode::Char(c) => {
             if key.modifiers.contains(KeyModifiers::CONTROL) {
                 return;
             }
-            let mut current = app.get_current_input().to_string();
-            current.push(c);
-            app.set_current_input(current);
+            app.chat.insert_at_cursor(c);
             app.update_mention_suggestions();
             app.update_emoji_suggestions();
         }
         KeyCode::Backspace => {
-            let mut current = app.get_current_input().to_string();
-            current.pop();
-            app.set_current_input(current);
+            app.chat.backspace_at_cursor();
             app.update_mention_suggestions();
             app.update_emoji_suggestions();
         }
         KeyCode::Esc => {
-            app.ui.set_mode(crate::state::AppMode::MainMenu);
+            if app.chat.selected_message.is_some() {
+                app.chat.clear_message_selection();
+            } else if app.chat.reply_target.is_some() {
+                app.chat.reply_target = None;
+            } else if !app.chat.search_query.is_empty() {
+                app.chat.clear_search();
+            } else {
+                app.ui.set_mode(crate::state::AppMode::MainMenu);
+            }
         }
         _ => {}
     }
 }
 
 fn handle_user_list_input(key: KeyEvent, app: &mut App) {
+    if app.chat.user_filter.is_some() && handle_user_filter_input(key, app) {
+        return;
+    }
+    let key = super::vim_list_key(key, app);
     match key.code {
         KeyCode::Tab => {
             app.chat.chat_focus = crate::state::ChatFocus::Sidebar;
@@ -361,7 +516,7 @@ fn handle_user_list_input(key: KeyEvent, app: &mut App) {
             };
         }
         KeyCode::Down => {
-            let len = app.chat.channel_userlist.len();
+            let len = app.chat.visible_user_indices(app.prefs.offline_users).len();
             if len > 0 {
                 app.sound_manager.play(SoundType::Scroll);
                 let sel = app.chat.user_list_state.selected().unwrap_or(0);
@@ -369,21 +524,46 @@ fn handle_user_list_input(key: KeyEvent, app: &mut App) {
             }
         }
         KeyCode::Up => {
-            let len = app.chat.channel_userlist.len();
+            let len = app.chat.visible_user_indices(app.prefs.offline_users).len();
             if len > 0 {
                 app.sound_manager.play(SoundType::Scroll);
                 let sel = app.chat.user_list_state.selected().unwrap_or(0);
                 app.chat.user_list_state.select(Some((sel + len - 1) % len));
             }
         }
+        KeyCode::Home | KeyCode::End => {
+            let len = app.chat.visible_user_indices(app.prefs.offline_users).len();
+            if len > 0 {
+                app.sound_manager.play(SoundType::Scroll);
+                app.chat.user_list_state.select(Some(if key.code == KeyCode::Home { 0 } else { len - 1 }));
+            }
+        }
         KeyCode::Enter => {
-            if let Some(idx) = app.chat.user_list_state.selected() {
+            if let Some(idx) = app.chat.selected_user_list_index(app.prefs.offline_users) {
                 app.sound_manager.play(SoundType::PopupOpen);
                 app.profile.show_user_actions = true;
                 app.profile.user_actions_selected = 0;
                 app.profile.user_actions_target = Some(idx);
             }
         }
+        KeyCode::Char('/') => {
+            let prior_selection = app.chat.user_list_state.selected();
+            app.chat.user_filter = Some(crate::state::forum::ListFilter { query: String::new(), prior_selection });
+        }
+        KeyCode::Char('s') => {
+            app.prefs.user_list_sort = app.prefs.user_list_sort.next();
+            app.resort_user_list();
+            app.prefs_dirty = true;
+            app.prefs_dirty_last_update = Some(std::time::Instant::now());
+        }
+        KeyCode::Char('o') => {
+            // Cycle how offline users are shown: dimmed, collapsed, hidden
+            app.prefs.offline_users = app.prefs.offline_users.next();
+            app.resort_user_list();
+            app.prefs_dirty = true;
+            app.prefs_dirty_last_update = Some(std::time::Instant::now());
+            app.set_notification(format!("Offline users: {}", app.prefs.offline_users.label()), Some(1500), true);
+        }
         KeyCode::Esc => {
             app.ui.set_mode(crate::state::AppMode::MainMenu);
         }
@@ -391,6 +571,31 @@ fn handle_user_list_input(key: KeyEvent, app: &mut App) {
     }
 }
 
+/// Keys while the user list filter is open: typing narrows the list with the first
+/// match selected, Esc clears it and restores the selection. Everything else (moving,
+/// Enter for the actions popup) returns false and works on the filtered rows as usual.
+fn handle_user_filter_input(key: KeyEvent, app: &mut App) -> bool {
+    let Some(filter) = app.chat.user_filter.as_mut() else { return false };
+    match key.code {
+        KeyCode::Esc => {
+            let prior = filter.prior_selection;
+            app.chat.user_filter = None;
+            app.chat.user_list_state.select(prior);
+            return true;
+        }
+        KeyCode::Backspace => {
+            filter.query.pop();
+        }
+        KeyCode::Char(c) if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+            filter.query.push(c);
+        }
+        _ => return false,
+    }
+    let any = !app.chat.visible_user_indices(app.prefs.offline_users).is_empty();
+    app.chat.user_list_state.select(any.then_some(0));
+    true
+}
+
 fn handle_dm_input(key: KeyEvent, app: &mut App) {
     match key.code {
         KeyCode::Enter => {
@@ -404,6 +609,14 @@ fn handle_dm_input(key: KeyEvent, app: &mut App) {
             app.chat.dm_input.clear();
             app.chat.chat_focus = crate::state::ChatFocus::Users;
         }
+        _ if is_paste_key(&key) => {
+            if let Some(text) = read_clipboard(app) {
+                app.chat.dm_input.push_str(&MessageService::flatten_newlines(&text));
+            }
+        }
+        _ if is_delete_word_key(&key) => {
+            MessageService::delete_word_back(&mut app.chat.dm_input);
+        }
         KeyCode::Char(c) => {
             app.chat.dm_input.push(c);
         }
@@ -426,9 +639,8 @@ fn handle_user_action(app: &mut App) {
         
         match app.profile.user_actions_selected {
             0 => { // View Profile
-                if let Some(user) = user {
-                    app.profile.profile_requested_by_user = true;
-                    app.send_to_server(ClientMessage::GetProfile { user_id: user.id });
+                if let Some(user_id) = user.map(|u| u.id) {
+                    app.request_profile_view(user_id);
                 }
             }
             1 => { // Send DM
@@ -439,10 +651,8 @@ fn handle_user_action(app: &mut App) {
                 }
             }
             2 => { // Invite to Server
-                if let Some(user) = user {
-                    app.ui.show_server_invite_selection = true;
-                    app.ui.server_invite_selected = 0;
-                    app.ui.server_invite_target_user = Some(user.id);
+                if let Some(user_id) = user.map(|u| u.id) {
+                    open_server_invite_selection(app, user_id);
                 }
             }
             _ => {}
@@ -451,57 +661,137 @@ fn handle_user_action(app: &mut App) {
     app.profile.show_user_actions = false;
 }
 
-fn handle_scroll_up(app: &mut App) {
-    let max_rows = app.chat.last_chat_rows.unwrap_or(20);
-    
-    match &app.chat.current_chat_target {
-        Some(crate::state::ChatTarget::Channel { server_id: _, channel_id }) => {
-            let total_msgs = app.get_current_message_list().len();
-            let max_scroll_offset = total_msgs.saturating_sub(max_rows);
-            
-            app.chat.chat_scroll_offset = (app.chat.chat_scroll_offset + max_rows).min(max_scroll_offset);
-            
-            // Fetch more messages if needed
-            if crate::services::ChatService::should_fetch_more_messages(&app.chat, max_rows) {
-                // Look for the oldest message in the actual chat messages, not channel.messages
-                if let Some(oldest_msg) = app.chat.chat_messages.first() {
-                    app.send_to_server(ClientMessage::GetChannelMessages {
-                        channel_id: *channel_id,
-                        before: Some(oldest_msg.timestamp),
-                    });
-                }
-            }
+/// Ask which server to invite `user_id` to, starting on the server open in the sidebar
+fn open_server_invite_selection(app: &mut App, user_id: uuid::Uuid) {
+    let servers = app.invite_servers(user_id);
+    if servers.is_empty() {
+        app.set_notification("They're already in all of your servers", Some(2000), true);
+        return;
+    }
+    app.ui.server_invite_selected = app.chat.selected_server
+        .and_then(|current| servers.iter().position(|&i| i == current))
+        .unwrap_or(0);
+    app.ui.server_invite_target_user = Some(user_id);
+    app.ui.show_server_invite_selection = true;
+}
+
+/// Keys while the emoji picker is open: typing searches, arrows and PgUp/PgDn move
+/// over the grid, Enter inserts the emoji at the cursor.
+fn handle_emoji_picker_input(key: KeyEvent, app: &mut App) {
+    use crate::state::chat::EmojiPicker;
+    let Some(picker) = app.chat.emoji_picker.as_mut() else { return };
+    let page = (EmojiPicker::COLS * EmojiPicker::ROWS) as isize;
+    match key.code {
+        KeyCode::Esc => {
+            app.chat.emoji_picker = None;
+            app.sound_manager.play(SoundType::PopupClose);
         }
-        Some(crate::state::ChatTarget::DM { user_id }) => {
-            let total_msgs = app.get_current_message_list().len();
-            let max_scroll_offset = total_msgs.saturating_sub(max_rows);
-            
-            app.chat.chat_scroll_offset = (app.chat.chat_scroll_offset + max_rows).min(max_scroll_offset);
-            
-            // Fetch more DM messages if needed
-            if crate::services::ChatService::should_fetch_more_messages(&app.chat, max_rows) {
-                if let Some(oldest) = app.chat.dm_messages.first() {
-                    app.send_to_server(ClientMessage::GetDirectMessages {
-                        user_id: *user_id,
-                        before: Some(oldest.timestamp),
-                    });
-                }
+        KeyCode::Enter => {
+            if let Some(emoji) = picker.matches.get(picker.selected).cloned() {
+                app.chat.emoji_picker = None;
+                app.chat.insert_str_at_cursor(&emoji);
+                app.record_emoji_use(&emoji);
+                app.sound_manager.play(SoundType::Select);
+            }
+        }
+        KeyCode::Char('f') if key.modifiers == KeyModifiers::CONTROL => {
+            // Pin or unpin the highlighted emoji; the order only changes on the next search
+            if let Some(emoji) = picker.matches.get(picker.selected).cloned() {
+                let pinned = app.toggle_favorite_emoji(&emoji);
+                let message = if pinned { format!("{} added to favorites", emoji) } else { format!("{} removed from favorites", emoji) };
+                app.set_notification(message, Some(1500), true);
+            }
+        }
+        KeyCode::Left => picker.move_by(-1),
+        KeyCode::Right => picker.move_by(1),
+        KeyCode::Up => picker.move_by(-(EmojiPicker::COLS as isize)),
+        KeyCode::Down => picker.move_by(EmojiPicker::COLS as isize),
+        KeyCode::PageUp => picker.move_by(-page),
+        KeyCode::PageDown => picker.move_by(page),
+        KeyCode::Backspace => {
+            picker.query.pop();
+            picker.refilter(&app.prefs.favorite_emoji, &app.prefs.recent_emoji);
+        }
+        KeyCode::Char(c) if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+            picker.query.push(c);
+            picker.refilter(&app.prefs.favorite_emoji, &app.prefs.recent_emoji);
+        }
+        _ => {}
+    }
+}
+
+fn handle_server_action(action: crate::state::ui::ServerAction, app: &mut App) {
+    use crate::state::ui::ServerAction;
+    let Some(server) = app.chat.selected_server.and_then(|s| app.chat.servers.get(s)) else {
+        app.set_notification("Select a server first", Some(2000), true);
+        return;
+    };
+    app.sound_manager.play(SoundType::PopupOpen);
+    match action {
+        ServerAction::ViewUsers => {
+            app.chat.show_user_list = true;
+            app.chat.chat_focus = crate::state::ChatFocus::Users;
+        }
+        ServerAction::InviteUser => {
+            // Pick the user from the list, then Enter -> "Invite to Server"
+            app.chat.show_user_list = true;
+            app.chat.chat_focus = crate::state::ChatFocus::Users;
+            app.set_notification("Select a user, press Enter and choose \"Invite to Server\"", Some(3000), true);
+        }
+        ServerAction::CopyInviteCode => {
+            let code = server.invite_code.clone().unwrap_or_default();
+            match crate::clipboard::set_text(&code) {
+                Ok(()) => app.set_notification(format!("Invite code copied: {}", code), Some(2000), true),
+                Err(e) => app.set_notification(format!("Invite code: {} ({})", code, e), Some(4000), false),
             }
         }
-        None => {}
     }
 }
 
-fn handle_scroll_down(app: &mut App) {
-    let max_rows = app.chat.last_chat_rows.unwrap_or(20);
+fn handle_scroll_up(app: &mut App) {
+    // A page is what was on screen, so the message above the top lands at the bottom
+    let page = app.chat.last_visible_messages.unwrap_or(20).max(1);
+    scroll_up_by(app, page);
+}
+
+/// Scroll `amount` messages toward older history, fetching more when near the top
+pub(crate) fn scroll_up_by(app: &mut App, amount: usize) {
+    let max_rows = app.chat.last_visible_messages.unwrap_or(20);
     
-    if app.chat.chat_scroll_offset >= max_rows {
-        app.chat.chat_scroll_offset -= max_rows;
-    } else {
-        app.chat.chat_scroll_offset = 0;
+    if app.chat.current_chat_target.is_none() {
+        return;
+    }
+    let total_msgs = app.current_message_count();
+    let max_scroll_offset = app.chat.max_scroll_offset.unwrap_or(total_msgs).min(total_msgs);
+
+    let target = (app.chat.final_scroll_offset() + amount).min(max_scroll_offset);
+    if scroll_with_history_check(app, target, max_rows) {
+        app.request_older_history();
     }
 }
 
+/// Scroll to `target` and report whether older history should be fetched. The check
+/// runs against the final offset so smooth scrolling fetches as early as a jump would.
+fn scroll_with_history_check(app: &mut App, target: usize, max_rows: usize) -> bool {
+    let current = app.chat.chat_scroll_offset;
+    app.chat.chat_scroll_offset = target;
+    let should_fetch = crate::services::ChatService::should_fetch_more_messages(&app.chat, max_rows);
+    app.chat.chat_scroll_offset = current;
+    app.chat.scroll_to(target, app.prefs.smooth_scrolling);
+    should_fetch
+}
+
+fn handle_scroll_down(app: &mut App) {
+    let page = app.chat.last_visible_messages.unwrap_or(20).max(1);
+    scroll_down_by(app, page);
+}
+
+/// Scroll `amount` messages toward the newest
+pub(crate) fn scroll_down_by(app: &mut App, amount: usize) {
+    let target = app.chat.final_scroll_offset().saturating_sub(amount);
+    app.chat.scroll_to(target, app.prefs.smooth_scrolling);
+}
+
 fn move_server_selection(app: &mut App, direction: i32) {
     if app.chat.servers.is_empty() {
         return;
@@ -558,6 +848,30 @@ fn move_server_selection(app: &mut App, direction: i32) {
     }
 }
 
+/// Select the first or last channel (Servers tab) or DM, in display order
+fn jump_sidebar_selection(app: &mut App, to_end: bool) {
+    match app.chat.sidebar_tab {
+        crate::state::SidebarTab::Servers => {
+            if app.chat.servers.is_empty() {
+                return;
+            }
+            let server_idx = if to_end { app.chat.servers.len() - 1 } else { 0 };
+            let channel_count = app.chat.servers[server_idx].channels.len();
+            app.chat.selected_server = Some(server_idx);
+            app.chat.selected_channel = Some(if to_end { channel_count.saturating_sub(1) } else { 0 });
+        }
+        crate::state::SidebarTab::DMs => {
+            // Same order as the UI: unread first, then by name
+            let target = app.chat.dm_user_list.iter().enumerate()
+                .map(|(idx, u)| (!app.chat.unread_dm_conversations.contains_key(&u.id), u.username.clone(), idx));
+            let target = if to_end { target.max() } else { target.min() };
+            if let Some((_, _, idx)) = target {
+                app.chat.selected_dm_user = Some(idx);
+            }
+        }
+    }
+}
+
 fn move_dm_selection(app: &mut App, direction: i32) {
     if app.chat.dm_user_list.is_empty() {
         return;
@@ -565,7 +879,7 @@ fn move_dm_selection(app: &mut App, direction: i32) {
 
     // Create the same sorted list as the UI to get the display order
     let mut indexed_users: Vec<(usize, &nexus_tui_common::User)> = app.chat.dm_user_list.iter().enumerate().collect();
-    indexed_users.sort_by_key(|(_, u)| (!app.chat.unread_dm_conversations.contains(&u.id), u.username.clone()));
+    indexed_users.sort_by_key(|(_, u)| (!app.chat.unread_dm_conversations.contains_key(&u.id), u.username.clone()));
     
     // Find current display index
     let current_display_idx = if let Some(selected_original_idx) = app.chat.selected_dm_user {
@@ -587,7 +901,7 @@ fn move_dm_selection(app: &mut App, direction: i32) {
     }
 }
 
-fn select_current_sidebar_target(app: &mut App) {
+pub(crate) fn select_current_sidebar_target(app: &mut App) {
     match app.chat.sidebar_tab {
         crate::state::SidebarTab::Servers => {
             if let (Some(s), Some(c)) = (app.chat.selected_server, app.chat.selected_channel) {
@@ -601,12 +915,12 @@ fn select_current_sidebar_target(app: &mut App) {
                     
                     // Clear old messages and set new target
                     app.chat.chat_messages.clear();
-                    app.set_current_chat_target(target);
+                    app.chat.invalidate_message_cache();
+                    app.set_current_chat_target(target.clone());
                     app.chat.reset_scroll_offset();
                     
-                    // Request new data
-                    app.send_to_server(ClientMessage::GetChannelMessages { channel_id, before: None });
-                    app.send_to_server(ClientMessage::GetChannelUserList { channel_id });
+                    // Request new data once the selection settles
+                    app.queue_chat_fetch(target);
                     
                     // Play sound feedback
                     app.sound_manager.play(SoundType::ChangeChannel);
@@ -621,12 +935,12 @@ fn select_current_sidebar_target(app: &mut App) {
                     
                     // Clear old messages and set new target
                     app.chat.dm_messages.clear();
-                    app.set_current_chat_target(target);
+                    app.chat.invalidate_message_cache();
+                    app.set_current_chat_target(target.clone());
                     app.chat.reset_scroll_offset();
                     
-                    // Request new data
-                    app.send_to_server(ClientMessage::GetDirectMessages { user_id, before: None });
-                    app.chat.unread_dm_conversations.remove(&user_id);
+                    // Request new data once the selection settles
+                    app.queue_chat_fetch(target);
                     
                     // Play sound feedback
                     app.sound_manager.play(SoundType::ChangeChannel);
@@ -634,4 +948,13 @@ fn select_current_sidebar_target(app: &mut App) {
             }
         }
     }
-}
\ No newline at end of file
+    app.remember_chat_target();
+}
+
+/// Open a link in the browser, or show it when that isn't possible (e.g. headless over SSH)
+fn open_link(app: &mut App, url: &str) {
+    match crate::browser::open_url(url) {
+        Ok(()) => app.set_notification(format!("Opened {}", url), Some(1500), true),
+        Err(e) => app.set_notification(format!("{}: {}", e, url), Some(6000), false),
+    }
+}
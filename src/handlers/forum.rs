@@ -1,6 +1,7 @@
 use crate::app::App;
 use crate::sound::SoundType;
 use nexus_tui_common::ClientMessage;
+use crate::state::forum::ListFilter;
 use crossterm::event::{KeyCode, KeyEvent};
 
 /// Handle forum-related input (forum list, thread list, post view)
@@ -14,6 +15,10 @@ pub fn handle_forum_input(key: KeyEvent, app: &mut App) {
 }
 
 fn handle_forum_list_input(key: KeyEvent, app: &mut App) {
+    if handle_list_filter_input(key, app, false) {
+        return;
+    }
+    let key = super::vim_list_key(key, app);
     match key.code {
         KeyCode::Down => {
             if !app.forum.forums.is_empty() {
@@ -31,6 +36,13 @@ fn handle_forum_list_input(key: KeyEvent, app: &mut App) {
                 app.forum.forum_list_state.select(Some(next));
             }
         }
+        KeyCode::Home | KeyCode::End => {
+            if !app.forum.forums.is_empty() {
+                app.sound_manager.play(SoundType::ChangeChannel);
+                let target = if key.code == KeyCode::Home { 0 } else { app.forum.forums.len() - 1 };
+                app.forum.forum_list_state.select(Some(target));
+            }
+        }
         KeyCode::Enter => {
             if let Some(idx) = app.forum.forum_list_state.selected() {
                 if let Some(forum) = app.forum.forums.get(idx) {
@@ -39,12 +51,23 @@ fn handle_forum_list_input(key: KeyEvent, app: &mut App) {
                 }
             }
         }
+        KeyCode::Char('/') => {
+            let prior_selection = app.forum.forum_list_state.selected();
+            app.forum.forum_filter = Some(ListFilter { query: String::new(), prior_selection });
+        }
         KeyCode::Char('n') | KeyCode::Char('N') => {
             // Admin-only: Create new forum
-            if let Some(user) = &app.auth.current_user {
-                if user.role == nexus_tui_common::UserRole::Admin {
-                    app.enter_input_mode(crate::state::InputMode::NewForumName);
-                }
+            if app.auth.current_user.as_ref().is_some_and(|user| user.role == nexus_tui_common::UserRole::Admin) {
+                app.enter_input_mode(crate::state::InputMode::NewForumName);
+            } else {
+                app.set_notification("Only admins can create forums", Some(2000), true);
+            }
+        }
+        KeyCode::Char('t') | KeyCode::Char('T') => {
+            // New thread in the highlighted forum without opening it first
+            if let Some(forum_id) = app.forum.forum_list_state.selected().and_then(|idx| app.forum.forums.get(idx)).map(|forum| forum.id) {
+                app.forum.select_forum(forum_id);
+                app.enter_input_mode(crate::state::InputMode::NewThreadTitle);
             }
         }
         KeyCode::Char('d') | KeyCode::Char('D') => {
@@ -70,6 +93,10 @@ fn handle_forum_list_input(key: KeyEvent, app: &mut App) {
 fn handle_thread_list_input(key: KeyEvent, app: &mut App) {
     use crossterm::event::KeyModifiers;
     
+    if handle_list_filter_input(key, app, true) {
+        return;
+    }
+    let key = super::vim_list_key(key, app);
     match key.code {
         KeyCode::Down => {
             if let Some(forum) = app.forum.get_current_forum() {
@@ -91,6 +118,15 @@ fn handle_thread_list_input(key: KeyEvent, app: &mut App) {
                 }
             }
         }
+        KeyCode::Home | KeyCode::End => {
+            if let Some(forum) = app.forum.get_current_forum() {
+                if !forum.threads.is_empty() {
+                    app.sound_manager.play(SoundType::ChangeChannel);
+                    let target = if key.code == KeyCode::Home { 0 } else { forum.threads.len() - 1 };
+                    app.forum.thread_list_state.select(Some(target));
+                }
+            }
+        }
         KeyCode::Enter => {
             if let Some(idx) = app.forum.thread_list_state.selected() {
                 if let Some(forum) = app.forum.get_current_forum() {
@@ -101,6 +137,10 @@ fn handle_thread_list_input(key: KeyEvent, app: &mut App) {
                 }
             }
         }
+        KeyCode::Char('/') => {
+            let prior_selection = app.forum.thread_list_state.selected();
+            app.forum.thread_filter = Some(ListFilter { query: String::new(), prior_selection });
+        }
         KeyCode::Char('n') | KeyCode::Char('N') => {
             app.enter_input_mode(crate::state::InputMode::NewThreadTitle);
         }
@@ -126,9 +166,85 @@ fn handle_thread_list_input(key: KeyEvent, app: &mut App) {
     }
 }
 
+/// Keys while the forum (`threads == false`) or thread list filter is open: typing
+/// narrows the list live with the best match selected, Up/Down move through the
+/// matches, Esc restores the full list and prior selection. Enter closes the filter
+/// and returns false so the list opens the selected entry as usual.
+fn handle_list_filter_input(key: KeyEvent, app: &mut App, threads: bool) -> bool {
+    use crossterm::event::KeyModifiers;
+
+    let forum = &mut app.forum;
+    let (filter, list_state) = if threads {
+        (&mut forum.thread_filter, &mut forum.thread_list_state)
+    } else {
+        (&mut forum.forum_filter, &mut forum.forum_list_state)
+    };
+    let Some(active) = filter.as_mut() else { return false };
+    let mut refilter = false;
+    match key.code {
+        KeyCode::Esc => {
+            list_state.select(active.prior_selection);
+            *filter = None;
+            return true;
+        }
+        KeyCode::Enter => {
+            *filter = None;
+            return false;
+        }
+        KeyCode::Backspace => {
+            active.query.pop();
+            refilter = true;
+        }
+        KeyCode::Char(c) if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+            active.query.push(c);
+            refilter = true;
+        }
+        KeyCode::Up | KeyCode::Down => {}
+        _ => return true,
+    }
+
+    let visible = if threads { app.forum.visible_thread_indices() } else { app.forum.visible_forum_indices() };
+    let list_state = if threads { &mut app.forum.thread_list_state } else { &mut app.forum.forum_list_state };
+    if refilter {
+        list_state.select(visible.first().copied());
+    } else if !visible.is_empty() {
+        app.sound_manager.play(SoundType::ChangeChannel);
+        let pos = list_state.selected().and_then(|sel| visible.iter().position(|&i| i == sel));
+        let next = match (pos, key.code) {
+            (None, _) => 0,
+            (Some(p), KeyCode::Down) => (p + 1) % visible.len(),
+            (Some(p), _) => (p + visible.len() - 1) % visible.len(),
+        };
+        list_state.select(Some(visible[next]));
+    }
+    true
+}
+
+/// Paste into the open forum or thread list filter, narrowing the list as typing would
+pub fn paste_into_filter(text: &str, app: &mut App, threads: bool) {
+    let filter = if threads { &mut app.forum.thread_filter } else { &mut app.forum.forum_filter };
+    let Some(active) = filter.as_mut() else { return };
+    active.query.extend(crate::services::MessageService::flatten_newlines(text).chars().filter(|c| !c.is_control()));
+    let first = if threads { app.forum.visible_thread_indices() } else { app.forum.visible_forum_indices() }.first().copied();
+    let list_state = if threads { &mut app.forum.thread_list_state } else { &mut app.forum.forum_list_state };
+    list_state.select(first);
+}
+
 fn handle_post_view_input(key: KeyEvent, app: &mut App) {
     use crossterm::event::KeyModifiers;
     
+    if let Some(post_id) = app.forum.confirm_delete_post {
+        app.forum.confirm_delete_post = None;
+        if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter) {
+            app.send_to_server(ClientMessage::DeletePost(post_id));
+            app.send_to_server(ClientMessage::GetForums);
+            app.set_notification("Post deletion requested", Some(2000), false);
+        } else {
+            app.sound_manager.play(SoundType::PopupClose);
+        }
+        return;
+    }
+    
     match key.code {
         // Post navigation
         KeyCode::Up => {
@@ -140,12 +256,10 @@ fn handle_post_view_input(key: KeyEvent, app: &mut App) {
             app.sound_manager.play(SoundType::ChangeChannel);
         }
         // Manual scrolling
-        KeyCode::PageUp => {
-            app.forum.scroll_posts(-1, 3); // Scroll up by 3 posts
-            app.sound_manager.play(SoundType::Scroll);
-        }
-        KeyCode::PageDown => {
-            app.forum.scroll_posts(1, 3); // Scroll down by 3 posts
+        KeyCode::PageUp | KeyCode::PageDown => {
+            // Scroll by a page, up for PageUp unless the direction is reversed
+            let direction = if (key.code == KeyCode::PageUp) != app.prefs.reverse_scroll { -1 } else { 1 };
+            app.forum.scroll_posts(direction, app.forum.post_view_height.saturating_sub(2).max(1));
             app.sound_manager.play(SoundType::Scroll);
         }
         // Home/End for quick navigation
@@ -153,7 +267,7 @@ fn handle_post_view_input(key: KeyEvent, app: &mut App) {
             if let Some(thread) = app.forum.get_current_thread() {
                 if !thread.posts.is_empty() {
                     app.forum.selected_post_index = Some(0);
-                    app.forum.scroll_offset = 0;
+                    app.forum.post_scroll = 0;
                     app.forum.selected_reply_index = None;
                     app.sound_manager.play(SoundType::ChangeChannel);
                 }
@@ -205,7 +319,7 @@ fn handle_post_view_input(key: KeyEvent, app: &mut App) {
                     if let Some((idx, _)) = thread.posts.iter().enumerate().find(|(_, p)| p.id == reply_post.id) {
                         app.forum.selected_post_index = Some(idx);
                         app.forum.selected_reply_index = None;
-                        app.forum.scroll_to_post(idx);
+                        app.forum.auto_scroll_to_selected_post();
                         app.sound_manager.play(SoundType::PopupOpen);
                     }
                 }
@@ -235,14 +349,14 @@ fn handle_post_view_input(key: KeyEvent, app: &mut App) {
                 app.enter_input_mode(crate::state::InputMode::NewPostContent);
             }
         }
-        KeyCode::Char('d') | KeyCode::Char('D') if key.modifiers.contains(KeyModifiers::ALT) => {
-            // Admin-only: Delete selected post
-            if let Some(user) = &app.auth.current_user {
-                if user.role == nexus_tui_common::UserRole::Admin {
-                    if let Some(post) = app.forum.get_selected_post() {
-                        app.send_to_server(ClientMessage::DeletePost(post.id));
-                        app.set_notification("Post deletion requested", Some(2000), false);
-                    }
+        KeyCode::Char('d') | KeyCode::Char('D') => {
+            // Delete the selected post (own posts, or any post for admins) after a confirm
+            if let Some(post) = app.forum.get_selected_post() {
+                if crate::state::forum::ForumState::can_delete_post(post, app.auth.current_user.as_ref()) {
+                    app.forum.confirm_delete_post = Some(post.id);
+                    app.sound_manager.play(SoundType::PopupOpen);
+                } else {
+                    app.set_notification("You can only delete your own posts", Some(2000), false);
                 }
             }
         }
@@ -3,12 +3,116 @@ pub mod chat;
 pub mod profile;
 pub mod forum;
 pub mod navigation;
+pub mod mouse;
 
 use crate::app::App;
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// Ctrl+Backspace, or Ctrl+W for terminals that can't report it: delete a word back.
+pub fn is_delete_word_key(key: &KeyEvent) -> bool {
+    key.modifiers.contains(KeyModifiers::CONTROL)
+        && matches!(key.code, KeyCode::Backspace | KeyCode::Char('w'))
+}
+
+pub fn is_paste_key(key: &KeyEvent) -> bool {
+    key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('v')
+}
+
+/// Clipboard text for a paste, or `None` (with a notification) if there's nothing to paste.
+pub fn read_clipboard(app: &mut App) -> Option<String> {
+    match crate::clipboard::get_text() {
+        Ok(text) if !text.is_empty() => Some(text),
+        Ok(_) => {
+            app.set_notification("Clipboard is empty", Some(1500), true);
+            None
+        }
+        Err(e) => {
+            app.set_notification(e, Some(2000), true);
+            None
+        }
+    }
+}
+
+/// Whether keys currently go into a text field. Unless `empty_input_counts`, the chat
+/// and DM inputs only count once something has been typed, so `?` still opens the
+/// legend from an empty input.
+fn is_typing_text(app: &App, empty_input_counts: bool) -> bool {
+    match app.ui.mode {
+        crate::state::AppMode::Login | crate::state::AppMode::Register
+        | crate::state::AppMode::Input | crate::state::AppMode::EditProfile => true,
+        crate::state::AppMode::Chat => match app.chat.chat_focus {
+            crate::state::ChatFocus::Messages => {
+                empty_input_counts || !app.chat.get_current_input().is_empty() || app.chat.emoji_picker.is_some()
+            }
+            crate::state::ChatFocus::DMInput => empty_input_counts || !app.chat.dm_input.is_empty(),
+            crate::state::ChatFocus::Users => app.chat.user_filter.is_some(),
+            crate::state::ChatFocus::Sidebar => false,
+        },
+        crate::state::AppMode::ForumList => app.forum.forum_filter.is_some(),
+        crate::state::AppMode::ThreadList => app.forum.thread_filter.is_some(),
+        _ => false,
+    }
+}
+
+/// Vim-style list keys: `j`/`k` become Down/Up, `G` End and `g g` Home. Only called
+/// from list handlers, so text fields never see the translation. A first `g` waits
+/// for the second (`UiState::pending_g`) and does nothing on its own.
+pub fn vim_list_key(key: KeyEvent, app: &mut App) -> KeyEvent {
+    if !(key.modifiers - KeyModifiers::SHIFT).is_empty() {
+        return key;
+    }
+    let code = match key.code {
+        KeyCode::Char('j') => KeyCode::Down,
+        KeyCode::Char('k') => KeyCode::Up,
+        KeyCode::Char('G') => KeyCode::End,
+        KeyCode::Char('g') if app.ui.pending_g => {
+            app.ui.pending_g = false;
+            KeyCode::Home
+        }
+        KeyCode::Char('g') => {
+            app.ui.pending_g = true;
+            KeyCode::Null
+        }
+        _ => return key,
+    };
+    KeyEvent { code, modifiers: KeyModifiers::NONE, ..key }
+}
+
+/// Text pasted into the terminal (bracketed paste, which is also how dropped files
+/// arrive). It goes straight to the focused field, never through the keybindings;
+/// the popups that sit above the fields don't take text.
+pub fn handle_paste(text: String, app: &mut App) {
+    if app.ui.trust_prompt.is_some() || app.ui.show_server_error || app.ui.show_quit_confirm
+        || app.ui.show_about || app.ui.show_shortcuts
+    {
+        return;
+    }
+    app.notifications.clear_notification();
+    match app.ui.mode {
+        crate::state::AppMode::Login | crate::state::AppMode::Register => auth::paste_text(&text, app),
+        crate::state::AppMode::Chat => chat::paste_text(&text, app),
+        crate::state::AppMode::EditProfile => profile::paste_text(text, app),
+        crate::state::AppMode::ForumList => forum::paste_into_filter(&text, app, false),
+        crate::state::AppMode::ThreadList => forum::paste_into_filter(&text, app, true),
+        crate::state::AppMode::Input => navigation::paste_text(&text, app),
+        _ => {}
+    }
+}
 
 /// Main input handler dispatcher
 pub fn handle_key_event(key: KeyEvent, app: &mut App) {
+    app.ui.note_input();
+    let key = app.keymap.resolve(key, is_typing_text(app, true));
+    // `g g` only counts when nothing comes between the two presses
+    if key.code != KeyCode::Char('g') {
+        app.ui.pending_g = false;
+    }
+    // A certificate waiting for trust sits above the server error it came with
+    if app.ui.trust_prompt.is_some() {
+        handle_trust_prompt_input(key, app);
+        return;
+    }
+
     // Handle server error popup first (highest priority)
     if app.ui.show_server_error {
         handle_server_error_input(key, app);
@@ -21,11 +125,48 @@ pub fn handle_key_event(key: KeyEvent, app: &mut App) {
         return;
     }
 
+    // Any key closes the About popup
+    if app.ui.show_about {
+        app.ui.show_about = false;
+        app.sound_manager.play(crate::sound::SoundType::PopupClose);
+        return;
+    }
+
+    // Any key closes the shortcut legend
+    if app.ui.show_shortcuts {
+        app.ui.show_shortcuts = false;
+        app.sound_manager.play(crate::sound::SoundType::PopupClose);
+        return;
+    }
+
+    // `?` opens the shortcut legend unless it's being typed into a text field
+    if key.code == KeyCode::Char('?') && !is_typing_text(app, false) && app.notifications.current_notification.is_none() {
+        app.ui.show_shortcuts = true;
+        app.sound_manager.play(crate::sound::SoundType::PopupOpen);
+        return;
+    }
+
     // Handle global shortcuts first
     if navigation::handle_global_shortcuts(key, app) {
         return;
     }
 
+    // Esc goes back; a second Esc on the main menu right after asks to quit. Checked
+    // before the notification below, which would otherwise swallow the second press.
+    let previous_esc = app.ui.last_esc_tick.take();
+    if key.code == KeyCode::Esc {
+        let window = app.config.double_esc_window_ticks;
+        if app.prefs.double_esc_quit
+            && app.ui.mode == crate::state::AppMode::MainMenu
+            && previous_esc.is_some_and(|tick| app.ui.tick_count.saturating_sub(tick) <= window)
+        {
+            app.notifications.clear_notification();
+            navigation::open_quit_confirm(app);
+            return;
+        }
+        app.ui.last_esc_tick = Some(app.ui.tick_count);
+    }
+
     // Check if there's an active notification and close it on any key press
     if app.notifications.current_notification.is_some() {
         app.notifications.clear_notification();
@@ -87,14 +228,50 @@ fn handle_quit_confirm_input(key: KeyEvent, app: &mut App) {
     }
 }
 
+/// Accept or reject an untrusted server certificate
+fn handle_trust_prompt_input(key: KeyEvent, app: &mut App) {
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    let accept = match key.code {
+        KeyCode::Left | KeyCode::Right | KeyCode::Tab | KeyCode::BackTab => {
+            app.sound_manager.play(crate::sound::SoundType::Scroll);
+            app.ui.trust_prompt_selected = 1 - app.ui.trust_prompt_selected;
+            return;
+        }
+        KeyCode::Enter => app.ui.trust_prompt_selected == 0,
+        KeyCode::Char('y') | KeyCode::Char('Y') => true,
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => false,
+        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            app.ui.quit();
+            return;
+        }
+        _ => return,
+    };
+    let Some(pending) = app.ui.trust_prompt.take() else { return };
+    app.sound_manager.play(crate::sound::SoundType::PopupClose);
+    if !accept {
+        // The error popup stays; retrying from there asks again
+        return;
+    }
+    match crate::known_hosts::trust(&pending.host, &pending.fingerprint) {
+        Ok(()) => {
+            app.ui.hide_server_error();
+            app.ui.should_retry_connection = true;
+            app.set_notification(format!("Trusted certificate for {}", pending.host), Some(2000), true);
+        }
+        Err(e) => app.set_notification(e, None, false),
+    }
+}
+
 /// Handle server error popup input
 fn handle_server_error_input(key: KeyEvent, app: &mut App) {
     use crossterm::event::{KeyCode, KeyModifiers};
 
     match key.code {
         KeyCode::Enter => {
-            // Request connection retry
+            // Retry now; the automatic backoff starts over from the shortest delay
             app.sound_manager.play(crate::sound::SoundType::PopupClose);
+            app.ui.reset_reconnect();
             app.ui.should_retry_connection = true;
             app.ui.hide_server_error();
         }
@@ -102,6 +279,10 @@ fn handle_server_error_input(key: KeyEvent, app: &mut App) {
             // Allow Ctrl+C to quit the application
             app.ui.quit();
         }
+        KeyCode::F(4) => {
+            // Pick a different server instead
+            app.request_server_switch();
+        }
         // Remove ESC handler - don't allow closing the popup with ESC
         _ => {}
     }
@@ -0,0 +1,131 @@
+use crate::app::App;
+use crate::sound::SoundType;
+use crate::state::ui::{PopupClick, SidebarClick};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::Position;
+
+/// Messages scrolled per wheel notch
+const WHEEL_MESSAGES: usize = 3;
+
+/// Handle mouse input (only when mouse support is enabled in preferences).
+/// Clicks are mapped onto the regions recorded in `click_map` during the last draw.
+pub fn handle_mouse_event(event: MouseEvent, app: &mut App) {
+    if !app.prefs.mouse_support {
+        return;
+    }
+    app.ui.note_input();
+    let pos = Position::new(event.column, event.row);
+    match event.kind {
+        MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+            // Wheel up goes toward older content unless the direction is reversed
+            let older = (event.kind == MouseEventKind::ScrollUp) != app.prefs.reverse_scroll;
+            handle_wheel(app, pos, older);
+        }
+        MouseEventKind::Down(MouseButton::Left) => handle_click(app, pos),
+        _ => {}
+    }
+}
+
+fn handle_wheel(app: &mut App, pos: Position, older: bool) {
+    if has_open_popup(app) {
+        return;
+    }
+    let map = &app.ui.click_map;
+    if app.ui.mode == crate::state::AppMode::Chat && map.message_list.is_some_and(|r| r.contains(pos)) {
+        if older {
+            super::chat::scroll_up_by(app, WHEEL_MESSAGES);
+        } else {
+            super::chat::scroll_down_by(app, WHEEL_MESSAGES);
+        }
+    } else if app.ui.mode == crate::state::AppMode::PostView && map.post_view.is_some_and(|r| r.contains(pos)) {
+        app.forum.scroll_posts(if older { -1 } else { 1 }, 3);
+    }
+}
+
+fn handle_click(app: &mut App, pos: Position) {
+    // Popups take every click while open
+    if app.ui.show_about || app.ui.show_shortcuts || app.profile.show_profile_view_popup {
+        press(app, KeyCode::Esc);
+        return;
+    }
+    if app.ui.show_quit_confirm || app.profile.show_user_actions || app.ui.show_server_invite_selection {
+        match app.ui.click_map.popup_at(pos) {
+            Some(PopupClick::QuitYes) => {
+                app.ui.quit_confirm_selected = 0;
+                press(app, KeyCode::Enter);
+            }
+            Some(PopupClick::QuitNo) => {
+                app.ui.quit_confirm_selected = 1;
+                press(app, KeyCode::Enter);
+            }
+            Some(PopupClick::UserAction(i)) => {
+                app.profile.user_actions_selected = i;
+                press(app, KeyCode::Enter);
+            }
+            Some(PopupClick::ServerInvite(i)) => {
+                app.ui.server_invite_selected = i;
+                press(app, KeyCode::Enter);
+            }
+            None => {}
+        }
+        return;
+    }
+    if has_open_popup(app) || app.ui.mode != crate::state::AppMode::Chat {
+        return;
+    }
+
+    if let Some(click) = app.ui.click_map.sidebar_at(pos) {
+        app.chat.chat_focus = crate::state::ChatFocus::Sidebar;
+        match click {
+            SidebarClick::Server(si) => {
+                app.chat.selected_server = Some(si);
+                app.chat.selected_channel = Some(0);
+            }
+            SidebarClick::Channel(si, ci) => {
+                app.chat.selected_server = Some(si);
+                app.chat.selected_channel = Some(ci);
+            }
+            SidebarClick::Dm(idx) => {
+                app.chat.selected_dm_user = Some(idx);
+            }
+        }
+        // A click lands on its target; there's nothing to wait out
+        super::chat::select_current_sidebar_target(app);
+        app.flush_chat_fetch();
+    } else if let Some(row) = app.ui.click_map.user_at(pos) {
+        // Select the user and open their actions, as Enter would
+        app.chat.chat_focus = crate::state::ChatFocus::Users;
+        app.chat.user_list_state.select(Some(row));
+        press(app, KeyCode::Enter);
+    } else if let Some(tabs) = app.ui.click_map.sidebar_tabs.filter(|r| r.contains(pos)) {
+        let tab = if pos.x < tabs.x + tabs.width / 2 {
+            crate::state::SidebarTab::Servers
+        } else {
+            crate::state::SidebarTab::DMs
+        };
+        app.chat.chat_focus = crate::state::ChatFocus::Sidebar;
+        if app.chat.sidebar_tab != tab {
+            app.chat.sidebar_tab = tab;
+            app.sound_manager.play(SoundType::ChangeChannel);
+            app.select_and_load_first_chat();
+        }
+    } else if app.ui.click_map.message_list.is_some_and(|r| r.contains(pos)) {
+        app.chat.chat_focus = crate::state::ChatFocus::Messages;
+    }
+}
+
+fn has_open_popup(app: &App) -> bool {
+    app.ui.show_server_error
+        || app.ui.show_quit_confirm
+        || app.ui.show_about
+        || app.ui.show_shortcuts
+        || app.ui.show_server_actions
+        || app.ui.show_server_invite_selection
+        || app.profile.show_user_actions
+        || app.profile.show_profile_view_popup
+}
+
+/// Feed a key through the normal key handling, so clicks reuse the keyboard actions
+fn press(app: &mut App, code: KeyCode) {
+    super::handle_key_event(KeyEvent::new(code, KeyModifiers::NONE), app);
+}
@@ -1,17 +1,28 @@
 use crate::app::App;
 use crate::sound::SoundType;
 use crate::desktop_notifications::DesktopNotificationService;
+use crate::handlers::{is_delete_word_key, is_paste_key, read_clipboard};
+use crate::services::MessageService;
 use nexus_tui_common::{ClientMessage, UserColor};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::style::Color;
 
+/// Show the quit confirmation dialog
+pub fn open_quit_confirm(app: &mut App) {
+    app.ui.show_quit_confirm = true;
+    app.ui.quit_confirm_selected = 1; // Default to "No"
+    app.sound_manager.play(SoundType::PopupOpen);
+}
+
 /// Handle global shortcuts that work across all modes
 pub fn handle_global_shortcuts(key: KeyEvent, app: &mut App) -> bool {
     match key.code {
         KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-            // Show quit confirmation dialog
-            app.ui.show_quit_confirm = true;
-            app.ui.quit_confirm_selected = 1; // Default to "No"
+            open_quit_confirm(app);
+            return true;
+        }
+        KeyCode::F(1) => {
+            app.ui.show_about = true;
             app.sound_manager.play(SoundType::PopupOpen);
             return true;
         }
@@ -21,6 +32,10 @@ pub fn handle_global_shortcuts(key: KeyEvent, app: &mut App) -> bool {
             app.sound_manager.play(SoundType::PopupOpen);
             return true;
         }
+        KeyCode::F(4) => {
+            app.request_server_switch();
+            return true;
+        }
         KeyCode::F(5) => {
             if app.ui.mode == crate::state::AppMode::Chat {
                 app.ui.show_server_actions = true;
@@ -29,32 +44,49 @@ pub fn handle_global_shortcuts(key: KeyEvent, app: &mut App) -> bool {
             }
         }
         KeyCode::F(6) => {
-            if app.ui.mode == crate::state::AppMode::Chat {
-                app.set_notification("Refreshing notifications...", Some(500), true);
-                app.send_to_server(ClientMessage::GetNotifications { before: None });
+            // Opens the notification center, or refreshes it when already there
+            if app.auth.current_user.is_some() && app.ui.mode != crate::state::AppMode::Input {
+                app.open_notification_center();
+                app.sound_manager.play(SoundType::PopupOpen);
+                return true;
+            }
+        }
+        KeyCode::F(3) => {
+            if app.ui.mode != crate::state::AppMode::Input {
+                app.open_appearance_picker();
+                app.sound_manager.play(SoundType::PopupOpen);
                 return true;
             }
         }
         KeyCode::F(7) => {
+            // While logged in the choice belongs to the account; otherwise it's the global default
             app.background_manager.cycle_background();
-            let bg_name = app.background_manager.get_background_name();
-            app.prefs.background_name = bg_name.to_string();
-            app.prefs_dirty = true;
-            app.prefs_dirty_last_update = Some(std::time::Instant::now());
-            app.set_notification(format!("Background changed to: {}", bg_name.to_uppercase()), Some(2000), true);
+            let scope = app.remember_background();
+            let bg_name = app.background_manager.get_background_name().to_uppercase();
+            app.set_notification(format!("Background changed to: {}{}", bg_name, scope), Some(2000), true);
             app.sound_manager.play(SoundType::ChangeChannel);
             return true;
         }
         KeyCode::F(8) => {
             app.theme_manager.cycle_theme();
-            let theme_name = app.theme_manager.get_theme_name();
-            app.prefs.theme_name = theme_name.to_string();
-            app.prefs_dirty = true;
-            app.prefs_dirty_last_update = Some(std::time::Instant::now());
-            app.set_notification(format!("Theme changed to: {}", theme_name.to_uppercase()), Some(2000), true);
+            let scope = app.remember_theme();
+            let theme_name = app.theme_manager.get_theme_name().to_uppercase();
+            app.set_notification(format!("Theme changed to: {}{}", theme_name, scope), Some(2000), true);
             app.sound_manager.play(SoundType::ChangeChannel);
             return true;
         }
+        KeyCode::F(9) => {
+            app.cycle_dnd();
+            return true;
+        }
+        KeyCode::F(12) => {
+            // Debug overlay; opening it asks the server for fresh cache numbers
+            app.ui.show_debug_overlay = !app.ui.show_debug_overlay;
+            if app.ui.show_debug_overlay && app.auth.is_logged_in() {
+                app.send_to_server(ClientMessage::GetCacheStats);
+            }
+            return true;
+        }
         _ => {}
     }
     false
@@ -67,6 +99,9 @@ pub fn handle_general_navigation(key: KeyEvent, app: &mut App) {
         crate::state::AppMode::Settings => handle_settings_input(key, app),
         crate::state::AppMode::ColorPicker => handle_color_picker_input(key, app),
         crate::state::AppMode::Preferences => handle_preferences_input(key, app),
+        crate::state::AppMode::NotificationCenter => handle_notification_center_input(key, app),
+        crate::state::AppMode::ServerPicker => handle_server_picker_input(key, app),
+        crate::state::AppMode::AppearancePicker => handle_appearance_picker_input(key, app),
         _ => {}
     }
 }
@@ -178,27 +213,76 @@ pub fn handle_input_mode(key: KeyEvent, app: &mut App) {
                         app.send_to_server(ClientMessage::UpdatePassword(input));
                         app.ui.set_mode(crate::state::AppMode::Settings);
                     }
+                    SearchMessages => {
+                        // Keep the results; n/N cycle through them from the chat
+                        app.ui.input_return_mode = None;
+                        app.ui.set_mode(crate::state::AppMode::Chat);
+                        let count = app.chat.search_matches.len();
+                        if !app.chat.search_query.is_empty() {
+                            let mut text = match count {
+                                0 => "No matches".to_string(),
+                                1 => "1 match".to_string(),
+                                n => format!("{} matches [n/N]", n),
+                            };
+                            if !app.chat.history_complete() {
+                                text.push_str(" (scroll up to load older messages)");
+                            }
+                            app.set_notification(text, Some(2000), true);
+                        }
+                    }
                     _ => {
                         app.ui.set_mode(crate::state::AppMode::MainMenu);
                     }
                 }
             }
         }
+        _ if is_paste_key(&key) => {
+            if let Some(text) = read_clipboard(app) {
+                paste_text(&text, app);
+            }
+        }
+        _ if is_delete_word_key(&key) => {
+            MessageService::delete_word_back(&mut app.auth.current_input);
+            update_live_search(app);
+        }
         KeyCode::Char(c) => {
             app.auth.current_input.push(c);
+            update_live_search(app);
         }
         KeyCode::Backspace => {
             app.auth.current_input.pop();
+            update_live_search(app);
         }
         KeyCode::Esc => {
+            if app.auth.input_mode == Some(SearchMessages) {
+                app.auth.clear_inputs();
+                app.chat.clear_search();
+                app.chat.clear_message_selection();
+            }
             app.auth.input_mode = None;
-            app.ui.set_mode(crate::state::AppMode::MainMenu);
+            let return_mode = app.ui.input_return_mode.take().unwrap_or(crate::state::AppMode::MainMenu);
+            app.ui.set_mode(return_mode);
         }
         _ => {}
     }
 }
 
+/// Message search filters as you type
+/// Paste into the single-line input of Input mode
+pub fn paste_text(text: &str, app: &mut App) {
+    app.auth.current_input.push_str(&MessageService::flatten_newlines(text));
+    update_live_search(app);
+}
+
+fn update_live_search(app: &mut App) {
+    if app.auth.input_mode == Some(crate::state::InputMode::SearchMessages) {
+        app.chat.search_query = app.auth.current_input.clone();
+        app.update_message_search();
+    }
+}
+
 fn handle_main_menu_input(key: KeyEvent, app: &mut App) {
+    let key = super::vim_list_key(key, app);
     match key.code {
         KeyCode::Down => {
             app.sound_manager.play(SoundType::Scroll);
@@ -210,6 +294,10 @@ fn handle_main_menu_input(key: KeyEvent, app: &mut App) {
             let current = app.ui.main_menu_state.selected().unwrap_or(0);
             app.ui.main_menu_state.select(Some((current + 3) % 4));
         }
+        KeyCode::Home | KeyCode::End => {
+            app.sound_manager.play(SoundType::Scroll);
+            app.ui.main_menu_state.select(Some(if key.code == KeyCode::Home { 0 } else { 3 }));
+        }
         KeyCode::Enter => {
             if let Some(selection) = app.ui.main_menu_state.selected() {
                 match selection {
@@ -219,30 +307,41 @@ fn handle_main_menu_input(key: KeyEvent, app: &mut App) {
                         app.forum.forum_list_state.select(Some(0));
                     }
                     1 => {
-                        app.ui.set_mode(crate::state::AppMode::Chat);
+                        app.enter_chat();
                         app.auth.current_input.clear();
-                        app.send_to_server(ClientMessage::GetServers);
-                        app.send_to_server(ClientMessage::GetDMUserList);
                     }
                     2 => {
                         app.ui.set_mode(crate::state::AppMode::Settings);
                         app.ui.settings_list_state.select(Some(0));
                     }
                     3 => {
+                        app.save_unread_state();
+                        // Nothing queued offline should go out after signing out
+                        app.outbox.clear();
+                        app.chat.pending_sends.clear();
+                        app.chat.invalidate_message_cache();
                         app.send_to_server(ClientMessage::Logout);
                         app.auth.logout();
+                        app.apply_appearance();
                         app.ui.set_mode(crate::state::AppMode::Login);
                         app.auth.clear_inputs();
+                        app.prefill_login();
                     }
                     _ => {}
                 }
             }
         }
+        // The second press is handled in `handle_key_event`; this one just says so
+        KeyCode::Esc if app.prefs.double_esc_quit => {
+            let ms = app.config.double_esc_window_ticks * 50;
+            app.set_notification("Press Esc again to quit", Some(ms), true);
+        }
         _ => {}
     }
 }
 
 fn handle_settings_input(key: KeyEvent, app: &mut App) {
+    let key = super::vim_list_key(key, app);
     match key.code {
         KeyCode::Down => {
             app.sound_manager.play(SoundType::Scroll);
@@ -256,6 +355,11 @@ fn handle_settings_input(key: KeyEvent, app: &mut App) {
             let current = app.ui.settings_list_state.selected().unwrap_or(0);
             app.ui.settings_list_state.select(Some((current + max - 1) % max));
         }
+        KeyCode::Home | KeyCode::End => {
+            app.sound_manager.play(SoundType::Scroll);
+            let max = if app.auth.is_logged_in() { 5 } else { 3 };
+            app.ui.settings_list_state.select(Some(if key.code == KeyCode::Home { 0 } else { max - 1 }));
+        }
         KeyCode::Enter => {
             if let Some(selection) = app.ui.settings_list_state.selected() {
                 match selection {
@@ -346,21 +450,44 @@ fn handle_color_picker_input(key: KeyEvent, app: &mut App) {
     }
 }
 
+/// Number of entries on the Preferences screen
+const PREFERENCE_COUNT: usize = 25;
+
 fn handle_preferences_input(key: KeyEvent, app: &mut App) {
     match key.code {
         KeyCode::Down => {
             app.sound_manager.play(SoundType::Scroll);
-            app.ui.preferences_selected = (app.ui.preferences_selected + 1) % 3; // 3 preferences total
+            app.ui.preferences_selected = (app.ui.preferences_selected + 1) % PREFERENCE_COUNT;
         }
         KeyCode::Up => {
             app.sound_manager.play(SoundType::Scroll);
-            app.ui.preferences_selected = if app.ui.preferences_selected == 0 { 2 } else { app.ui.preferences_selected - 1 };
+            app.ui.preferences_selected = if app.ui.preferences_selected == 0 { PREFERENCE_COUNT - 1 } else { app.ui.preferences_selected - 1 };
+        }
+        KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Char('-') => {
+            // Volume steps of 10; changing the volume also unmutes
+            app.prefs.sound_volume = if key.code == KeyCode::Char('-') {
+                app.prefs.sound_volume.saturating_sub(10)
+            } else {
+                (app.prefs.sound_volume + 10).min(100)
+            };
+            app.prefs.sound_muted = false;
+            app.apply_sound_settings();
+            app.sound_manager.play(SoundType::Scroll);
+            app.prefs_dirty = true;
+            app.prefs_dirty_last_update = Some(std::time::Instant::now());
+        }
+        KeyCode::Char('m') => {
+            app.prefs.sound_muted = !app.prefs.sound_muted;
+            app.apply_sound_settings();
+            app.prefs_dirty = true;
+            app.prefs_dirty_last_update = Some(std::time::Instant::now());
         }
         KeyCode::Char(' ') | KeyCode::Enter => {
             app.sound_manager.play(SoundType::Save);
             match app.ui.preferences_selected {
                 0 => {
                     app.prefs.sound_effects_enabled = !app.prefs.sound_effects_enabled;
+                    app.apply_sound_settings();
                 }
                 1 => {
                     app.prefs.minimal_banner_glitch_enabled = !app.prefs.minimal_banner_glitch_enabled;
@@ -371,6 +498,95 @@ fn handle_preferences_input(key: KeyEvent, app: &mut App) {
                         DesktopNotificationService::show_info_notification("Desktop notifications enabled!");
                     }
                 }
+                3 => {
+                    app.prefs.user_list_sort = app.prefs.user_list_sort.next();
+                    app.resort_user_list();
+                }
+                4 => {
+                    app.prefs.offline_users = app.prefs.offline_users.next();
+                    app.resort_user_list();
+                }
+                5 => {
+                    app.prefs.auto_reconnect_on_startup = !app.prefs.auto_reconnect_on_startup;
+                }
+                6 => {
+                    app.prefs.chat_entry_focus = app.prefs.chat_entry_focus.next();
+                }
+                7 => {
+                    app.prefs.wide_emoji = !app.prefs.wide_emoji;
+                }
+                8 => {
+                    app.prefs.message_density = app.prefs.message_density.next();
+                }
+                9 => {
+                    app.prefs.smooth_scrolling = !app.prefs.smooth_scrolling;
+                }
+                10 => {
+                    app.prefs.reverse_scroll = !app.prefs.reverse_scroll;
+                }
+                11 => {
+                    app.prefs.mouse_support = !app.prefs.mouse_support;
+                }
+                12 => {
+                    // Choosing a protocol here replaces any --image-protocol override
+                    app.prefs.image_protocol = app.prefs.image_protocol.next();
+                    app.ui.image_protocol_override = None;
+                    app.apply_image_protocol();
+                }
+                13 => {
+                    app.prefs.pause_background_when_idle = !app.prefs.pause_background_when_idle;
+                }
+                14 => {
+                    app.prefs.timestamp_position = app.prefs.timestamp_position.next();
+                }
+                15 => {
+                    app.prefs.double_esc_quit = !app.prefs.double_esc_quit;
+                }
+                16 => {
+                    // Turning it off forgets the stored name, for shared machines
+                    app.prefs.remember_username = !app.prefs.remember_username;
+                    if !app.prefs.remember_username {
+                        app.prefs.last_username = None;
+                    }
+                }
+                17 => {
+                    app.prefs.sound_muted = !app.prefs.sound_muted;
+                    app.apply_sound_settings();
+                }
+                18 => {
+                    app.prefs.mark_read_mode = app.prefs.mark_read_mode.next();
+                }
+                19 => {
+                    app.cycle_dnd();
+                }
+                20 => {
+                    app.prefs.max_fps = match app.prefs.max_fps {
+                        f if f > 15 => 15,
+                        f if f > 10 => 10,
+                        f if f > 5 => 5,
+                        _ => 20,
+                    };
+                }
+                21 => {
+                    app.prefs.avatar_pixel_size = match app.prefs.avatar_pixel_size {
+                        p if p < 24 => 24,
+                        p if p < 32 => 32,
+                        p if p < 48 => 48,
+                        _ => 16,
+                    };
+                }
+                22 => {
+                    app.prefs.text_only_avatars = !app.prefs.text_only_avatars;
+                }
+                23 => {
+                    app.prefs.time_format_24h = !app.prefs.time_format_24h;
+                }
+                24 => {
+                    match app.clear_cache() {
+                        Ok(()) => app.set_notification("Image cache cleared", Some(2000), true),
+                        Err(e) => app.set_notification(e, Some(3000), false),
+                    }
+                }
                 _ => {}
             }
             app.prefs_dirty = true;
@@ -391,4 +607,114 @@ fn handle_preferences_input(key: KeyEvent, app: &mut App) {
         }
         _ => {}
     }
-}
\ No newline at end of file
+}
+fn handle_notification_center_input(key: KeyEvent, app: &mut App) {
+    let key = super::vim_list_key(key, app);
+    let count = app.notifications.notifications.len();
+    let current = app.notifications.list_state.selected();
+    let target = match key.code {
+        KeyCode::Esc => {
+            app.sound_manager.play(SoundType::PopupClose);
+            app.close_notification_center();
+            return;
+        }
+        KeyCode::Enter => {
+            app.open_selected_notification();
+            return;
+        }
+        KeyCode::Char('r') | KeyCode::Char('R') => {
+            app.open_notification_center();
+            app.set_notification("Refreshing notifications...", Some(500), true);
+            return;
+        }
+        KeyCode::Char('a') | KeyCode::Char('A') => {
+            app.mark_all_notifications_read();
+            app.sound_manager.play(SoundType::Save);
+            return;
+        }
+        _ if count == 0 => return,
+        KeyCode::Down => current.map_or(0, |i| (i + 1).min(count - 1)),
+        KeyCode::Up => current.map_or(0, |i| i.saturating_sub(1)),
+        KeyCode::PageDown => current.map_or(0, |i| (i + 10).min(count - 1)),
+        KeyCode::PageUp => current.map_or(0, |i| i.saturating_sub(10)),
+        KeyCode::Home => 0,
+        KeyCode::End => count - 1,
+        _ => return,
+    };
+    if Some(target) != current {
+        app.sound_manager.play(SoundType::Scroll);
+        app.notifications.list_state.select(Some(target));
+        app.mark_selected_notification_read();
+    }
+    // Moving onto the last notification loads the next page
+    if target == count - 1 && matches!(key.code, KeyCode::Down | KeyCode::PageDown | KeyCode::End) {
+        app.request_older_notifications();
+    }
+}
+
+fn handle_server_picker_input(key: KeyEvent, app: &mut App) {
+    let key = super::vim_list_key(key, app);
+    let count = app.ui.server_profiles.len();
+    if count == 0 {
+        return;
+    }
+    let current = app.ui.server_picker_state.selected().unwrap_or(0);
+    match key.code {
+        KeyCode::Down => {
+            app.sound_manager.play(SoundType::Scroll);
+            app.ui.server_picker_state.select(Some((current + 1) % count));
+        }
+        KeyCode::Up => {
+            app.sound_manager.play(SoundType::Scroll);
+            app.ui.server_picker_state.select(Some((current + count - 1) % count));
+        }
+        KeyCode::Home | KeyCode::End => {
+            app.ui.server_picker_state.select(Some(if key.code == KeyCode::Home { 0 } else { count - 1 }));
+        }
+        KeyCode::Enter => {
+            app.sound_manager.play(SoundType::Select);
+            app.ui.connect_profile = Some(current);
+        }
+        KeyCode::Esc => app.ui.quit(),
+        _ => {}
+    }
+}
+
+fn handle_appearance_picker_input(key: KeyEvent, app: &mut App) {
+    let key = super::vim_list_key(key, app);
+    match key.code {
+        KeyCode::Esc => {
+            app.sound_manager.play(SoundType::PopupClose);
+            app.cancel_appearance_picker();
+        }
+        KeyCode::Enter => {
+            app.sound_manager.play(SoundType::Save);
+            app.commit_appearance();
+        }
+        KeyCode::Left | KeyCode::Right | KeyCode::Tab | KeyCode::BackTab => {
+            app.sound_manager.play(SoundType::Scroll);
+            app.ui.appearance_themes_focused = !app.ui.appearance_themes_focused;
+        }
+        KeyCode::Up | KeyCode::Down | KeyCode::Home | KeyCode::End => {
+            let (count, state) = if app.ui.appearance_themes_focused {
+                (app.theme_manager.names().len(), &mut app.ui.appearance_themes)
+            } else {
+                (app.background_manager.names().len(), &mut app.ui.appearance_backgrounds)
+            };
+            if count == 0 {
+                return;
+            }
+            let current = state.selected().unwrap_or(0);
+            let target = match key.code {
+                KeyCode::Up => (current + count - 1) % count,
+                KeyCode::Down => (current + 1) % count,
+                KeyCode::Home => 0,
+                _ => count - 1,
+            };
+            state.select(Some(target));
+            app.sound_manager.play(SoundType::Scroll);
+            app.preview_appearance();
+        }
+        _ => {}
+    }
+}
@@ -1,11 +1,18 @@
 use crate::app::App;
 use crate::sound::SoundType;
+use crate::handlers::{is_delete_word_key, is_paste_key, read_clipboard};
+use crate::services::{ImageService, MessageService, ProfileService};
 use crossterm::event::{KeyCode, KeyEvent};
 
 /// Handle profile editing input
 pub fn handle_profile_edit_input(key: KeyEvent, app: &mut App) {
     use crate::state::ProfileEditFocus::*;
     
+    if app.profile.downscale_prompt.is_some() {
+        handle_downscale_prompt_input(key, app);
+        return;
+    }
+
     match key.code {
         KeyCode::Tab | KeyCode::Down => {
             app.profile.profile_edit_focus = match app.profile.profile_edit_focus {
@@ -68,6 +75,24 @@ pub fn handle_profile_edit_input(key: KeyEvent, app: &mut App) {
         KeyCode::Esc => {
             app.ui.set_mode(crate::state::AppMode::Settings);
         }
+        _ if is_paste_key(&key) => {
+            if let Some(text) = read_clipboard(app) {
+                paste_text(text, app);
+            }
+        }
+        _ if is_delete_word_key(&key) => {
+            let field = match app.profile.profile_edit_focus {
+                Bio => &mut app.profile.edit_bio,
+                Url1 => &mut app.profile.edit_url1,
+                Url2 => &mut app.profile.edit_url2,
+                Url3 => &mut app.profile.edit_url3,
+                Location => &mut app.profile.edit_location,
+                ProfilePic => &mut app.profile.edit_profile_pic,
+                CoverBanner => &mut app.profile.edit_cover_banner,
+                _ => return,
+            };
+            MessageService::delete_word_back(field);
+        }
         KeyCode::Char(c) => {
             match app.profile.profile_edit_focus {
                 Bio => app.profile.edit_bio.push(c),
@@ -94,4 +119,64 @@ pub fn handle_profile_edit_input(key: KeyEvent, app: &mut App) {
         }
         _ => {}
     }
-}
\ No newline at end of file
+}
+
+/// Put `path` in the focused image field and report whether it's usable
+fn set_image_path(app: &mut App, path: String) {
+    match ImageService::validate_image_data(&path) {
+        Ok(()) => {
+            let size_kb = std::fs::metadata(&path).map(|m| m.len() / 1024).unwrap_or(0);
+            app.profile.profile_edit_error = None;
+            app.set_notification(format!("Image OK ({} KB)", size_kb), Some(1500), true);
+        }
+        Err(e) => {
+            app.profile.profile_edit_error = Some(e.to_string());
+            app.sound_manager.play(SoundType::Error);
+        }
+    }
+    if app.profile.profile_edit_focus == crate::state::ProfileEditFocus::ProfilePic {
+        app.profile.edit_profile_pic = path;
+    } else {
+        app.profile.edit_cover_banner = path;
+    }
+}
+
+/// Downscale dialog: digits edit the size, Enter downscales, Esc goes back to editing
+fn handle_downscale_prompt_input(key: KeyEvent, app: &mut App) {
+    let Some(prompt) = app.profile.downscale_prompt.as_mut() else { return };
+    match key.code {
+        KeyCode::Enter => app.apply_image_downscale(),
+        KeyCode::Esc => app.profile.downscale_prompt = None,
+        KeyCode::Backspace => { prompt.max_dimension.pop(); }
+        KeyCode::Char(c) if c.is_ascii_digit() && prompt.max_dimension.len() < 5 => prompt.max_dimension.push(c),
+        _ => {}
+    }
+}
+
+/// Paste into the focused profile field
+pub fn paste_text(text: String, app: &mut App) {
+    use crate::state::ProfileEditFocus::*;
+    
+    if app.profile.downscale_prompt.is_some() {
+        return;
+    }
+    // A pasted or dropped image file replaces the field and is checked right away
+    if matches!(app.profile.profile_edit_focus, ProfilePic | CoverBanner) {
+        if let Some(path) = ProfileService::pasted_file_path(&text) {
+            set_image_path(app, path);
+            return;
+        }
+    }
+    // The bio is multi-line; every other field is a single line
+    let (field, text) = match app.profile.profile_edit_focus {
+        Bio => (&mut app.profile.edit_bio, text),
+        Url1 => (&mut app.profile.edit_url1, MessageService::flatten_newlines(&text)),
+        Url2 => (&mut app.profile.edit_url2, MessageService::flatten_newlines(&text)),
+        Url3 => (&mut app.profile.edit_url3, MessageService::flatten_newlines(&text)),
+        Location => (&mut app.profile.edit_location, MessageService::flatten_newlines(&text)),
+        ProfilePic => (&mut app.profile.edit_profile_pic, MessageService::flatten_newlines(&text)),
+        CoverBanner => (&mut app.profile.edit_cover_banner, MessageService::flatten_newlines(&text)),
+        _ => return,
+    };
+    field.push_str(&text);
+}
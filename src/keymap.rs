@@ -0,0 +1,343 @@
+// Custom keybindings from ~/.nexus_keybindings.toml. A bound key is translated into the
+// key its action uses by default, so the handlers keep matching the default keys and
+// the defaults keep working alongside the custom ones.
+//
+//     [bindings]
+//     up = "k"
+//     down = ["j", "ctrl+n"]
+//     quit = "ctrl+q"
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+/// Logical actions that can be rebound
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    About,
+    Preferences,
+    Shortcuts,
+    ServerActions,
+    SwitchServer,
+    RefreshNotifications,
+    CycleBackground,
+    CycleTheme,
+    ToggleDnd,
+    NextFocus,
+    PrevFocus,
+    Up,
+    Down,
+    Left,
+    Right,
+    PageUp,
+    PageDown,
+    Home,
+    End,
+    JumpToLatest,
+    /// Enter: select, submit or send
+    Confirm,
+    /// Esc: close, cancel or go back
+    Back,
+    ToggleUserList,
+    Reply,
+    CopyMessage,
+    OpenLink,
+    Search,
+    EmojiPicker,
+}
+
+impl Action {
+    pub const ALL: &'static [Action] = &[
+        Action::Quit,
+        Action::About,
+        Action::Preferences,
+        Action::Shortcuts,
+        Action::ServerActions,
+        Action::SwitchServer,
+        Action::RefreshNotifications,
+        Action::CycleBackground,
+        Action::CycleTheme,
+        Action::ToggleDnd,
+        Action::NextFocus,
+        Action::PrevFocus,
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::PageUp,
+        Action::PageDown,
+        Action::Home,
+        Action::End,
+        Action::JumpToLatest,
+        Action::Confirm,
+        Action::Back,
+        Action::ToggleUserList,
+        Action::Reply,
+        Action::CopyMessage,
+        Action::OpenLink,
+        Action::Search,
+        Action::EmojiPicker,
+    ];
+
+    /// Name used in the keybindings file
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::About => "about",
+            Action::Preferences => "preferences",
+            Action::Shortcuts => "shortcuts",
+            Action::ServerActions => "server-actions",
+            Action::SwitchServer => "switch-server",
+            Action::RefreshNotifications => "refresh-notifications",
+            Action::CycleBackground => "cycle-background",
+            Action::CycleTheme => "cycle-theme",
+            Action::ToggleDnd => "toggle-dnd",
+            Action::NextFocus => "next-focus",
+            Action::PrevFocus => "prev-focus",
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::PageUp => "scroll-up",
+            Action::PageDown => "scroll-down",
+            Action::Home => "home",
+            Action::End => "end",
+            Action::JumpToLatest => "jump-to-latest",
+            Action::Confirm => "send",
+            Action::Back => "back",
+            Action::ToggleUserList => "toggle-user-list",
+            Action::Reply => "reply",
+            Action::CopyMessage => "copy-message",
+            Action::OpenLink => "open-link",
+            Action::Search => "search",
+            Action::EmojiPicker => "emoji-picker",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|action| action.name() == name)
+    }
+
+    /// The key the handlers match for this action
+    pub fn default_key(&self) -> (KeyCode, KeyModifiers) {
+        let none = KeyModifiers::NONE;
+        match self {
+            Action::Quit => (KeyCode::Char('c'), KeyModifiers::CONTROL),
+            Action::About => (KeyCode::F(1), none),
+            Action::Preferences => (KeyCode::F(2), none),
+            Action::Shortcuts => (KeyCode::Char('?'), none),
+            Action::ServerActions => (KeyCode::F(5), none),
+            Action::SwitchServer => (KeyCode::F(4), none),
+            Action::RefreshNotifications => (KeyCode::F(6), none),
+            Action::CycleBackground => (KeyCode::F(7), none),
+            Action::CycleTheme => (KeyCode::F(8), none),
+            Action::ToggleDnd => (KeyCode::F(9), none),
+            Action::NextFocus => (KeyCode::Tab, none),
+            Action::PrevFocus => (KeyCode::BackTab, none),
+            Action::Up => (KeyCode::Up, none),
+            Action::Down => (KeyCode::Down, none),
+            Action::Left => (KeyCode::Left, none),
+            Action::Right => (KeyCode::Right, none),
+            Action::PageUp => (KeyCode::PageUp, none),
+            Action::PageDown => (KeyCode::PageDown, none),
+            Action::Home => (KeyCode::Home, none),
+            Action::End => (KeyCode::End, none),
+            Action::JumpToLatest => (KeyCode::End, KeyModifiers::CONTROL),
+            Action::Confirm => (KeyCode::Enter, none),
+            Action::Back => (KeyCode::Esc, none),
+            Action::ToggleUserList => (KeyCode::Char('u'), KeyModifiers::CONTROL),
+            Action::Reply => (KeyCode::Char('r'), none),
+            Action::CopyMessage => (KeyCode::Char('y'), none),
+            Action::OpenLink => (KeyCode::Char('o'), none),
+            Action::Search => (KeyCode::Char('/'), none),
+            Action::EmojiPicker => (KeyCode::Char('e'), KeyModifiers::CONTROL),
+        }
+    }
+}
+
+/// `[bindings]` table: action name -> one key or a list of keys
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct KeybindingsFile {
+    bindings: HashMap<String, Keys>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Keys {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Custom keys and the action each one triggers
+#[derive(Debug, Default)]
+pub struct KeyMap {
+    bindings: HashMap<(KeyCode, KeyModifiers), Action>,
+}
+
+impl KeyMap {
+    pub fn config_path() -> PathBuf {
+        crate::global_prefs::GlobalPrefs::config_file("keybindings.toml")
+    }
+
+    /// Load the keybindings file. A missing file just means the defaults; anything
+    /// wrong with it (syntax, unknown actions or keys, conflicts) comes back as messages.
+    pub fn load() -> (Self, Vec<String>) {
+        match fs::read_to_string(Self::config_path()) {
+            Ok(text) => Self::from_toml(&text),
+            Err(_) => (Self::default(), Vec::new()),
+        }
+    }
+
+    pub fn from_toml(text: &str) -> (Self, Vec<String>) {
+        let mut problems = Vec::new();
+        let file: KeybindingsFile = match toml::from_str(text) {
+            Ok(file) => file,
+            Err(e) => return (Self::default(), vec![format!("invalid keybindings file: {}", e.message())]),
+        };
+        let mut actions: Vec<(Action, Vec<String>)> = Vec::new();
+        for (name, keys) in file.bindings {
+            match Action::from_name(&name) {
+                Some(action) => actions.push((action, match keys {
+                    Keys::One(key) => vec![key],
+                    Keys::Many(keys) => keys,
+                })),
+                None => problems.push(format!("unknown action '{}'", name)),
+            }
+        }
+        // Bind in a fixed order so conflicts resolve the same way every time
+        actions.sort_by_key(|(action, _)| Action::ALL.iter().position(|a| a == action));
+
+        let mut map = Self::default();
+        for (action, keys) in actions {
+            for text in keys {
+                let Some(key) = parse_key(&text) else {
+                    problems.push(format!("unknown key '{}' for {}", text, action.name()));
+                    continue;
+                };
+                if key == action.default_key() {
+                    continue;
+                }
+                if let Some(other) = Action::ALL.iter().find(|a| a.default_key() == key) {
+                    problems.push(format!("'{}' for {} conflicts with the default key of {}", text, action.name(), other.name()));
+                } else if let Some(other) = map.bindings.get(&key) {
+                    problems.push(format!("'{}' is bound to both {} and {}", text, other.name(), action.name()));
+                } else {
+                    map.bindings.insert(key, action);
+                }
+            }
+        }
+        (map, problems)
+    }
+
+    /// Translate a bound key into its action's default key. While `typing`, plain
+    /// character keys are left alone so they still go into the text field.
+    pub fn resolve(&self, key: KeyEvent, typing: bool) -> KeyEvent {
+        let (code, modifiers) = normalize(key.code, key.modifiers);
+        if typing && matches!(code, KeyCode::Char(_)) && modifiers.is_empty() {
+            return key;
+        }
+        match self.bindings.get(&(code, modifiers)) {
+            Some(action) => {
+                let (code, modifiers) = action.default_key();
+                KeyEvent { code, modifiers, ..key }
+            }
+            None => key,
+        }
+    }
+}
+
+/// Shift is implied by the character itself (and by BackTab), so it's dropped for matching
+fn normalize(code: KeyCode, modifiers: KeyModifiers) -> (KeyCode, KeyModifiers) {
+    match code {
+        KeyCode::Char(_) | KeyCode::BackTab => (code, modifiers - KeyModifiers::SHIFT),
+        _ => (code, modifiers),
+    }
+}
+
+/// Parse a key like `j`, `ctrl+k`, `alt+enter`, `shift+tab`, `pagedown` or `f5`
+pub fn parse_key(text: &str) -> Option<(KeyCode, KeyModifiers)> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut parts: Vec<&str> = text.trim().split('+').collect();
+    let key = parts.pop()?;
+    for part in parts {
+        modifiers |= match part.to_ascii_lowercase().as_str() {
+            "ctrl" | "control" => KeyModifiers::CONTROL,
+            "alt" | "meta" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return None,
+        };
+    }
+    let shift = modifiers.contains(KeyModifiers::SHIFT);
+    let code = match key.to_ascii_lowercase().as_str() {
+        "enter" | "return" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" if shift => KeyCode::BackTab,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "space" => KeyCode::Char(' '),
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        lower => {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if shift => KeyCode::Char(c.to_ascii_uppercase()),
+                (Some(c), None) => KeyCode::Char(c),
+                _ => match lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return None,
+                },
+            }
+        }
+    };
+    Some(normalize(code, modifiers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shift_tab_is_back_tab() {
+        assert_eq!(parse_key("shift+tab"), Some((KeyCode::BackTab, KeyModifiers::NONE)));
+        assert_eq!(parse_key("backtab"), Some((KeyCode::BackTab, KeyModifiers::NONE)));
+        assert_eq!(parse_key("shift+k"), Some((KeyCode::Char('K'), KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        assert_eq!(parse_key("f12"), Some((KeyCode::F(12), KeyModifiers::NONE)));
+        assert_eq!(parse_key("f13"), None);
+        assert_eq!(parse_key("hyper+k"), None);
+        let (map, problems) = KeyMap::from_toml("[bindings]\nup = \"f13\"\n");
+        assert!(map.bindings.is_empty());
+        assert_eq!(problems, vec!["unknown key 'f13' for up".to_string()]);
+    }
+
+    #[test]
+    fn clash_with_a_default_key_is_reported() {
+        // `r` is Reply's default key
+        let (map, problems) = KeyMap::from_toml("[bindings]\nup = [\"r\", \"k\"]\n");
+        assert_eq!(problems, vec!["'r' for up conflicts with the default key of reply".to_string()]);
+        assert_eq!(map.bindings.get(&(KeyCode::Char('k'), KeyModifiers::NONE)), Some(&Action::Up));
+        assert!(!map.bindings.contains_key(&(KeyCode::Char('r'), KeyModifiers::NONE)));
+    }
+
+    #[test]
+    fn key_bound_to_two_actions_keeps_the_first() {
+        let (map, problems) = KeyMap::from_toml("[bindings]\ndown = \"ctrl+n\"\nup = \"ctrl+n\"\n");
+        assert_eq!(problems, vec!["'ctrl+n' is bound to both up and down".to_string()]);
+        assert_eq!(map.bindings.get(&(KeyCode::Char('n'), KeyModifiers::CONTROL)), Some(&Action::Up));
+    }
+}
@@ -0,0 +1,122 @@
+// Trust-on-first-use for servers whose certificate doesn't chain to a trusted root
+// (self-signed hobby servers). The user confirms the SHA-256 fingerprint once; it's
+// kept in ~/.nexus_known_hosts as `host:port fingerprint` lines and checked on every
+// later connection, so a changed certificate is flagged instead of silently accepted.
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio_rustls::rustls::{self, DigitallySignedStruct, SignatureScheme};
+use tokio_rustls::rustls::client::WebPkiServerVerifier;
+use tokio_rustls::rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+
+/// A certificate the user has to decide about before connecting
+#[derive(Debug, Clone)]
+pub struct PendingTrust {
+    pub host: String,
+    pub fingerprint: String,
+    /// Fingerprint trusted before, when the server's certificate has changed
+    pub previous: Option<String>,
+}
+
+pub fn config_path() -> PathBuf {
+    crate::global_prefs::GlobalPrefs::config_file("known_hosts")
+}
+
+/// Trusted fingerprints by `host:port`
+pub fn load() -> HashMap<String, String> {
+    let text = fs::read_to_string(config_path()).unwrap_or_default();
+    text.lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some((parts.next()?.to_string(), parts.next()?.to_string()))
+        })
+        .collect()
+}
+
+/// Remember `fingerprint` for `host`, replacing any earlier one
+pub fn trust(host: &str, fingerprint: &str) -> Result<(), String> {
+    let mut hosts = load();
+    hosts.insert(host.to_string(), fingerprint.to_string());
+    let mut lines: Vec<String> = hosts.iter().map(|(host, fp)| format!("{} {}", host, fp)).collect();
+    lines.sort();
+    fs::write(config_path(), lines.join("\n") + "\n")
+        .map_err(|e| format!("Cannot save {}: {}", config_path().display(), e))
+}
+
+/// SHA-256 of the DER certificate as colon-separated hex
+pub fn fingerprint(cert: &CertificateDer<'_>) -> String {
+    let digest = ring::digest::digest(&ring::digest::SHA256, cert.as_ref());
+    digest.as_ref().iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(":")
+}
+
+/// Accepts certificates the roots vouch for, or whose fingerprint was trusted for this
+/// host. Anything else fails the handshake and is left in `pending` for the UI to ask about.
+#[derive(Debug)]
+pub struct TofuVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+    host: String,
+    pub pending: Mutex<Option<PendingTrust>>,
+}
+
+impl TofuVerifier {
+    pub fn new(roots: rustls::RootCertStore, host: String) -> Result<Arc<Self>, String> {
+        let inner = WebPkiServerVerifier::builder(Arc::new(roots))
+            .build()
+            .map_err(|e| format!("TLS setup failed: {}", e))?;
+        Ok(Arc::new(Self { inner, host, pending: Mutex::new(None) }))
+    }
+
+    pub fn take_pending(&self) -> Option<PendingTrust> {
+        self.pending.lock().ok()?.take()
+    }
+}
+
+impl ServerCertVerifier for TofuVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let error = match self.inner.verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now) {
+            Ok(verified) => return Ok(verified),
+            Err(e) => e,
+        };
+        let fingerprint = fingerprint(end_entity);
+        let previous = load().remove(&self.host);
+        if previous.as_deref() == Some(fingerprint.as_str()) {
+            return Ok(ServerCertVerified::assertion());
+        }
+        if let Ok(mut pending) = self.pending.lock() {
+            *pending = Some(PendingTrust { host: self.host.clone(), fingerprint, previous });
+        }
+        Err(error)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
@@ -5,41 +5,53 @@ mod state;
 mod ui;
 mod sound;
 mod global_prefs;
+mod unread_store;
 mod model;
 mod desktop_notifications;
+mod proxy;
+mod clipboard;
+mod browser;
+mod power;
+mod keymap;
+mod server_profiles;
+mod known_hosts;
 
 use app::App;
 use sound::SoundManager;
+use proxy::ProxyConfig;
+use global_prefs::ImageProtocol;
 use nexus_tui_common::{ClientMessage, ServerMessage};
 use crossterm::{
-    event::{self, Event as CEvent},
+    event::{self, DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste, EnableFocusChange, EnableMouseCapture, Event as CEvent},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use futures::{SinkExt, StreamExt};
 use ratatui::{backend::CrosstermBackend, Terminal};
-use std::{env, error::Error, io, time::Duration};
+use std::{error::Error, io, time::{Duration, Instant}};
+use clap::Parser;
+use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::net::TcpStream;
 use tokio::sync::mpsc;
 use tokio_util::codec::{Framed, LengthDelimitedCodec};
 use tokio_rustls::rustls::{self, ClientConfig as RustlsClientConfig, RootCertStore};
-use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
 use tokio_rustls::TlsConnector;
+use tokio_rustls::client::TlsStream;
 use std::sync::Arc;
 use std::fs::File;
 use std::io::BufReader;
 use rustls_pemfile;
-use tokio_rustls::rustls::client::danger::ServerCertVerifier;
 
-fn load_root_cert(path: &str) -> RootCertStore {
+fn load_root_cert(path: &str) -> Result<RootCertStore, String> {
     let mut root_store = RootCertStore::empty();
-    let certfile = File::open(path).expect("Cannot open cert.pem");
+    let certfile = File::open(path).map_err(|e| format!("Cannot open certificate '{}': {}", path, e))?;
     let mut reader = BufReader::new(certfile);
     let certs: Vec<_> = rustls_pemfile::certs(&mut reader).filter_map(|res| res.ok()).collect();
     for cert in certs {
-        root_store.add(cert).unwrap();
+        root_store.add(cert).map_err(|e| format!("Invalid certificate in '{}': {}", path, e))?;
     }
-    root_store
+    Ok(root_store)
 }
 
 fn system_root_store() -> RootCertStore {
@@ -52,6 +64,27 @@ fn system_root_store() -> RootCertStore {
     root_store
 }
 
+/// Load a client certificate chain and private key for mutual TLS
+fn load_client_identity(
+    cert_path: &str,
+    key_path: &str,
+) -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>), String> {
+    let certfile = File::open(cert_path)
+        .map_err(|e| format!("Cannot open client certificate '{}': {}", cert_path, e))?;
+    let certs: Vec<_> = rustls_pemfile::certs(&mut BufReader::new(certfile))
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("Cannot parse client certificate '{}': {}", cert_path, e))?;
+    if certs.is_empty() {
+        return Err(format!("No certificates found in '{}'", cert_path));
+    }
+    let keyfile = File::open(key_path)
+        .map_err(|e| format!("Cannot open client key '{}': {}", key_path, e))?;
+    let key = rustls_pemfile::private_key(&mut BufReader::new(keyfile))
+        .map_err(|e| format!("Cannot parse client key '{}': {}", key_path, e))?
+        .ok_or_else(|| format!("No private key found in '{}'", key_path))?;
+    Ok((certs, key))
+}
+
 /// Application events
 enum AppEvent {
     Terminal(CEvent),
@@ -59,23 +92,324 @@ enum AppEvent {
     Tick,
     RetryConnection, // New event for connection retry
     ConnectionLost, // New event for when connection is lost
+    /// A background connection attempt finished; `attempt` tells stale ones apart
+    Connected { attempt: u64, result: Result<ServerStream, String> },
+}
+
+/// Human-readable message for a failed TCP connection attempt
+fn describe_connect_error(e: &io::Error, server_addr: &str) -> String {
+    match e.kind() {
+        io::ErrorKind::ConnectionRefused => format!("Connection refused to {}", server_addr),
+        io::ErrorKind::TimedOut => format!("Connection timeout to {}", server_addr),
+        io::ErrorKind::NotFound => format!("Host not found: {}", server_addr),
+        _ => format!("Network error: {}", e),
+    }
+}
+
+/// A connected server stream, either TLS-wrapped or plain TCP
+trait ServerIo: AsyncRead + AsyncWrite + Unpin + Send {}
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> ServerIo for T {}
+type ServerStream = Box<dyn ServerIo>;
+
+/// Where and how to connect to the server
+#[derive(Clone)]
+struct ConnectionConfig {
+    server_addr: String,
+    /// SOCKS5/HTTP CONNECT proxy to tunnel through, if any
+    proxy: Option<ProxyConfig>,
+    /// `None` means plain TCP (`--insecure` / `nexus://`)
+    tls: Option<(TlsConnector, ServerName<'static>)>,
+    /// Holds an untrusted certificate after a failed handshake, for the trust prompt
+    verifier: Option<Arc<known_hosts::TofuVerifier>>,
+}
+
+/// Split an optional `nexus://` (plain) or `nexuss://` (TLS) scheme off a server address.
+/// Returns the bare address and whether the scheme asked for plain TCP.
+fn parse_server_scheme(addr: &str) -> (String, bool) {
+    if let Some(rest) = addr.strip_prefix("nexus://") {
+        (rest.to_string(), true)
+    } else if let Some(rest) = addr.strip_prefix("nexuss://") {
+        (rest.to_string(), false)
+    } else {
+        (addr.to_string(), false)
+    }
+}
+
+/// Human-readable message for a failed TLS handshake
+fn describe_tls_error(e: &io::Error, server_name: &ServerName<'static>) -> String {
+    let rustls_error = e.get_ref().and_then(|inner| inner.downcast_ref::<rustls::Error>());
+    match rustls_error {
+        Some(rustls::Error::InvalidCertificate(rustls::CertificateError::NotValidForName)) => format!(
+            "TLS error: server certificate is not valid for '{}' (use --tls-servername to set the expected name)",
+            server_name.to_str()
+        ),
+        _ => format!("TLS error: {}", e),
+    }
+}
+
+/// Open a TCP connection to the server and complete the TLS handshake (unless insecure)
+async fn connect_to_server(config: &ConnectionConfig) -> Result<ServerStream, String> {
+    let stream = match &config.proxy {
+        Some(proxy) => proxy.connect(&config.server_addr).await?,
+        None => TcpStream::connect(&config.server_addr).await
+            .map_err(|e| describe_connect_error(&e, &config.server_addr))?,
+    };
+    match &config.tls {
+        Some((tls_connector, server_name)) => {
+            let tls_stream: TlsStream<TcpStream> = tls_connector.connect(server_name.clone(), stream).await
+                .map_err(|e| describe_tls_error(&e, server_name))?;
+            Ok(Box::new(tls_stream))
+        }
+        None => Ok(Box::new(stream)),
+    }
+}
+
+/// Spawn the task that shuttles messages between the app and an established connection.
+/// Returns the sender for outgoing messages and the task handle.
+fn spawn_server_io(
+    stream: ServerStream,
+    event_tx: mpsc::UnboundedSender<AppEvent>,
+) -> (mpsc::UnboundedSender<ClientMessage>, tokio::task::JoinHandle<()>) {
+    let (tx_to_server, mut rx_from_ui) = mpsc::unbounded_channel::<ClientMessage>();
+    let mut framed = Framed::new(stream, LengthDelimitedCodec::new());
+    let handle = tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                // Handle outgoing messages to server
+                msg = rx_from_ui.recv() => {
+                    if let Some(msg) = msg {
+                        let serialized = bincode::serialize(&msg).unwrap();
+                        if framed.send(serialized.into()).await.is_err() {
+                            // Connection lost while sending
+                            let _ = event_tx.send(AppEvent::ConnectionLost);
+                            break;
+                        }
+                    } else {
+                        break;
+                    }
+                }
+                
+                // Handle incoming messages from server
+                result = framed.next() => {
+                    match result {
+                        Some(Ok(bytes)) => {
+                            if let Ok(msg) = bincode::deserialize::<ServerMessage>(&bytes) {
+                                if event_tx.send(AppEvent::Server(msg)).is_err() {
+                                    break;
+                                }
+                            }
+                        }
+                        Some(Err(_)) | None => {
+                            // Connection lost while receiving
+                            let _ = event_tx.send(AppEvent::ConnectionLost);
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    });
+    (tx_to_server, handle)
+}
+
+/// Command-line settings. The address and root certificate are `None` when not given;
+/// the rest applies to whichever server is connected to.
+#[derive(Parser)]
+#[command(version, about = "Terminal client for Nexus chat and forums")]
+struct CliArgs {
+    /// Server address (host:port); a nexus:// prefix connects without TLS, nexuss:// with it.
+    /// Without one, the servers in ~/.nexus_servers.toml are offered, falling back to 127.0.0.1:8080
+    #[arg(value_name = "ADDRESS", conflicts_with = "server")]
+    address: Option<String>,
+    /// PEM root certificate to trust instead of the system store (same as --cert)
+    #[arg(value_name = "CERT", conflicts_with = "cert")]
+    cert_file: Option<String>,
+    /// Server address, as an alternative to the positional ADDRESS
+    #[arg(long, value_name = "ADDRESS")]
+    server: Option<String>,
+    /// PEM root certificate to trust instead of the system store
+    #[arg(long, value_name = "PEM")]
+    cert: Option<String>,
+    /// Connect over plain TCP without TLS (local development)
+    #[arg(long)]
+    insecure: bool,
+    /// Keep retrying in the background when the server isn't reachable at startup
+    #[arg(long)]
+    auto_reconnect: bool,
+    /// Hostname the server certificate is verified against (e.g. when connecting by IP)
+    #[arg(long, value_name = "NAME")]
+    tls_servername: Option<String>,
+    /// Client certificate for mutual TLS
+    #[arg(long, value_name = "PEM", requires = "client_key")]
+    client_cert: Option<String>,
+    /// Private key for --client-cert
+    #[arg(long, value_name = "PEM", requires = "client_cert")]
+    client_key: Option<String>,
+    /// Tunnel through a proxy (socks5://host:port or http://host:port); defaults to HTTPS_PROXY/ALL_PROXY
+    #[arg(long = "proxy", value_name = "URL")]
+    proxy_url: Option<String>,
+    /// Terminal graphics protocol for this session: auto, kitty, sixel, iterm or halfblocks
+    #[arg(long, value_name = "PROTOCOL", value_parser = parse_image_protocol)]
+    image_protocol: Option<ImageProtocol>,
+    /// Theme for this session
+    #[arg(long, value_name = "NAME", value_parser = parse_theme)]
+    theme: Option<String>,
+    /// Background for this session
+    #[arg(long, value_name = "NAME", value_parser = parse_background)]
+    background: Option<String>,
+    /// Turn off sound effects for this session
+    #[arg(long)]
+    no_sound: bool,
+}
+
+impl CliArgs {
+    fn server_addr(&self) -> Option<&str> {
+        self.server.as_deref().or(self.address.as_deref())
+    }
+
+    fn cert_path(&self) -> Option<&str> {
+        self.cert.as_deref().or(self.cert_file.as_deref())
+    }
+}
+
+fn parse_image_protocol(value: &str) -> Result<ImageProtocol, String> {
+    ImageProtocol::parse(value)
+        .ok_or_else(|| "expected auto, kitty, sixel, iterm or halfblocks".to_string())
+}
+
+fn parse_theme(value: &str) -> Result<String, String> {
+    let themes = ui::themes::ThemeManager::new();
+    let names = themes.names();
+    match names.iter().find(|name| name.eq_ignore_ascii_case(value)) {
+        Some(name) => Ok(name.to_string()),
+        None => Err(format!("expected one of: {}", names.join(", "))),
+    }
+}
+
+fn parse_background(value: &str) -> Result<String, String> {
+    let backgrounds = ui::backgrounds::BackgroundManager::new();
+    let names = backgrounds.names();
+    match names.iter().find(|name| name.eq_ignore_ascii_case(value)) {
+        Some(name) => Ok(name.to_string()),
+        None => Err(format!("expected one of: {}", names.join(", "))),
+    }
+}
+
+/// Build the connection settings for `address`, combined with the command-line options
+fn build_connection(
+    args: &CliArgs,
+    address: &str,
+    cert_path: Option<&str>,
+    tls_servername: Option<&str>,
+) -> Result<ConnectionConfig, String> {
+    let (server_addr, plain_scheme) = parse_server_scheme(address);
+    let parts: Vec<String> = server_addr.split(':').map(|s| s.to_string()).collect();
+    let server_host = parts.get(0).cloned().unwrap_or_else(|| "127.0.0.1".to_string());
+    let proxy = match &args.proxy_url {
+        Some(url) => Some(ProxyConfig::parse(url)?),
+        None => ProxyConfig::from_env(&server_host).transpose()?,
+    };
+
+    // TLS setup
+    let mut tofu = None;
+    let tls = if args.insecure || plain_scheme {
+        None
+    } else {
+        let root_store = if let Some(path) = cert_path {
+            load_root_cert(path)?
+        } else {
+            system_root_store()
+        };
+        let verifier = known_hosts::TofuVerifier::new(root_store, server_addr.clone())?;
+        let builder = RustlsClientConfig::builder()
+            .dangerous()
+            .with_custom_certificate_verifier(verifier.clone());
+        let tls_config = match (&args.client_cert, &args.client_key) {
+            (Some(cert_path), Some(key_path)) => {
+                let (certs, key) = load_client_identity(cert_path, key_path)?;
+                builder.with_client_auth_cert(certs, key)
+                    .map_err(|e| format!("Invalid client certificate/key: {}", e))?
+            }
+            _ => builder.with_no_client_auth(),
+        };
+        let tls_connector = TlsConnector::from(Arc::new(tls_config));
+        tofu = Some(verifier);
+        let server_name = match tls_servername {
+            Some(name) => ServerName::try_from(name.to_string())
+                .map_err(|e| format!("Invalid --tls-servername '{}': {}", name, e))?,
+            None => ServerName::try_from(server_host.clone())
+                .map_err(|e| format!("Invalid server host '{}': {} (use --tls-servername)", server_host, e))?,
+        };
+        Some((tls_connector, server_name))
+    };
+    Ok(ConnectionConfig { server_addr, proxy, tls, verifier: tofu })
+}
+
+/// After a failed connection: if the server's certificate just needs the user's
+/// trust, ask for it. Returns true when the prompt was opened.
+fn prompt_for_trust(app: &mut App, connection: &ConnectionConfig) -> bool {
+    match connection.verifier.as_ref().and_then(|v| v.take_pending()) {
+        Some(pending) => {
+            // Default to rejecting a certificate that changed
+            app.ui.trust_prompt_selected = if pending.previous.is_some() { 1 } else { 0 };
+            app.ui.trust_prompt = Some(pending);
+            app.ui.reset_reconnect();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Show where the app is connecting in the UI
+fn show_connection_info(app: &mut App, connection: &ConnectionConfig) {
+    app.ui.insecure_connection = connection.tls.is_none();
+    app.ui.server_address = connection.server_addr.clone();
+    app.ui.proxy_address = connection.proxy.as_ref().map(|p| p.addr.clone());
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
     // Initialize global preferences
     global_prefs::init_global_prefs();
+
+    // Parse arguments before touching the terminal so errors print normally (clap
+    // prints usage and exits on bad flags). An address on the command line wins;
+    // otherwise a single configured server is used directly and several get a picker
+    // before login.
+    let args = CliArgs::parse();
+    let (profiles, profiles_problem) = server_profiles::load();
+    let mut active_profile = None;
+    let startup_connection = match (args.server_addr(), profiles.len()) {
+        (None, n) if n > 1 => Ok(None),
+        (None, 1) => {
+            active_profile = Some(0);
+            let profile = &profiles[0];
+            build_connection(&args, &profile.address, profile.cert.as_deref(), profile.tls_servername.as_deref()).map(Some)
+        }
+        (addr, _) => build_connection(
+            &args,
+            addr.unwrap_or("127.0.0.1:8080"),
+            args.cert_path(),
+            args.tls_servername.as_deref(),
+        ).map(Some),
+    };
+    let mut connection = match startup_connection {
+        Ok(connection) => connection,
+        Err(e) => {
+            eprintln!("nexus_client: {}", e);
+            std::process::exit(2);
+        }
+    };
     
     // Enable terminal raw mode
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableFocusChange, EnableBracketedPaste)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // Create event channels
-    let (tx_to_server, mut rx_from_ui) = mpsc::unbounded_channel::<ClientMessage>();
-    let (tx_to_ui, mut rx_from_server) = mpsc::unbounded_channel::<ServerMessage>();
+    // Outgoing messages go nowhere until a connection is established
+    let (tx_to_server, _) = mpsc::unbounded_channel::<ClientMessage>();
 
     // Initialize sound manager
     let sound_manager = SoundManager::new();
@@ -83,61 +417,54 @@ async fn main() -> Result<(), Box<dyn Error>> {
     // Create app instance
     let mut app = App::new(tx_to_server, &sound_manager);
 
-    // Get server address from command line or use default
-    let server_addr = env::args().nth(1).unwrap_or_else(|| "127.0.0.1:8080".to_string());
-    let cert_path = env::args().nth(2); // Optional cert path
-    let parts: Vec<String> = server_addr.split(':').map(|s| s.to_string()).collect();
-    let server_host = parts.get(0).cloned().unwrap_or_else(|| "127.0.0.1".to_string());
-    let server_port = parts.get(1).cloned().unwrap_or_else(|| "8080".to_string());
-
-    // TLS setup
-    let root_store = if let Some(path) = cert_path {
-        load_root_cert(&path)
-    } else {
-        system_root_store()
-    };
-    let tls_config = RustlsClientConfig::builder()
-        .with_root_certificates(root_store)
-        .with_no_client_auth();
-    let tls_connector = TlsConnector::from(Arc::new(tls_config));
-    let server_name = ServerName::try_from(server_host.clone()).unwrap();
-
-    // Try to connect to server with error handling (TLS)
-    let tcp_stream = TcpStream::connect(&server_addr).await;
-    let connection_result = match tcp_stream {
-        Ok(stream) => {
-            match tls_connector.connect(server_name.clone(), stream).await {
-                Ok(tls_stream) => Ok(tls_stream),
-                Err(e) => Err(std::io::Error::new(std::io::ErrorKind::Other, format!("TLS error: {}", e))),
-            }
-        },
-        Err(e) => Err(e),
-    };
-    
-    // Show error popup if initial connection fails
-    if let Err(e) = &connection_result {
-        let error_msg = match e.kind() {
-            std::io::ErrorKind::ConnectionRefused => {
-                format!("Connection refused to {}", server_addr)
-            }
-            std::io::ErrorKind::TimedOut => {
-                format!("Connection timeout to {}", server_addr)
-            }
-            std::io::ErrorKind::NotFound => {
-                format!("Host not found: {}", server_addr)
-            }
-            _ => {
-                format!("Network error: {}", e)
-            }
-        };
-        
-        app.ui.show_server_error(error_msg);
-        app.sound_manager.play(sound::SoundType::Error);
+    app.ui.auto_reconnect = args.auto_reconnect || app.prefs.auto_reconnect_on_startup;
+    if args.image_protocol.is_some() {
+        app.ui.image_protocol_override = args.image_protocol;
+        app.apply_image_protocol();
+    }
+    if args.theme.is_some() || args.background.is_some() {
+        app.ui.theme_override = args.theme.clone();
+        app.ui.background_override = args.background.clone();
+        app.apply_appearance();
+    }
+    if args.no_sound {
+        app.ui.sound_disabled = true;
+        app.apply_sound_settings();
+    }
+    app.ui.server_profiles = profiles;
+    app.ui.active_profile = active_profile;
+    if let Some(problem) = profiles_problem {
+        tracing::warn!("Servers: {}", problem);
+        app.set_notification(format!("Servers: {}", problem), Some(8000), false);
+    }
+    match &connection {
+        Some(connection) => show_connection_info(&mut app, connection),
+        None => app.open_server_picker(),
     }
 
     // Create event loop channels
     let (event_tx, mut event_rx) = mpsc::unbounded_channel::<AppEvent>();
 
+    // Try to connect to server with error handling (TLS), unless a server is picked first
+    let mut server_comm_handle = None;
+    if let Some(connection) = &connection {
+        match connect_to_server(connection).await {
+            Ok(stream) => {
+                let (tx, handle) = spawn_server_io(stream, event_tx.clone());
+                app.to_server = tx;
+                server_comm_handle = Some(handle);
+            }
+            Err(error_msg) => {
+                // Show error popup if initial connection fails
+                app.ui.show_server_error(error_msg);
+                app.sound_manager.play(sound::SoundType::Error);
+                if !prompt_for_trust(&mut app, connection) && app.ui.auto_reconnect {
+                    app.schedule_reconnect();
+                }
+            }
+        }
+    }
+
     // Spawn terminal event handler
     let event_tx_clone = event_tx.clone();
     tokio::spawn(async move {
@@ -161,171 +488,112 @@ async fn main() -> Result<(), Box<dyn Error>> {
         }
     });
 
-    // Server communication handler (only if initially connected)
-    let mut server_comm_handle = None;
-    if connection_result.is_ok() {
-        let stream = connection_result.unwrap();
-        let mut framed = Framed::new(stream, LengthDelimitedCodec::new());
-        
-        // Spawn server message handler
-        let event_tx_clone = event_tx.clone();
-        tokio::spawn(async move {
-            while let Some(msg) = rx_from_server.recv().await {
-                if event_tx_clone.send(AppEvent::Server(msg)).is_err() {
-                    break;
-                }
-            }
-        });
-
-        // Spawn server communication handler
-        let event_tx_clone = event_tx.clone();
-        server_comm_handle = Some(tokio::spawn(async move {
-            loop {
-                tokio::select! {
-                    // Handle outgoing messages to server
-                    msg = rx_from_ui.recv() => {
-                        if let Some(msg) = msg {
-                            let serialized = bincode::serialize(&msg).unwrap();
-                            if framed.send(serialized.into()).await.is_err() {
-                                // Connection lost while sending
-                                let _ = event_tx_clone.send(AppEvent::ConnectionLost);
-                                break;
-                            }
-                        } else {
-                            break;
-                        }
-                    }
-                    
-                    // Handle incoming messages from server
-                    result = framed.next() => {
-                        match result {
-                            Some(Ok(bytes)) => {
-                                if let Ok(msg) = bincode::deserialize::<ServerMessage>(&bytes) {
-                                    if tx_to_ui.send(msg).is_err() {
-                                        break;
-                                    }
-                                }
-                            }
-                            Some(Err(_)) | None => {
-                                // Connection lost while receiving
-                                let _ = event_tx_clone.send(AppEvent::ConnectionLost);
-                                break;
-                            }
-                        }
-                    }
-                }
-            }
-        }));
-    }
-
     // Main application loop
+    let mut mouse_captured = false;
+    // Frames are drawn at most `max_fps` times a second, and only while something animates
+    // or changed; input is drawn right away. A slow heartbeat keeps clocks and countdowns current.
+    let mut last_draw: Option<Instant> = None;
+    let mut redraw_now = true;
+    let mut dirty = false;
+    // Retries connect in the background so the UI keeps running; a result is only
+    // used if no newer attempt (or a disconnect) came after it
+    let mut connect_attempt: u64 = 0;
     while !app.ui.should_quit {
-        // Check for retry connection request
-        if app.ui.should_retry_connection {
-            app.ui.should_retry_connection = false;
-            // Attempt to reconnect (TLS)
-            match TcpStream::connect(&server_addr).await {
-                Ok(stream) => {
-                    match tls_connector.connect(server_name.clone(), stream).await {
-                        Ok(tls_stream) => {
-                            app.sound_manager.play(sound::SoundType::LoginSuccess);
-                            if let Some(handle) = server_comm_handle.take() {
-                                handle.abort();
-                            }
-                            let (new_tx_to_server, mut new_rx_from_ui) = mpsc::unbounded_channel::<ClientMessage>();
-                            let (new_tx_to_ui, mut new_rx_from_server) = mpsc::unbounded_channel::<ServerMessage>();
-                            app.to_server = new_tx_to_server;
-                            let mut framed = Framed::new(tls_stream, LengthDelimitedCodec::new());
-                            // Spawn new server message handler
-                            let event_tx_clone = event_tx.clone();
-                            tokio::spawn(async move {
-                                while let Some(msg) = new_rx_from_server.recv().await {
-                                    if event_tx_clone.send(AppEvent::Server(msg)).is_err() {
-                                        break;
-                                    }
-                                }
-                            });
-
-                            // Spawn new server communication handler
-                            let event_tx_clone = event_tx.clone();
-                            server_comm_handle = Some(tokio::spawn(async move {
-                                loop {
-                                    tokio::select! {
-                                        msg = new_rx_from_ui.recv() => {
-                                            if let Some(msg) = msg {
-                                                let serialized = bincode::serialize(&msg).unwrap();
-                                                if framed.send(serialized.into()).await.is_err() {
-                                                    // Connection lost while sending
-                                                    let _ = event_tx_clone.send(AppEvent::ConnectionLost);
-                                                    break;
-                                                }
-                                            } else {
-                                                break;
-                                            }
-                                        }
-                                        
-                                        result = framed.next() => {
-                                            match result {
-                                                Some(Ok(bytes)) => {
-                                                    if let Ok(msg) = bincode::deserialize::<ServerMessage>(&bytes) {
-                                                        if new_tx_to_ui.send(msg).is_err() {
-                                                            break;
-                                                        }
-                                                    }
-                                                }
-                                                Some(Err(_)) | None => {
-                                                    // Connection lost while receiving
-                                                    let _ = event_tx_clone.send(AppEvent::ConnectionLost);
-                                                    break;
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            }));
-                        }
-                        Err(e) => {
-                            let error_msg = format!("TLS error: {}", e);
-                            app.ui.show_server_error(error_msg);
-                            app.sound_manager.play(sound::SoundType::Error);
-                        }
-                    }
+        // Back to the server picker: drop the connection and everything from the session
+        if app.ui.should_disconnect {
+            app.ui.should_disconnect = false;
+            if let Some(handle) = server_comm_handle.take() {
+                handle.abort();
+            }
+            connection = None;
+            connect_attempt += 1;
+            app.ui.connecting = false;
+            app.to_server = mpsc::unbounded_channel::<ClientMessage>().0;
+            app.end_session();
+            app.open_server_picker();
+            dirty = true;
+        }
+
+        // A server was picked: connect to it through the retry path below
+        if let Some(idx) = app.ui.connect_profile.take() {
+            dirty = true;
+            let profile = app.ui.server_profiles[idx].clone();
+            match build_connection(&args, &profile.address, profile.cert.as_deref(), profile.tls_servername.as_deref()) {
+                Ok(picked) => {
+                    show_connection_info(&mut app, &picked);
+                    connection = Some(picked);
+                    app.ui.active_profile = Some(idx);
+                    app.ui.set_mode(state::AppMode::Login);
+                    app.ui.should_retry_connection = true;
                 }
                 Err(e) => {
-                    // Connection failed, show error and continue
-                    let error_msg = match e.kind() {
-                        std::io::ErrorKind::ConnectionRefused => {
-                            format!("Connection refused to {}", server_addr)
-                        }
-                        std::io::ErrorKind::TimedOut => {
-                            format!("Connection timeout to {}", server_addr)
-                        }
-                        std::io::ErrorKind::NotFound => {
-                            format!("Host not found: {}", server_addr)
-                        }
-                        _ => {
-                            format!("Network error: {}", e)
-                        }
-                    };
-                    
-                    app.ui.show_server_error(error_msg);
+                    app.set_notification(format!("{}: {}", profile.name, e), None, false);
                     app.sound_manager.play(sound::SoundType::Error);
                 }
             }
         }
 
+        // Check for retry connection request
+        let retry_requested = std::mem::take(&mut app.ui.should_retry_connection);
+        if let (true, Some(connection)) = (retry_requested, &connection) {
+            dirty = true;
+            // Attempt to reconnect (TLS) without blocking the UI
+            connect_attempt += 1;
+            app.ui.connecting = true;
+            let (attempt, connection, event_tx) = (connect_attempt, connection.clone(), event_tx.clone());
+            tokio::spawn(async move {
+                let result = connect_to_server(&connection).await;
+                let _ = event_tx.send(AppEvent::Connected { attempt, result });
+            });
+        }
+
+        // Follow the mouse support preference (capture blocks terminal text selection)
+        if app.prefs.mouse_support != mouse_captured {
+            mouse_captured = app.prefs.mouse_support;
+            if mouse_captured {
+                execute!(terminal.backend_mut(), EnableMouseCapture)?;
+            } else {
+                execute!(terminal.backend_mut(), DisableMouseCapture)?;
+            }
+        }
+
         // Render UI
-        terminal.draw(|f| ui::ui(f, &mut app))?;
+        let frame_interval = Duration::from_millis(1000 / u64::from(app.prefs.max_fps.max(1)));
+        let frame_due = match last_draw {
+            None => true,
+            Some(at) => {
+                let elapsed = at.elapsed();
+                // Ticks arrive every 50ms give or take, so allow a little slack at full rate
+                (elapsed + Duration::from_millis(5) >= frame_interval
+                    && (dirty || app.ui.needs_redraw || app.is_animating()))
+                    || elapsed >= Duration::from_secs(1)
+            }
+        };
+        if redraw_now || frame_due {
+            terminal.draw(|f| ui::ui(f, &mut app))?;
+            app.ui.record_frame();
+            last_draw = Some(Instant::now());
+            redraw_now = false;
+            dirty = false;
+            app.ui.needs_redraw = false;
+        }
 
         // Handle events
         if let Some(event) = event_rx.recv().await {
             match event {
                 AppEvent::Terminal(terminal_event) => {
-                    if let CEvent::Key(key) = terminal_event {
-                        handlers::handle_key_event(key, &mut app);
+                    redraw_now = true;
+                    match terminal_event {
+                        CEvent::Key(key) => handlers::handle_key_event(key, &mut app),
+                        CEvent::Mouse(mouse) => handlers::mouse::handle_mouse_event(mouse, &mut app),
+                        CEvent::Paste(text) => handlers::handle_paste(text, &mut app),
+                        CEvent::FocusGained => app.ui.has_focus = true,
+                        CEvent::FocusLost => app.ui.has_focus = false,
+                        _ => {}
                     }
                 }
                 AppEvent::Server(server_msg) => {
+                    dirty = true;
                     app.handle_server_message(server_msg);
                 }
                 AppEvent::Tick => {
@@ -334,21 +602,64 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 AppEvent::RetryConnection => {
                     app.ui.should_retry_connection = true;
                 }
+                AppEvent::Connected { attempt, result } => {
+                    let Some(connection) = connection.as_ref().filter(|_| attempt == connect_attempt) else {
+                        continue;
+                    };
+                    dirty = true;
+                    app.ui.connecting = false;
+                    match result {
+                        Ok(stream) => {
+                            app.sound_manager.play(sound::SoundType::LoginSuccess);
+                            if let Some(handle) = server_comm_handle.take() {
+                                handle.abort();
+                            }
+                            let (tx, handle) = spawn_server_io(stream, event_tx.clone());
+                            app.to_server = tx;
+                            server_comm_handle = Some(handle);
+                            app.on_reconnected();
+                            app.ui.reset_reconnect();
+                            app.ui.hide_server_error();
+                            if app.ui.mode == state::AppMode::Chat {
+                                app.apply_chat_entry_focus();
+                            }
+                        }
+                        Err(error_msg) => {
+                            // Connection failed, show error and continue
+                            app.ui.show_server_error(error_msg);
+                            if prompt_for_trust(&mut app, connection) || !(app.ui.auto_reconnect && app.schedule_reconnect()) {
+                                app.sound_manager.play(sound::SoundType::Error);
+                            }
+                        }
+                    }
+                }
                 AppEvent::ConnectionLost => {
-                    // Handle connection lost event (e.g., show a message, play a sound, etc.)
+                    dirty = true;
+                    // A dropped connection is always retried automatically, backing off
                     app.ui.show_server_error("Connection to server was lost.".to_string());
                     app.sound_manager.play(sound::SoundType::Error);
+                    app.ui.auto_reconnect = true;
+                    app.ui.reset_reconnect();
+                    app.schedule_reconnect();
                 }
             }
         }
     }
 
     // Cleanup
+    app.save_unread_state();
+    // A theme/background/preference change made just before quitting hasn't been saved yet
+    if app.prefs_dirty {
+        app.prefs.save();
+    }
     if let Some(handle) = server_comm_handle {
         handle.abort();
     }
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    if mouse_captured {
+        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    }
+    execute!(terminal.backend_mut(), DisableBracketedPaste, DisableFocusChange, LeaveAlternateScreen)?;
     terminal.show_cursor()?;
 
     Ok(())
@@ -6,11 +6,16 @@ use ratatui::style::Color;
 /// Chat message with metadata for UI rendering
 #[derive(Debug, Clone)]
 pub struct ChatMessageWithMeta {
+    pub id: uuid::Uuid,
+    /// Author's user id; display names aren't unique, so lookups go by this
+    pub author_id: uuid::Uuid,
     pub author: String,
     pub content: String,
     pub color: Color,
     pub profile_pic: Option<String>,
     pub timestamp: Option<i64>,
+    /// Sent while offline and not yet echoed back by the server
+    pub pending: bool,
 }
 
 // // --- Mock Data Creation ---
@@ -0,0 +1,21 @@
+// Power source detection, used to pause animations on battery. Reads the kernel's
+// power_supply class on Linux; other platforms are treated as always on AC.
+use std::fs;
+
+/// Whether the machine is running on battery (a battery discharging and no AC online)
+pub fn on_battery() -> bool {
+    let Ok(entries) = fs::read_dir("/sys/class/power_supply") else {
+        return false;
+    };
+    let mut discharging = false;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let read = |name: &str| fs::read_to_string(path.join(name)).map(|s| s.trim().to_string()).unwrap_or_default();
+        match read("type").as_str() {
+            "Mains" | "USB" if read("online") == "1" => return false,
+            "Battery" if read("status") == "Discharging" => discharging = true,
+            _ => {}
+        }
+    }
+    discharging
+}
@@ -0,0 +1,45 @@
+// Named servers from ~/.nexus_servers.toml. With more than one listed (and no address
+// on the command line) a picker is shown before login.
+//
+//     [[server]]
+//     name = "Home"
+//     address = "nexuss://chat.example.net:8080"
+//
+//     [[server]]
+//     name = "Lab"
+//     address = "10.0.0.5:8080"
+//     cert = "/home/me/lab-ca.pem"      # root certificate to trust (optional)
+//     tls_servername = "lab.internal"   # name the certificate is checked against (optional)
+use std::fs;
+use std::path::PathBuf;
+use serde::Deserialize;
+
+/// One server the user can connect to
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerProfile {
+    pub name: String,
+    /// `host:port`, optionally with a `nexus://` (plain) or `nexuss://` (TLS) scheme
+    pub address: String,
+    pub cert: Option<String>,
+    pub tls_servername: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ServersFile {
+    server: Vec<ServerProfile>,
+}
+
+pub fn config_path() -> PathBuf {
+    crate::global_prefs::GlobalPrefs::config_file("servers.toml")
+}
+
+/// Load the configured servers. A missing file means none; a broken one comes back as
+/// a message alongside an empty list.
+pub fn load() -> (Vec<ServerProfile>, Option<String>) {
+    let Ok(text) = fs::read_to_string(config_path()) else { return (Vec::new(), None) };
+    match toml::from_str::<ServersFile>(&text) {
+        Ok(file) => (file.server, None),
+        Err(e) => (Vec::new(), Some(format!("invalid servers file: {}", e.message()))),
+    }
+}
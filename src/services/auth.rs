@@ -0,0 +1,82 @@
+/// How hard a password would be to guess, for the registration meter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordStrength {
+    Weak,
+    Fair,
+    Strong,
+}
+
+impl PasswordStrength {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PasswordStrength::Weak => "Weak",
+            PasswordStrength::Fair => "Fair",
+            PasswordStrength::Strong => "Strong",
+        }
+    }
+}
+
+/// Passwords that show up at the top of every leaked-password list
+const COMMON_PASSWORDS: &[&str] = &[
+    "password", "passw0rd", "123456", "12345678", "123456789", "1234567890", "qwerty",
+    "qwertyuiop", "abc123", "111111", "123123", "letmein", "welcome", "monkey", "dragon",
+    "iloveyou", "admin", "login", "master", "sunshine", "princess", "football", "baseball",
+    "shadow", "superman", "trustno1", "whatever", "starwars", "hunter2", "changeme",
+];
+
+/// Service for client-side authentication helpers
+pub struct AuthService;
+
+impl AuthService {
+    /// Score a password on length and character classes. Common passwords, repeated or
+    /// sequential characters and passwords containing the username are always weak.
+    /// This is guidance only; the server decides what it accepts.
+    pub fn password_strength(password: &str, username: &str) -> PasswordStrength {
+        let lower = password.to_lowercase();
+        let length = password.chars().count();
+        if length < 8 || Self::is_common(&lower) || Self::is_pattern(&lower) {
+            return PasswordStrength::Weak;
+        }
+        let username = username.trim().to_lowercase();
+        if username.chars().count() >= 3 && lower.contains(&username) {
+            return PasswordStrength::Weak;
+        }
+
+        let classes = [
+            password.chars().any(|c| c.is_lowercase()),
+            password.chars().any(|c| c.is_uppercase()),
+            password.chars().any(|c| c.is_ascii_digit()),
+            password.chars().any(|c| !c.is_alphanumeric()),
+        ].iter().filter(|&&present| present).count();
+
+        let mut score = match length {
+            0..=11 => 0,
+            12..=15 => 1,
+            _ => 2,
+        };
+        score += match classes {
+            0 | 1 => 0,
+            2 => 1,
+            3 => 2,
+            _ => 3,
+        };
+        match score {
+            0 | 1 => PasswordStrength::Weak,
+            2 | 3 => PasswordStrength::Fair,
+            _ => PasswordStrength::Strong,
+        }
+    }
+
+    /// A common password, possibly with digits or `!` tacked on the end ("password123!")
+    fn is_common(lower: &str) -> bool {
+        let stem = lower.trim_end_matches(|c: char| c.is_ascii_digit() || c == '!');
+        COMMON_PASSWORDS.iter().any(|&common| common == lower || common == stem)
+    }
+
+    /// One repeated character ("aaaaaaaa") or a straight run ("abcdefgh", "87654321")
+    fn is_pattern(lower: &str) -> bool {
+        let chars: Vec<u32> = lower.chars().map(u32::from).collect();
+        let steps: Vec<i64> = chars.windows(2).map(|w| w[1] as i64 - w[0] as i64).collect();
+        steps.iter().all(|&d| d == 0) || steps.iter().all(|&d| d == 1) || steps.iter().all(|&d| d == -1)
+    }
+}
@@ -1,10 +1,22 @@
 use crate::state::{ChatState, ChatTarget};
 use crate::model::ChatMessageWithMeta;
-use crate::services::image::{ImageCache, ImageCacheKey, CachedImage, ImageCacheStats};
-use nexus_tui_common::{User, ClientMessage};
+use crate::services::image::{ImageCache, ImageCacheKey, CachedImage, ImageCacheStats, ImageFormat};
+use nexus_tui_common::{User, UserRole, ClientMessage};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
+/// Mentions that address a group instead of a single user: (name, description, staff only).
+/// Staff-only mentions are offered to moderators and admins; the server still has the final say.
+pub const SPECIAL_MENTIONS: &[(&str, &str, bool)] = &[
+    ("everyone", "notify the whole channel", true),
+    ("here", "notify online members", true),
+    ("admins", "notify admins", false),
+    ("mods", "notify moderators", false),
+];
+
+/// How long a cached avatar stays valid, in seconds
+const AVATAR_TTL_SECONDS: u64 = 7200;
+
 /// Enhanced chat service with pagination and caching capabilities
 pub struct ChatService {
     image_cache: Option<Arc<ImageCache>>,
@@ -84,35 +96,6 @@ impl ChatService {
         optimal_size.min(1000).max(base_buffer) // Cap at 1000, minimum base_buffer
     }
 
-    /// Process and cache user avatar with optimization
-    pub fn process_user_avatar(&self, user: &User) -> Option<CachedImage> {
-        if let (Some(cache), Some(avatar_data)) = (&self.image_cache, &user.profile_pic) {
-            let cache_key = ImageCacheKey::user_avatar(user.id);
-            
-            // Check if already cached
-            if let Ok(Some(cached)) = cache.get(&cache_key) {
-                return Some(cached);
-            }
-            
-            // Process and cache new avatar
-            if let Ok(cached_image) = cache.process_and_cache_base64(
-                cache_key, 
-                avatar_data, 
-                Some(7200) // 2 hour TTL for avatars
-            ) {
-                return Some(cached_image);
-            }
-        }
-        None
-    }
-
-    /// Batch process multiple user avatars for efficiency
-    pub fn batch_process_avatars(&self, users: &[User]) -> Vec<(uuid::Uuid, Option<CachedImage>)> {
-        users.iter().map(|user| {
-            (user.id, self.process_user_avatar(user))
-        }).collect()
-    }
-
     /// Get cache statistics for monitoring
     pub fn get_cache_stats(&self) -> Option<ImageCacheStats> {
         self.image_cache.as_ref()
@@ -125,100 +108,173 @@ impl ChatService {
             .and_then(|cache| cache.cleanup_expired().ok())
     }
 
-    /// Preload images for current conversation participants
+    /// Preload images for current conversation participants. Decoding happens on a
+    /// blocking thread so switching conversations doesn't wait for it.
     pub fn preload_conversation_images(&self, chat_state: &ChatState) {
-        if let Some(cache) = &self.image_cache {
-            match &chat_state.current_chat_target {
-                Some(ChatTarget::Channel { .. }) => {
-                    // Preload channel user avatars
-                    for user in &chat_state.channel_userlist {
-                        if let Some(avatar_data) = &user.profile_pic {
-                            let cache_key = ImageCacheKey::user_avatar(user.id);
-                            if !cache.contains_key(&cache_key) {
-                                let _ = cache.process_and_cache_base64(
-                                    cache_key,
-                                    avatar_data,
-                                    Some(7200)
-                                );
-                            }
-                        }
-                    }
-                }
-                Some(ChatTarget::DM { .. }) => {
-                    // Preload DM user avatars
-                    for user in &chat_state.dm_user_list {
-                        if let Some(avatar_data) = &user.profile_pic {
-                            let cache_key = ImageCacheKey::user_avatar(user.id);
-                            if !cache.contains_key(&cache_key) {
-                                let _ = cache.process_and_cache_base64(
-                                    cache_key,
-                                    avatar_data,
-                                    Some(7200)
-                                );
-                            }
-                        }
-                    }
-                }
-                None => {}
+        let Some(cache) = &self.image_cache else { return };
+        let users = match &chat_state.current_chat_target {
+            Some(ChatTarget::Channel { .. }) => &chat_state.channel_userlist,
+            Some(ChatTarget::DM { .. }) => &chat_state.dm_user_list,
+            None => return,
+        };
+        let pending: Vec<(ImageCacheKey, String)> = users
+            .iter()
+            .filter_map(|user| Some((ImageCacheKey::user_avatar(user.id), user.profile_pic.clone()?)))
+            .collect();
+        if pending.is_empty() {
+            return;
+        }
+        let cache = cache.clone();
+        let preload = move || {
+            for (cache_key, avatar_data) in pending {
+                Self::store_avatar(&cache, cache_key, &avatar_data);
             }
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => { runtime.spawn_blocking(preload); }
+            Err(_) => preload(),
         }
     }
 
-    /// Get mention suggestions for users starting with the given input
-    pub fn get_mention_suggestions(input: &str, users: &[User]) -> Vec<String> {
-        let cursor = input.len();
+    /// Keep the cached copy of a user's avatar in step with a picture the server just
+    /// sent; `None` means they have no picture any more.
+    pub fn cache_avatar(&self, user_id: uuid::Uuid, profile_pic: Option<&str>) {
+        let Some(cache) = &self.image_cache else { return };
+        let cache_key = ImageCacheKey::user_avatar(user_id);
+        match profile_pic {
+            Some(avatar_data) => Self::store_avatar(cache, cache_key, avatar_data),
+            None => { let _ = cache.remove(&cache_key); }
+        }
+    }
+
+    /// Cache a user's picture unless the same bytes are already stored. The lookup
+    /// goes through `get` so it counts toward the hit ratio.
+    fn store_avatar(cache: &ImageCache, cache_key: ImageCacheKey, avatar_data: &str) {
+        let Some((format, data)) = ImageFormat::from_base64_data_url(avatar_data) else { return };
+        if cache.get(&cache_key).ok().flatten().is_some_and(|cached| cached.data == data) {
+            return;
+        }
+        let _ = cache.put(cache_key, CachedImage::new(data, format, Some(AVATAR_TTL_SECONDS)));
+    }
+
+    /// Whether `name` (without the `@`) is a group mention like `everyone`
+    pub fn is_special_mention(name: &str) -> bool {
+        SPECIAL_MENTIONS.iter().any(|(special, _, _)| special.eq_ignore_ascii_case(name))
+    }
+    
+    pub fn special_mention_description(name: &str) -> Option<&'static str> {
+        SPECIAL_MENTIONS.iter()
+            .find(|(special, _, _)| special.eq_ignore_ascii_case(name))
+            .map(|(_, description, _)| *description)
+    }
+    
+    /// The first group mention in `content` that addresses someone with `role`:
+    /// `@everyone` and `@here` reach everybody, `@admins` and `@mods` only staff.
+    pub fn group_mention_for(content: &str, role: UserRole) -> Option<&'static str> {
+        content
+            .split(|ch: char| !(ch.is_alphanumeric() || ch == '_' || ch == '@'))
+            .filter_map(|word| word.strip_prefix('@'))
+            .find_map(|name| {
+                let (special, _, _) = SPECIAL_MENTIONS.iter().find(|(special, _, _)| special.eq_ignore_ascii_case(name))?;
+                let addressed = match *special {
+                    "admins" => role == UserRole::Admin,
+                    "mods" => role >= UserRole::Moderator,
+                    _ => true,
+                };
+                addressed.then_some(*special)
+            })
+    }
+    
+    /// The `@name` being typed just left of `cursor` (a byte index): the byte index
+    /// of its `@` and the partial name after it
+    pub fn mention_token_before(input: &str, cursor: usize) -> Option<(usize, &str)> {
         let upto = &input[..cursor];
-        
-        if let Some(idx) = upto.rfind('@') {
-            let after_at = &upto[(idx + 1)..];
-            if after_at.chars().all(|ch| ch.is_alphanumeric() || ch == '_') && !after_at.is_empty() {
-                let prefix = after_at.to_lowercase();
-                let mut suggestions: Vec<String> = users
-                    .iter()
-                    .filter(|u| u.username.to_lowercase().starts_with(&prefix))
-                    .map(|u| u.username.clone())
-                    .collect();
-                suggestions.sort();
-                return suggestions;
-            }
+        let idx = upto.rfind('@')?;
+        let after_at = &upto[(idx + 1)..];
+        (after_at.chars().all(|ch| ch.is_alphanumeric() || ch == '_') && !after_at.is_empty()).then_some((idx, after_at))
+    }
+    
+    /// Get mention suggestions for the `@name` at the cursor: group mentions the
+    /// user may send come first, then matching usernames.
+    pub fn get_mention_suggestions(input: &str, cursor: usize, users: &[User], role: Option<UserRole>) -> Vec<String> {
+        if let Some((_, after_at)) = Self::mention_token_before(input, cursor) {
+            let prefix = after_at.to_lowercase();
+            let is_staff = matches!(role, Some(UserRole::Admin) | Some(UserRole::Moderator));
+            let mut suggestions: Vec<String> = SPECIAL_MENTIONS
+                .iter()
+                .filter(|(name, _, staff_only)| (is_staff || !staff_only) && name.starts_with(&prefix))
+                .map(|(name, _, _)| name.to_string())
+                .collect();
+            let mut usernames: Vec<String> = users
+                .iter()
+                .filter(|u| u.username.to_lowercase().starts_with(&prefix))
+                .map(|u| u.username.clone())
+                .collect();
+            usernames.sort();
+            suggestions.extend(usernames);
+            return suggestions;
         }
         
         Vec::new()
     }
     
-    /// Apply the mention suggestion to the input text
-    pub fn apply_mention_suggestion(input: &str, suggestion: &str, prefix: &str) -> String {
+    /// Complete the mention at the cursor (a byte index) with `suggestion`, replacing the
+    /// whole `@name` token even when the cursor is inside it. Returns the new input and
+    /// the byte index just after the inserted `@suggestion `.
+    pub fn apply_mention_suggestion(input: &str, cursor: usize, suggestion: &str) -> (String, usize) {
+        let Some((start, _)) = Self::mention_token_before(input, cursor) else {
+            return (input.to_string(), cursor);
+        };
+        let rest = &input[cursor..];
+        let token_end = cursor + rest.find(|ch: char| !(ch.is_alphanumeric() || ch == '_')).unwrap_or(rest.len());
+        // Don't double up the space when one already follows
+        let inserted = if input[token_end..].starts_with(' ') {
+            format!("@{}", suggestion)
+        } else {
+            format!("@{} ", suggestion)
+        };
         let mut result = input.to_string();
-        if let Some(idx) = input.rfind(&format!("@{}", prefix)) {
-            result.replace_range(idx..(idx + 1 + prefix.len()), &format!("@{} ", suggestion));
-        }
-        result
+        result.replace_range(start..token_end, &inserted);
+        // Either way a space now follows the name; the cursor goes after it
+        let new_cursor = start + suggestion.len() + 2;
+        (result, new_cursor)
+    }
+    
+    /// Emojis with a shortcode starting with `prefix`, favorites first, then recently
+    /// used ones, then the rest sorted. An empty prefix gives every emoji, the rest in
+    /// the standard (category) order.
+    pub fn emoji_matches(prefix: &str, favorites: &[String], recent: &[String]) -> Vec<String> {
+        let mut matches: Vec<String> = if prefix.is_empty() {
+            emojis::iter().map(|emoji| emoji.as_str().to_string()).collect()
+        } else {
+            let prefix = prefix.to_lowercase();
+            let mut matches: Vec<String> = emojis::iter()
+                .filter(|emoji| emoji.shortcodes().any(|shortcode| shortcode.to_lowercase().starts_with(&prefix)))
+                .map(|emoji| emoji.as_str().to_string())
+                .collect();
+            matches.sort();
+            matches.dedup();
+            matches
+        };
+        let rank = |emoji: &String| {
+            favorites.iter().position(|f| f == emoji)
+                .or_else(|| recent.iter().position(|r| r == emoji).map(|i| favorites.len() + i))
+                .unwrap_or(usize::MAX)
+        };
+        matches.sort_by_key(rank);
+        matches
     }
     
     /// Get emoji suggestions based on input text
-    pub fn get_emoji_suggestions(input: &str) -> Vec<String> {
+    pub fn get_emoji_suggestions(input: &str, favorites: &[String], recent: &[String]) -> Vec<String> {
         let cursor = input.len();
         let upto = &input[..cursor];
         
         if let Some(idx) = upto.rfind(':') {
             let after_colon = &upto[(idx + 1)..];
             if after_colon.chars().all(|ch| ch.is_alphabetic() || ch == '_') && !after_colon.is_empty() {
-                let prefix = after_colon.to_lowercase();
-                let mut suggestions: Vec<String> = emojis::iter()
-                    .filter_map(|emoji| {
-                        // Check if any shortcode matches the prefix
-                        for shortcode in emoji.shortcodes() {
-                            if shortcode.to_lowercase().starts_with(&prefix) {
-                                return Some(emoji.as_str().to_string());
-                            }
-                        }
-                        None
-                    })
-                    .collect();
-                
-                // Remove duplicates and limit to reasonable number
-                suggestions.sort();
-                suggestions.dedup();
+                // Limit to a reasonable number
+                let mut suggestions = Self::emoji_matches(after_colon, favorites, recent);
                 suggestions.truncate(10);
                 return suggestions;
             }
@@ -248,15 +304,80 @@ impl ChatService {
         None
     }
     
+    /// Indices of messages containing `query` (case-insensitive).
+    pub fn search_messages(messages: &[ChatMessageWithMeta], query: &str) -> Vec<usize> {
+        let query = query.to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        messages.iter()
+            .enumerate()
+            .filter(|(_, m)| m.content.to_lowercase().contains(&query))
+            .map(|(i, _)| i)
+            .collect()
+    }
+    
+    /// The author of a message among `users`. Display names aren't unique, so this
+    /// goes by id and two users with the same name never get each other's avatar.
+    pub fn find_author<'a>(users: impl IntoIterator<Item = &'a User>, author_id: uuid::Uuid) -> Option<&'a User> {
+        users.into_iter().find(|u| u.id == author_id)
+    }
+    
+    /// Number of messages in the current conversation, queued sends included
+    pub fn message_count(chat_state: &ChatState, current_user: Option<&User>) -> usize {
+        let loaded = match &chat_state.current_chat_target {
+            Some(ChatTarget::Channel { .. }) => chat_state.chat_messages.len(),
+            Some(ChatTarget::DM { .. }) => chat_state.dm_messages.len(),
+            None => 0,
+        };
+        loaded + Self::pending_for_target(chat_state, current_user).count()
+    }
+    
+    /// Queued sends for the current conversation, in the order they were written
+    fn pending_for_target<'a>(chat_state: &'a ChatState, current_user: Option<&User>) -> impl Iterator<Item = &'a crate::state::PendingSend> {
+        let target_id = match (&chat_state.current_chat_target, current_user) {
+            (Some(ChatTarget::Channel { channel_id, .. }), Some(_)) => Some(*channel_id),
+            (Some(ChatTarget::DM { user_id }), Some(_)) => Some(*user_id),
+            _ => None,
+        };
+        chat_state.pending_sends.iter().filter(move |p| Some(p.target_id) == target_id)
+    }
+    
     pub fn build_message_list(
         chat_state: &ChatState,
         current_user: Option<&User>,
     ) -> Vec<ChatMessageWithMeta> {
-        match &chat_state.current_chat_target {
+        Self::build_message_range(chat_state, current_user, 0..Self::message_count(chat_state, current_user))
+    }
+    
+    /// Run `f` on the current conversation's built message list, building it only when
+    /// it was invalidated or the conversation changed since the last call
+    pub fn with_message_list<R>(
+        chat_state: &ChatState,
+        current_user: Option<&User>,
+        f: impl FnOnce(&[ChatMessageWithMeta]) -> R,
+    ) -> R {
+        let mut cache = chat_state.message_cache.borrow_mut();
+        let built = match cache.take() {
+            Some((target, messages)) if target == chat_state.current_chat_target => messages,
+            _ => Self::build_message_list(chat_state, current_user),
+        };
+        f(&cache.insert((chat_state.current_chat_target.clone(), built)).1)
+    }
+    
+    /// Messages `range` of the current conversation (clamped to what's there), built
+    /// without touching the rest, so drawing costs a screenful however long the history is.
+    pub fn build_message_range(
+        chat_state: &ChatState,
+        current_user: Option<&User>,
+        range: std::ops::Range<usize>,
+    ) -> Vec<ChatMessageWithMeta> {
+        let clamp = |len: usize| range.start.min(len)..range.end.min(len);
+        let mut messages: Vec<ChatMessageWithMeta> = match &chat_state.current_chat_target {
             Some(ChatTarget::Channel { .. }) => {
-                chat_state.chat_messages.iter().map(|msg| {
+                chat_state.chat_messages[clamp(chat_state.chat_messages.len())].iter().map(|msg| {
                     // Look up user info by sent_by ID
-                    let (author, color, profile_pic) = if let Some(user) = chat_state.channel_userlist.iter().find(|u| u.id == msg.sent_by) {
+                    let (author, color, profile_pic) = if let Some(user) = Self::find_author(&chat_state.channel_userlist, msg.sent_by) {
                         (user.username.clone(), user.color.clone().into(), user.profile_pic.clone())
                     } else {
                         // Fallback for unknown users
@@ -264,16 +385,19 @@ impl ChatService {
                     };
                     
                     ChatMessageWithMeta {
+                        id: msg.id,
+                        author_id: msg.sent_by,
                         author,
                         content: msg.content.clone(),
                         color,
                         profile_pic,
                         timestamp: Some(msg.timestamp),
+                        pending: false,
                     }
                 }).collect()
             }
             Some(ChatTarget::DM { .. }) => {
-                chat_state.dm_messages.iter().map(|msg| {
+                chat_state.dm_messages[clamp(chat_state.dm_messages.len())].iter().map(|msg| {
                     let (author, color, profile_pic) = if let Some(user) = current_user {
                         if msg.from != user.id {
                             // Find user in dm_user_list by from ID
@@ -293,16 +417,42 @@ impl ChatService {
                     };
                     
                     ChatMessageWithMeta {
+                        id: msg.id,
+                        author_id: msg.from,
                         author,
                         content: msg.content.clone(),
                         color,
                         profile_pic,
                         timestamp: Some(msg.timestamp),
+                        pending: false,
                     }
                 }).collect()
             }
             None => Vec::new(),
+        };
+
+        // Messages queued while offline go last, until the server echoes them
+        if let Some(user) = current_user {
+            let loaded = match &chat_state.current_chat_target {
+                Some(ChatTarget::Channel { .. }) => chat_state.chat_messages.len(),
+                _ => chat_state.dm_messages.len(),
+            };
+            let skip = range.start.saturating_sub(loaded);
+            let take = range.end.saturating_sub(loaded.max(range.start));
+            messages.extend(Self::pending_for_target(chat_state, current_user).skip(skip).take(take).map(|p| {
+                ChatMessageWithMeta {
+                    id: p.id,
+                    author_id: user.id,
+                    author: user.username.clone(),
+                    content: p.content.clone(),
+                    color: user.color.clone().into(),
+                    profile_pic: user.profile_pic.clone(),
+                    timestamp: Some(p.queued_at),
+                    pending: true,
+                }
+            }));
         }
+        messages
     }
 
     pub fn should_fetch_more_messages(
@@ -312,20 +462,14 @@ impl ChatService {
         Self::should_fetch_more_messages_enhanced(chat_state, max_rows, 10)
     }
 
-    /// Request avatars for users that don't have profile pictures loaded
+    /// Request avatars for users that don't have profile pictures loaded. Ones the image
+    /// cache already holds (from this session or a previous one) aren't fetched again.
     pub fn request_missing_avatars(&self, chat_state: &ChatState, to_server: &mpsc::UnboundedSender<ClientMessage>) {
         let mut missing_user_ids = std::collections::HashSet::new();
         
-        // Check channel users for missing avatars
-        for user in &chat_state.channel_userlist {
-            if user.profile_pic.is_none() {
-                missing_user_ids.insert(user.id);
-            }
-        }
-        
-        // Check DM users for missing avatars
-        for user in &chat_state.dm_user_list {
-            if user.profile_pic.is_none() {
+        // Check channel users and DM users for missing avatars
+        for user in chat_state.channel_userlist.iter().chain(&chat_state.dm_user_list) {
+            if user.profile_pic.is_none() && !self.has_cached_avatar(user.id) {
                 missing_user_ids.insert(user.id);
             }
         }
@@ -338,4 +482,154 @@ impl ChatService {
             let _ = to_server.send(ClientMessage::GetUserAvatars { user_ids: unique_user_ids });
         }
     }
-}
\ No newline at end of file
+
+    fn has_cached_avatar(&self, user_id: uuid::Uuid) -> bool {
+        self.image_cache.as_ref()
+            .is_some_and(|cache| matches!(cache.get(&ImageCacheKey::user_avatar(user_id)), Ok(Some(_))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexus_tui_common::{ChannelMessage, UserStatus};
+    use std::time::Instant;
+
+    fn user(name: &str, pic: &str) -> User {
+        User {
+            id: uuid::Uuid::new_v4(),
+            username: name.to_string(),
+            color: "cyan".into(),
+            role: UserRole::User,
+            profile_pic: Some(pic.to_string()),
+            cover_banner: None,
+            status: UserStatus::Connected,
+        }
+    }
+
+    #[test]
+    fn same_named_users_keep_their_own_avatars() {
+        let first = user("sam", "first-pic");
+        let second = user("sam", "second-pic");
+        let users = vec![first.clone(), second.clone()];
+        let found = ChatService::find_author(&users, second.id).unwrap();
+        assert_eq!(found.profile_pic.as_deref(), Some("second-pic"));
+        let found = ChatService::find_author(&users, first.id).unwrap();
+        assert_eq!(found.profile_pic.as_deref(), Some("first-pic"));
+        assert!(ChatService::find_author(&users, uuid::Uuid::new_v4()).is_none());
+    }
+
+    #[test]
+    fn preloading_counts_cache_hits_and_misses() {
+        let service = ChatService::with_image_cache(Arc::new(ImageCache::with_default_config()));
+        let mut chat = channel_with(0);
+        chat.channel_userlist = vec![user("sam", "data:image/png;base64,iVBORw0KGgo=")];
+        service.preload_conversation_images(&chat);
+        let stats = service.get_cache_stats().unwrap();
+        assert_eq!((stats.hits, stats.misses, stats.total_entries), (0, 1, 1));
+        service.preload_conversation_images(&chat);
+        let stats = service.get_cache_stats().unwrap();
+        assert_eq!((stats.hits, stats.misses, stats.total_entries), (1, 1, 1));
+    }
+
+    #[test]
+    fn cached_avatars_are_not_requested_again() {
+        let service = ChatService::with_image_cache(Arc::new(ImageCache::with_default_config()));
+        let mut chat = channel_with(0);
+        let cached = user("sam", "data:image/png;base64,iVBORw0KGgo=");
+        service.cache_avatar(cached.id, cached.profile_pic.as_deref());
+        let uncached = User { profile_pic: None, ..user("alex", "") };
+        chat.channel_userlist = vec![User { profile_pic: None, ..cached }, uncached.clone()];
+        let (to_server, mut requests) = mpsc::unbounded_channel();
+        service.request_missing_avatars(&chat, &to_server);
+        match requests.try_recv() {
+            Ok(ClientMessage::GetUserAvatars { user_ids }) => assert_eq!(user_ids, vec![uncached.id]),
+            other => panic!("unexpected request: {:?}", other),
+        }
+    }
+
+    /// A channel with `count` loaded messages spread over a hundred authors
+    fn channel_with(count: usize) -> ChatState {
+        let mut chat = ChatState::default();
+        let channel_id = uuid::Uuid::new_v4();
+        chat.channel_userlist = (0..100).map(|i| user(&format!("user{}", i), "pic")).collect();
+        chat.chat_messages = (0..count).map(|i| ChannelMessage {
+            id: uuid::Uuid::new_v4(),
+            channel_id,
+            sent_by: chat.channel_userlist[i % 100].id,
+            timestamp: i as i64,
+            content: format!("message number {}", i),
+        }).collect();
+        chat.set_current_chat_target(ChatTarget::Channel { server_id: uuid::Uuid::new_v4(), channel_id });
+        chat
+    }
+
+    fn newest_content(chat: &ChatState) -> String {
+        ChatService::with_message_list(chat, None, |messages| messages.last().unwrap().content.clone())
+    }
+
+    #[test]
+    fn message_list_is_rebuilt_only_after_invalidation() {
+        let mut chat = channel_with(3);
+        assert_eq!(newest_content(&chat), "message number 2");
+        let mut appended = chat.chat_messages[0].clone();
+        appended.content = "appended".to_string();
+        chat.chat_messages.push(appended);
+        // Still the list built before the push
+        assert_eq!(newest_content(&chat), "message number 2");
+        chat.invalidate_message_cache();
+        assert_eq!(newest_content(&chat), "appended");
+        // Renaming an author shows once invalidated
+        chat.channel_userlist[0].username = "renamed".to_string();
+        chat.invalidate_message_cache();
+        ChatService::with_message_list(&chat, None, |messages| assert_eq!(messages[0].author, "renamed"));
+    }
+
+    #[test]
+    fn switching_conversations_rebuilds_the_list() {
+        let mut chat = channel_with(3);
+        assert_eq!(newest_content(&chat), "message number 2");
+        chat.set_current_chat_target(ChatTarget::DM { user_id: uuid::Uuid::new_v4() });
+        ChatService::with_message_list(&chat, None, |messages| assert!(messages.is_empty()));
+    }
+
+    /// Per-frame cost of the message list in a channel with thousands of loaded
+    /// messages: a frame copies two screenfuls (the oldest page and the window
+    /// around the view). Run with `cargo test -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn per_frame_message_list_cost() {
+        const FRAMES: u32 = 1000;
+        const SCREENFUL: usize = 50;
+        let chat = channel_with(5000);
+        let frame = |chat: &ChatState| {
+            ChatService::with_message_list(chat, None, |messages| {
+                let oldest = messages[..SCREENFUL].to_vec();
+                let window = messages[messages.len() - SCREENFUL..].to_vec();
+                oldest.len() + window.len()
+            })
+        };
+        let time = |f: &dyn Fn() -> usize| {
+            let start = Instant::now();
+            for _ in 0..FRAMES {
+                std::hint::black_box(f());
+            }
+            start.elapsed() / FRAMES
+        };
+        let rebuilt = time(&|| {
+            *chat.message_cache.borrow_mut() = None;
+            frame(&chat)
+        });
+        let ranges = time(&|| {
+            ChatService::build_message_range(&chat, None, 0..SCREENFUL).len()
+                + ChatService::build_message_range(&chat, None, 5000 - SCREENFUL..5000).len()
+        });
+        let cached = time(&|| frame(&chat));
+        eprintln!(
+            "5000 messages, per frame: {:?} rebuilding the list, {:?} building two ranges, {:?} cached",
+            rebuilt, ranges, cached,
+        );
+        assert!(cached < rebuilt);
+    }
+}
+
@@ -1,9 +1,12 @@
-use crate::state::AppError;
+use crate::state::{AppError, ProfileImageField};
 use std::io::Cursor;
 use base64::Engine;
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::fs;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 
@@ -180,6 +183,87 @@ impl ImageService {
         Ok(())
     }
 
+    /// Whether a server rejection reason is about an image being too large
+    pub fn is_image_size_rejection(reason: &str) -> bool {
+        let reason = reason.to_lowercase();
+        let about_size = ["too large", "too big", "exceeds", "maximum size", "max size", "size limit", "payload"]
+            .iter().any(|k| reason.contains(k));
+        let about_image = ["image", "pic", "avatar", "banner", "picture"]
+            .iter().any(|k| reason.contains(k));
+        about_size && about_image
+    }
+
+    /// Which image a size rejection names, if it names one
+    pub fn rejected_image_field(reason: &str) -> Option<ProfileImageField> {
+        let reason = reason.to_lowercase();
+        if reason.contains("banner") || reason.contains("cover") {
+            Some(ProfileImageField::CoverBanner)
+        } else if reason.contains("pic") || reason.contains("avatar") || reason.contains("profile image") {
+            Some(ProfileImageField::ProfilePic)
+        } else {
+            None
+        }
+    }
+
+    /// A pixel limit in a rejection reason ("max 512x512", "up to 800px"), if any
+    pub fn rejected_max_dimension(reason: &str) -> Option<u32> {
+        let lower = reason.to_lowercase();
+        let mut rest = lower.as_str();
+        while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+            let tail = &rest[start..];
+            let end = tail.find(|c: char| !c.is_ascii_digit()).unwrap_or(tail.len());
+            let after = tail[end..].trim_start();
+            if after.starts_with('x') || after.starts_with("px") || after.starts_with("pixel") {
+                if let Ok(n) = tail[..end].parse::<u32>() {
+                    if (16..=8192).contains(&n) {
+                        return Some(n);
+                    }
+                }
+            }
+            rest = &tail[end..];
+        }
+        None
+    }
+
+    /// Rough byte size of an edit-page image field (file path, data URL or raw base64)
+    pub fn approx_image_bytes(data: &str) -> u64 {
+        let trimmed = data.trim();
+        if trimmed.is_empty() {
+            return 0;
+        }
+        match std::fs::metadata(trimmed) {
+            Ok(meta) => meta.len(),
+            Err(_) => trimmed.len() as u64 * 3 / 4,
+        }
+    }
+
+    /// Shrink an image so its longest side is at most `max_dimension` pixels and
+    /// return it as a PNG data URL, with the new size
+    pub fn downscale_to_data_url(data: &str, max_dimension: u32) -> Result<(String, (u32, u32)), AppError> {
+        let trimmed = data.trim();
+        if trimmed.starts_with("http") {
+            return Err(AppError::Image("Can't downscale a URL; save the image and use its file path".to_string()));
+        }
+        let bytes = if std::path::Path::new(trimmed).exists() {
+            std::fs::read(trimmed)
+                .map_err(|e| AppError::Image(format!("Cannot read file '{}': {}", trimmed, e)))?
+        } else {
+            Self::decode_image_bytes(&Some(trimmed.to_string()))
+                .ok_or_else(|| AppError::Image("Failed to decode image data".to_string()))?
+        };
+        let img = image::load_from_memory(&bytes)
+            .map_err(|e| AppError::Image(format!("Invalid image data: {}", e)))?;
+        let img = if img.width() > max_dimension || img.height() > max_dimension {
+            img.thumbnail(max_dimension, max_dimension)
+        } else {
+            img
+        };
+        let mut buffer = Vec::new();
+        img.write_to(&mut Cursor::new(&mut buffer), image::ImageFormat::Png)
+            .map_err(|e| AppError::Image(format!("Failed to encode image: {}", e)))?;
+        Ok((format!("data:image/png;base64,{}", BASE64.encode(&buffer)), (img.width(), img.height())))
+    }
+
     /// Draw simple text on the image using a basic bitmap approach
     fn draw_simple_text(image: &mut image::RgbaImage, text: &str, banner_size: (u32, u32)) {
         if text.is_empty() {
@@ -399,6 +483,8 @@ pub struct ImageCacheConfig {
     pub max_entries: usize,
     pub default_ttl_seconds: u64,
     pub cleanup_interval_seconds: u64,
+    /// Directory mirroring the cache so images survive restarts; `None` keeps it in memory only
+    pub disk_dir: Option<PathBuf>,
 }
 
 impl Default for ImageCacheConfig {
@@ -408,6 +494,7 @@ impl Default for ImageCacheConfig {
             max_entries: 1000,
             default_ttl_seconds: 3600, // 1 hour
             cleanup_interval_seconds: 300, // 5 minutes
+            disk_dir: None,
         }
     }
 }
@@ -475,6 +562,29 @@ impl ImageFormat {
     }
 }
 
+impl ImageFormat {
+    /// Short tag stored in the on-disk header
+    fn disk_tag(&self) -> String {
+        match self {
+            ImageFormat::Png => "png".to_string(),
+            ImageFormat::Jpeg => "jpeg".to_string(),
+            ImageFormat::Gif => "gif".to_string(),
+            ImageFormat::WebP => "webp".to_string(),
+            ImageFormat::Base64(header) => format!("b64:{}", header),
+        }
+    }
+
+    fn from_disk_tag(tag: &str) -> Option<Self> {
+        Some(match tag {
+            "png" => ImageFormat::Png,
+            "jpeg" => ImageFormat::Jpeg,
+            "gif" => ImageFormat::Gif,
+            "webp" => ImageFormat::WebP,
+            _ => ImageFormat::Base64(tag.strip_prefix("b64:")?.to_string()),
+        })
+    }
+}
+
 impl CachedImage {
     pub fn new(data: Vec<u8>, format: ImageFormat, ttl_seconds: Option<u64>) -> Self {
         let now = SystemTime::now()
@@ -543,20 +653,64 @@ impl ImageCacheKey {
     }
 }
 
+impl ImageCacheKey {
+    /// File name in the disk cache; custom keys are hex-encoded to stay path-safe
+    fn file_name(&self) -> String {
+        match self {
+            Self::UserAvatar(id) => format!("avatar-{}", id),
+            Self::UserCoverBanner(id) => format!("cover-{}", id),
+            Self::ServerIcon(id) => format!("server-icon-{}", id),
+            Self::ServerBanner(id) => format!("server-banner-{}", id),
+            Self::Custom(key) => format!("custom-{}", key.bytes().map(|b| format!("{:02x}", b)).collect::<String>()),
+        }
+    }
+
+    fn from_file_name(name: &str) -> Option<Self> {
+        let uuid = |rest: &str| Uuid::parse_str(rest).ok();
+        if let Some(rest) = name.strip_prefix("avatar-") {
+            uuid(rest).map(Self::UserAvatar)
+        } else if let Some(rest) = name.strip_prefix("cover-") {
+            uuid(rest).map(Self::UserCoverBanner)
+        } else if let Some(rest) = name.strip_prefix("server-icon-") {
+            uuid(rest).map(Self::ServerIcon)
+        } else if let Some(rest) = name.strip_prefix("server-banner-") {
+            uuid(rest).map(Self::ServerBanner)
+        } else if let Some(hex) = name.strip_prefix("custom-") {
+            let bytes = (0..hex.len()).step_by(2)
+                .map(|i| hex.get(i..i + 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()))
+                .collect::<Option<Vec<u8>>>()?;
+            String::from_utf8(bytes).ok().map(Self::Custom)
+        } else {
+            None
+        }
+    }
+}
+
+/// Default location of the on-disk image cache: `~/.nexus_cache/images`
+pub fn default_disk_cache_dir() -> PathBuf {
+    crate::global_prefs::GlobalPrefs::config_file("cache").join("images")
+}
+
 /// Thread-safe image cache with LRU eviction and TTL
 pub struct ImageCache {
     cache: Arc<Mutex<HashMap<ImageCacheKey, CachedImage>>>,
     config: ImageCacheConfig,
     current_size_bytes: Arc<Mutex<usize>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
 }
 
 impl ImageCache {
     pub fn new(config: ImageCacheConfig) -> Self {
-        Self {
+        let cache = Self {
             cache: Arc::new(Mutex::new(HashMap::new())),
             config,
             current_size_bytes: Arc::new(Mutex::new(0)),
-        }
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        };
+        cache.load_from_disk();
+        cache
     }
 
     pub fn with_default_config() -> Self {
@@ -565,6 +719,11 @@ impl ImageCache {
 
     /// Store an image in the cache
     pub fn put(&self, key: ImageCacheKey, image: CachedImage) -> Result<(), String> {
+        self.insert(key, image, true)
+    }
+
+    /// `put`, optionally without writing through (for entries read from the disk store)
+    fn insert(&self, key: ImageCacheKey, image: CachedImage, write_to_disk: bool) -> Result<(), String> {
         let mut cache = self.cache.lock().map_err(|e| format!("Cache lock error: {}", e))?;
         let mut current_size = self.current_size_bytes.lock()
             .map_err(|e| format!("Size lock error: {}", e))?;
@@ -576,6 +735,7 @@ impl ImageCache {
             if let Some(evict_key) = self.find_lru_key(&cache) {
                 if let Some(evicted) = cache.remove(&evict_key) {
                     *current_size = current_size.saturating_sub(evicted.size_bytes);
+                    self.remove_from_disk(&evict_key);
                 }
             } else {
                 break; // No more entries to evict
@@ -583,8 +743,13 @@ impl ImageCache {
         }
 
         // Add the new image
+        if write_to_disk {
+            self.write_to_disk(&key, &image);
+        }
         *current_size += image.size_bytes;
-        cache.insert(key, image);
+        if let Some(replaced) = cache.insert(key, image) {
+            *current_size = current_size.saturating_sub(replaced.size_bytes);
+        }
 
         Ok(())
     }
@@ -600,14 +765,18 @@ impl ImageCache {
                     .map_err(|e| format!("Size lock error: {}", e))?;
                 *current_size = current_size.saturating_sub(image.size_bytes);
                 cache.remove(key);
+                self.remove_from_disk(key);
+                self.misses.fetch_add(1, Ordering::Relaxed);
                 return Ok(None);
             }
 
             // Update access information
             image.touch();
             cache.insert(key.clone(), image.clone());
+            self.hits.fetch_add(1, Ordering::Relaxed);
             Ok(Some(image))
         } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
             Ok(None)
         }
     }
@@ -633,6 +802,7 @@ impl ImageCache {
             let mut current_size = self.current_size_bytes.lock()
                 .map_err(|e| format!("Size lock error: {}", e))?;
             *current_size = current_size.saturating_sub(image.size_bytes);
+            self.remove_from_disk(key);
             Ok(Some(image))
         } else {
             Ok(None)
@@ -656,6 +826,8 @@ impl ImageCache {
         let current_size = *self.current_size_bytes.lock()
             .map_err(|e| format!("Size lock error: {}", e))?;
 
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
         let mut expired_count = 0;
         let mut total_access_count = 0;
 
@@ -672,7 +844,9 @@ impl ImageCache {
             total_size_mb: current_size as f64 / (1024.0 * 1024.0),
             expired_entries: expired_count,
             total_access_count,
-            hit_ratio: 0.0, // Would need to track misses to calculate
+            hit_ratio: if hits + misses == 0 { 0.0 } else { hits as f64 / (hits + misses) as f64 },
+            hits,
+            misses,
         })
     }
 
@@ -694,12 +868,81 @@ impl ImageCache {
         for key in to_remove {
             if let Some(image) = cache.remove(&key) {
                 *current_size = current_size.saturating_sub(image.size_bytes);
+                self.remove_from_disk(&key);
             }
         }
 
         Ok(removed_count)
     }
 
+    /// Delete everything in the disk cache (the in-memory cache is left alone; see `clear`)
+    pub fn clear_disk(&self) -> Result<(), String> {
+        let Some(dir) = &self.config.disk_dir else { return Ok(()) };
+        match fs::remove_dir_all(dir) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("Cannot clear {}: {}", dir.display(), e)),
+        }
+    }
+
+    /// Fill the cache from the disk store, dropping expired or unreadable files.
+    /// Entries go through the same eviction as `put`, so its size and entry limits apply to the disk store too.
+    fn load_from_disk(&self) {
+        let Some(dir) = &self.config.disk_dir else { return };
+        let Ok(entries) = fs::read_dir(dir) else { return };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let key = path.file_name().and_then(|n| n.to_str()).and_then(ImageCacheKey::from_file_name);
+            match key.zip(Self::read_disk_entry(&path)) {
+                Some((key, image)) if !image.is_expired() => {
+                    if let Err(e) = self.insert(key, image, false) {
+                        tracing::warn!("Image cache: cannot load {}: {}", path.display(), e);
+                    }
+                }
+                _ => {
+                    let _ = fs::remove_file(&path);
+                }
+            }
+        }
+    }
+
+    /// A disk entry is a `<cached at> <ttl> <format>` header line followed by the image bytes
+    fn read_disk_entry(path: &Path) -> Option<CachedImage> {
+        let bytes = fs::read(path).ok()?;
+        let newline = bytes.iter().position(|&b| b == b'\n')?;
+        let header = std::str::from_utf8(&bytes[..newline]).ok()?;
+        let mut fields = header.split(' ');
+        let timestamp_cached: u64 = fields.next()?.parse().ok()?;
+        let ttl_seconds: u64 = fields.next()?.parse().ok()?;
+        let format = ImageFormat::from_disk_tag(fields.next()?)?;
+        let data = bytes[newline + 1..].to_vec();
+        Some(CachedImage {
+            size_bytes: data.len(),
+            data,
+            format,
+            timestamp_cached,
+            ttl_seconds,
+            access_count: 0,
+            last_accessed: timestamp_cached,
+        })
+    }
+
+    fn write_to_disk(&self, key: &ImageCacheKey, image: &CachedImage) {
+        let Some(dir) = &self.config.disk_dir else { return };
+        let path = dir.join(key.file_name());
+        let mut bytes = format!("{} {} {}\n", image.timestamp_cached, image.ttl_seconds, image.format.disk_tag()).into_bytes();
+        bytes.extend_from_slice(&image.data);
+        if let Err(e) = fs::create_dir_all(dir).and_then(|_| fs::write(&path, bytes)) {
+            tracing::warn!("Image cache: cannot write {}: {}", path.display(), e);
+        }
+    }
+
+    fn remove_from_disk(&self, key: &ImageCacheKey) {
+        if let Some(dir) = &self.config.disk_dir {
+            let _ = fs::remove_file(dir.join(key.file_name()));
+        }
+    }
+
     /// Find the LRU key for eviction
     fn find_lru_key(&self, cache: &HashMap<ImageCacheKey, CachedImage>) -> Option<ImageCacheKey> {
         cache.iter()
@@ -733,4 +976,7 @@ pub struct ImageCacheStats {
     pub expired_entries: usize,
     pub total_access_count: u64,
     pub hit_ratio: f64,
+    /// `get` calls that found a live entry / found nothing (or an expired one)
+    pub hits: u64,
+    pub misses: u64,
 }
\ No newline at end of file
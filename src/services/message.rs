@@ -1,5 +1,18 @@
 use crate::state::AppConfig;
 
+/// A piece of message content, split on markdown-style backticks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageSegment<'a> {
+    Text(&'a str),
+    /// `` `code` `` on a single line
+    InlineCode(&'a str),
+    /// A ```` ``` ```` fenced block, without the fences or language tag
+    CodeBlock(&'a str),
+}
+
+/// Starts the quote line `quote_reply` puts before a reply
+const QUOTE_MARKER: &str = "> ↪ ";
+
 /// Service for message validation and processing
 pub struct MessageService;
 
@@ -20,6 +33,93 @@ impl MessageService {
         }
     }
     
+    /// Shorten text to at most `max_chars` characters, ending in "..." when cut.
+    /// Counts chars rather than bytes so multibyte text never splits mid-character.
+    pub fn truncate_with_ellipsis(text: &str, max_chars: usize) -> String {
+        if text.chars().count() <= max_chars {
+            return text.to_string();
+        }
+        let kept: String = text.chars().take(max_chars.saturating_sub(3)).collect();
+        format!("{}...", kept)
+    }
+    
+    /// Text to copy for a message: the URL when the message is only an image or
+    /// attachment marker (`![alt](url)`, `[image: url]`, `[attachment: url]`),
+    /// otherwise the content itself.
+    pub fn copyable_text(content: &str) -> String {
+        let trimmed = content.trim();
+        if trimmed.starts_with("![") && trimmed.ends_with(')') {
+            if let Some(start) = trimmed.find("](") {
+                return trimmed[start + 2..trimmed.len() - 1].trim().to_string();
+            }
+        }
+        if let Some(inner) = trimmed.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            for marker in ["image:", "attachment:"] {
+                if let Some(url) = inner.strip_prefix(marker) {
+                    return url.trim().to_string();
+                }
+            }
+        }
+        content.to_string()
+    }
+    
+    /// Prefix a reply with a one-line quote of the message it answers. The protocol has
+    /// no reply field, so the quote travels in the content as `> ↪ author: snippet`.
+    pub fn quote_reply(author: &str, quoted: &str, reply: &str) -> String {
+        let (_, quoted_body) = Self::split_quote(quoted);
+        let snippet = Self::truncate_with_ellipsis(Self::flatten_newlines(quoted_body).trim(), 60);
+        format!("{}{}: {}\n{}", QUOTE_MARKER, author, snippet, reply)
+    }
+    
+    /// Split a quote-reply into its quote line (without the marker) and body. A plain
+    /// `> ` block quote typed by hand isn't a reply and stays part of the body.
+    pub fn split_quote(content: &str) -> (Option<&str>, &str) {
+        if let Some(rest) = content.strip_prefix(QUOTE_MARKER) {
+            if let Some((quote, body)) = rest.split_once('\n') {
+                return (Some(quote), body);
+            }
+        }
+        (None, content)
+    }
+    
+    /// Replace line breaks with spaces for single-line inputs (a trailing one is dropped).
+    pub fn flatten_newlines(text: &str) -> String {
+        text.trim_end_matches(['\r', '\n']).replace("\r\n", " ").replace(['\n', '\r'], " ")
+    }
+    
+    /// Byte index where the word before `end` starts, shell-style: trailing spaces
+    /// plus one run of non-whitespace. Never crosses a newline; a newline directly
+    /// before `end` is deleted on its own so lines join one at a time.
+    pub fn word_start_before(text: &str, end: usize) -> usize {
+        let before = &text[..end];
+        let mut chars = before.char_indices().rev().peekable();
+        if let Some(&(i, '\n')) = chars.peek() {
+            return i;
+        }
+        let mut start = end;
+        while let Some(&(i, c)) = chars.peek() {
+            if c == '\n' || !c.is_whitespace() {
+                break;
+            }
+            start = i;
+            chars.next();
+        }
+        while let Some(&(i, c)) = chars.peek() {
+            if c.is_whitespace() {
+                break;
+            }
+            start = i;
+            chars.next();
+        }
+        start
+    }
+    
+    /// Delete the word at the end of `text` (Ctrl+W / Ctrl+Backspace).
+    pub fn delete_word_back(text: &mut String) {
+        let start = Self::word_start_before(text, text.len());
+        text.truncate(start);
+    }
+    
     pub fn is_command(content: &str) -> bool {
         content.trim().starts_with('/')
     }
@@ -44,4 +144,155 @@ impl MessageService {
     pub fn format_mention(username: &str) -> String {
         format!("@{}", username)
     }
-}
\ No newline at end of file
+    
+    /// Split content into plain text, inline code and fenced code blocks.
+    /// Backticks without a closing partner are kept as literal text.
+    pub fn parse_code_segments(content: &str) -> Vec<MessageSegment<'_>> {
+        let mut segments = Vec::new();
+        let mut rest = content;
+        while let Some(open) = rest.find("```") {
+            let after_open = &rest[open + 3..];
+            let Some(close) = after_open.find("```") else { break };
+            Self::push_inline_segments(&rest[..open], &mut segments);
+            let mut code = &after_open[..close];
+            // An opening fence followed by a word on its own line is a language tag
+            if let Some((first, body)) = code.split_once('\n') {
+                if !first.contains(char::is_whitespace) {
+                    code = body;
+                }
+            }
+            segments.push(MessageSegment::CodeBlock(code.strip_suffix('\n').unwrap_or(code)));
+            rest = after_open[close + 3..].strip_prefix('\n').unwrap_or(&after_open[close + 3..]);
+        }
+        Self::push_inline_segments(rest, &mut segments);
+        segments
+    }
+
+    fn push_inline_segments<'a>(text: &'a str, segments: &mut Vec<MessageSegment<'a>>) {
+        let mut rest = text;
+        while let Some(open) = rest.find('`') {
+            let after_open = &rest[open + 1..];
+            let Some(close) = after_open.find(['`', '\n']) else { break };
+            if close == 0 || !after_open[close..].starts_with('`') {
+                // Empty or unterminated on this line: keep the backtick literally
+                segments.push(MessageSegment::Text(&rest[..open + 1]));
+                rest = after_open;
+                continue;
+            }
+            if open > 0 {
+                segments.push(MessageSegment::Text(&rest[..open]));
+            }
+            segments.push(MessageSegment::InlineCode(&after_open[..close]));
+            rest = &after_open[close + 1..];
+        }
+        if !rest.is_empty() {
+            segments.push(MessageSegment::Text(rest));
+        }
+    }
+
+    /// The lines a message occupies once code fences are removed (before wrapping)
+    pub fn display_lines(content: &str) -> Vec<String> {
+        let mut lines = vec![String::new()];
+        for segment in Self::parse_code_segments(content) {
+            match segment {
+                MessageSegment::Text(text) | MessageSegment::InlineCode(text) => {
+                    let mut parts = text.split('\n');
+                    if let Some(first) = parts.next() {
+                        lines.last_mut().unwrap().push_str(first);
+                    }
+                    lines.extend(parts.map(str::to_string));
+                }
+                MessageSegment::CodeBlock(code) => {
+                    if lines.last().is_some_and(|l| l.is_empty()) {
+                        lines.pop();
+                    }
+                    lines.extend(code.split('\n').map(str::to_string));
+                    lines.push(String::new());
+                }
+            }
+        }
+        if lines.len() > 1 && lines.last().is_some_and(|l| l.is_empty()) {
+            lines.pop();
+        }
+        lines
+    }
+    
+    /// Case-insensitive fuzzy match: every query character must appear in order.
+    /// Returns a score (higher is better) and the matched char indices of `text`.
+    /// Consecutive runs and matches at word starts score higher, so "gen" ranks
+    /// "General" above "Off-topic: engines".
+    pub fn fuzzy_match(query: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+        let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+        if query.is_empty() {
+            return Some((0, Vec::new()));
+        }
+        let chars: Vec<char> = text.chars().collect();
+        let mut indices = Vec::with_capacity(query.len());
+        let mut score = 0i64;
+        let mut q = 0;
+        for (i, ch) in chars.iter().enumerate() {
+            if q == query.len() {
+                break;
+            }
+            if ch.to_lowercase().eq(std::iter::once(query[q])) {
+                score += 1;
+                if indices.last().is_some_and(|&last| last + 1 == i) {
+                    score += 5;
+                }
+                if i == 0 || !chars[i - 1].is_alphanumeric() {
+                    score += 3;
+                }
+                indices.push(i);
+                q += 1;
+            }
+        }
+        if q < query.len() {
+            return None;
+        }
+        // Prefer shorter texts and earlier matches among equals
+        score -= (chars.len() as i64) / 8 + indices[0] as i64 / 4;
+        Some((score, indices))
+    }
+
+    /// Byte ranges of the http(s) links in text, without trailing punctuation
+    pub fn find_urls(text: &str) -> Vec<(usize, usize)> {
+        let url_re = regex::Regex::new(r#"https?://[^\s<>"'`]+"#).unwrap();
+        url_re.find_iter(text)
+            .map(|m| {
+                let url = m.as_str().trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']', '}', '\'']);
+                (m.start(), m.start() + url.len())
+            })
+            .filter(|&(start, end)| text[start..end].split_once("://").is_some_and(|(_, rest)| !rest.is_empty()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_reply_round_trips_through_split_quote() {
+        let content = MessageService::quote_reply("alice", "hello there", "hi!");
+        assert_eq!(MessageService::split_quote(&content), (Some("alice: hello there"), "hi!"));
+    }
+
+    #[test]
+    fn hand_typed_block_quote_is_not_a_reply() {
+        let content = "> to be or not to be\nthat is the question";
+        assert_eq!(MessageService::split_quote(content), (None, content));
+    }
+
+    #[test]
+    fn truncation_counts_chars_not_bytes() {
+        // Short multibyte names come back whole even though they're longer in bytes
+        assert_eq!(MessageService::truncate_with_ellipsis("Zoë", 3), "Zoë");
+        assert_eq!(MessageService::truncate_with_ellipsis("山田太郎", 4), "山田太郎");
+        // Cuts land between characters, never inside one
+        assert_eq!(MessageService::truncate_with_ellipsis("José Müller-Lüdenscheidt", 10), "José Mü...");
+        assert_eq!(MessageService::truncate_with_ellipsis("山田太郎さんのサーバー", 8), "山田太郎さ...");
+        assert_eq!(MessageService::truncate_with_ellipsis("🦀🦀🦀🦀🦀", 4), "🦀...");
+        // Too narrow for any of the name
+        assert_eq!(MessageService::truncate_with_ellipsis("éééé", 2), "...");
+    }
+}
@@ -2,8 +2,10 @@ pub mod chat;
 pub mod message;
 pub mod profile;
 pub mod image;
+pub mod auth;
 
 pub use chat::ChatService;
 pub use message::MessageService;
 pub use profile::ProfileService;
-pub use image::ImageService;
\ No newline at end of file
+pub use image::ImageService;
+pub use auth::{AuthService, PasswordStrength};
\ No newline at end of file
@@ -30,8 +30,57 @@ impl ProfileService {
         Ok(())
     }
     
+    /// Checks that a URL has an http(s) scheme and a non-empty host.
     pub fn is_valid_url(url: &str) -> bool {
-        url.starts_with("http://") || url.starts_with("https://")
+        let rest = match url.strip_prefix("http://").or_else(|| url.strip_prefix("https://")) {
+            Some(rest) => rest,
+            None => return false,
+        };
+        if url.chars().any(|c| c.is_whitespace()) {
+            return false;
+        }
+        let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+        let host_port = authority.rsplit('@').next().unwrap_or("");
+        // Split off an optional numeric port (IPv6 literals keep their brackets)
+        let (host, port) = if host_port.starts_with('[') {
+            match host_port.find(']') {
+                Some(end) if end + 1 == host_port.len() => (host_port, None),
+                Some(end) => match host_port[end + 1..].strip_prefix(':') {
+                    Some(p) => (&host_port[..=end], Some(p)),
+                    None => return false,
+                },
+                None => return false,
+            }
+        } else {
+            match host_port.rsplit_once(':') {
+                Some((h, p)) => (h, Some(p)),
+                None => (host_port, None),
+            }
+        };
+        if let Some(port) = port {
+            if port.is_empty() || !port.chars().all(|c| c.is_ascii_digit()) {
+                return false;
+            }
+        }
+        !host.is_empty()
+            && !host.starts_with('.')
+            && !host.ends_with('.')
+            && !host.contains("..")
+    }
+
+    /// Trims a URL and prefixes `https://` when no scheme was given.
+    pub fn normalize_url(url: &str) -> String {
+        let trimmed = url.trim();
+        if trimmed.is_empty() || trimmed.contains("://") {
+            trimmed.to_string()
+        } else {
+            format!("https://{}", trimmed)
+        }
+    }
+
+    /// Whether a profile URL field is acceptable as typed (empty is allowed).
+    pub fn is_url_field_valid(url: &str) -> bool {
+        url.trim().is_empty() || Self::is_valid_url(&Self::normalize_url(url))
     }
     
     pub fn file_or_url_to_base64(val: &str) -> Result<Option<String>, String> {
@@ -70,4 +119,42 @@ impl ProfileService {
             }
         }
     }
-}
\ No newline at end of file
+
+    /// A pasted or dropped file path, cleaned up: terminals may wrap it in quotes,
+    /// escape spaces with backslashes or send it as a `file://` URL. `None` unless
+    /// the result is an existing file.
+    pub fn pasted_file_path(text: &str) -> Option<String> {
+        let mut path = text.trim();
+        for quote in ['\'', '"'] {
+            if path.len() >= 2 && path.starts_with(quote) && path.ends_with(quote) {
+                path = &path[1..path.len() - 1];
+            }
+        }
+        let path = match path.strip_prefix("file://") {
+            Some(url_path) => Self::percent_decode(url_path),
+            None => path.replace("\\ ", " "),
+        };
+        Path::new(&path).is_file().then_some(path)
+    }
+
+    fn percent_decode(text: &str) -> String {
+        let bytes = text.as_bytes();
+        let mut out = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            let hex = (bytes[i] == b'%').then(|| text.get(i + 1..i + 3)).flatten()
+                .and_then(|h| u8::from_str_radix(h, 16).ok());
+            match hex {
+                Some(byte) => {
+                    out.push(byte);
+                    i += 3;
+                }
+                None => {
+                    out.push(bytes[i]);
+                    i += 1;
+                }
+            }
+        }
+        String::from_utf8_lossy(&out).into_owned()
+    }
+}
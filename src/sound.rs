@@ -1,7 +1,10 @@
-// SoundManager for playing UI sounds
-use crate::global_prefs::global_prefs;
+// SoundManager for playing UI sounds. Any sound can be replaced by dropping a WAV, OGG
+// or MP3 named after it (`mention.wav`, `popup_open.ogg`, see `SoundType::file_stem`)
+// into ~/.nexus_sounds/, or the `sounds_dir` set in ~/.nexus_prefs.json.
 #[cfg(not(target_env = "musl"))]
 use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+#[cfg(not(target_env = "musl"))]
+use std::cell::Cell;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
@@ -24,54 +27,124 @@ pub enum SoundType {
     Save,
 }
 
+impl SoundType {
+    pub const ALL: &'static [SoundType] = &[
+        SoundType::ChangeChannel,
+        SoundType::SendChannelMessage,
+        SoundType::ReceiveChannelMessage,
+        SoundType::DirectMessage,
+        SoundType::Error,
+        SoundType::Notify,
+        SoundType::LoginSuccess,
+        SoundType::LoginFailure,
+        SoundType::MessageSent,
+        SoundType::Mention,
+        SoundType::PopupOpen,
+        SoundType::PopupClose,
+        SoundType::Select,
+        SoundType::Scroll,
+        SoundType::Save,
+    ];
+
+    /// File name (without extension) of the built-in sound, also used for custom overrides
+    pub fn file_stem(&self) -> &'static str {
+        match self {
+            SoundType::ChangeChannel => "change_channel",
+            SoundType::SendChannelMessage => "send_channel_message",
+            SoundType::ReceiveChannelMessage => "receive_channel_message",
+            SoundType::DirectMessage => "received_direct_message",
+            SoundType::Error => "error",
+            SoundType::Notify => "notify",
+            SoundType::LoginSuccess => "login_success",
+            SoundType::LoginFailure => "login_failure",
+            SoundType::MessageSent => "sent_direct_message",
+            SoundType::Mention => "mention",
+            SoundType::PopupOpen => "popup_open",
+            SoundType::PopupClose => "popup_close",
+            SoundType::Select => "select",
+            SoundType::Scroll => "scroll",
+            SoundType::Save => "save",
+        }
+    }
+
+    /// Sounds announcing something that arrived, silenced by Do Not Disturb
+    pub fn is_alert(&self) -> bool {
+        matches!(
+            self,
+            SoundType::ReceiveChannelMessage | SoundType::DirectMessage | SoundType::Mention | SoundType::Notify
+        )
+    }
+
+    #[cfg(not(target_env = "musl"))]
+    fn builtin_path(&self) -> String {
+        match self {
+            SoundType::Error => "sounds/error.mp3".to_string(),
+            _ => format!("assets/sounds/{}.mp3", self.file_stem()),
+        }
+    }
+}
+
 #[cfg(not(target_env = "musl"))]
 pub struct SoundManager {
     _stream: OutputStream,
     stream_handle: OutputStreamHandle,
     sounds: HashMap<SoundType, Vec<u8>>, // Store sound data in memory
+    /// Sink volume, 0.0 (silent) to 1.0; set from the preferences by `App::apply_sound_settings`
+    volume: Cell<f32>,
+    /// Do Not Disturb: skip the sounds that announce incoming messages
+    dnd: Cell<bool>,
 }
 
 #[cfg(not(target_env = "musl"))]
 impl SoundManager {
     pub fn new() -> Self {
         let (_stream, stream_handle) = OutputStream::try_default().expect("Failed to open audio output");
+        let base_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let custom_dir = Self::custom_sounds_dir();
         let mut sounds = HashMap::new();
-        let base_path = env!("CARGO_MANIFEST_DIR");
-        let error_path = PathBuf::from(base_path).join("sounds/error.mp3");
-        let notify_path = PathBuf::from(base_path).join("assets/sounds/notify.mp3");
-        let login_success_path = PathBuf::from(base_path).join("assets/sounds/login_success.mp3");
-        let login_failure_path = PathBuf::from(base_path).join("assets/sounds/login_failure.mp3");
-        let received_direct_message_path = PathBuf::from(base_path).join("assets/sounds/received_direct_message.mp3");
-        let sent_direct_message_path = PathBuf::from(base_path).join("assets/sounds/sent_direct_message.mp3");
-        let mention_path = PathBuf::from(base_path).join("assets/sounds/mention.mp3");
-        let change_channel_path = PathBuf::from(base_path).join("assets/sounds/change_channel.mp3");
-        let send_channel_message_path = PathBuf::from(base_path).join("assets/sounds/send_channel_message.mp3");
-        let receive_channel_message_path = PathBuf::from(base_path).join("assets/sounds/receive_channel_message.mp3");
-        let popup_open_path = PathBuf::from(base_path).join("assets/sounds/popup_open.mp3");
-        let popup_close_path = PathBuf::from(base_path).join("assets/sounds/popup_close.mp3");
-        let scroll_path = PathBuf::from(base_path).join("assets/sounds/scroll.mp3");
-        let save_path = PathBuf::from(base_path).join("assets/sounds/save.mp3");
-        let select_path = PathBuf::from(base_path).join("assets/sounds/select.mp3");
-        sounds.insert(SoundType::Select, std::fs::read(select_path).unwrap_or_default());
-        sounds.insert(SoundType::Save, std::fs::read(save_path).unwrap_or_default());
-        sounds.insert(SoundType::Scroll, std::fs::read(scroll_path).unwrap_or_default());
-        sounds.insert(SoundType::ChangeChannel, std::fs::read(change_channel_path).unwrap_or_default());
-        sounds.insert(SoundType::SendChannelMessage, std::fs::read(send_channel_message_path).unwrap_or_default());
-        sounds.insert(SoundType::ReceiveChannelMessage, std::fs::read(receive_channel_message_path).unwrap_or_default());
-        sounds.insert(SoundType::PopupOpen, std::fs::read(popup_open_path).unwrap_or_default());
-        sounds.insert(SoundType::LoginSuccess, std::fs::read(login_success_path).unwrap_or_default());
-        sounds.insert(SoundType::LoginFailure, std::fs::read(login_failure_path).unwrap_or_default());
-        sounds.insert(SoundType::DirectMessage, std::fs::read(received_direct_message_path).unwrap_or_default());
-        sounds.insert(SoundType::MessageSent, std::fs::read(sent_direct_message_path).unwrap_or_default());
-        sounds.insert(SoundType::Error, std::fs::read(error_path).unwrap_or_default());
-        sounds.insert(SoundType::PopupClose, std::fs::read(popup_close_path).unwrap_or_default());
-        sounds.insert(SoundType::Notify, std::fs::read(notify_path).unwrap_or_default());
-        sounds.insert(SoundType::Mention, std::fs::read(mention_path).unwrap_or_default());
-        Self { _stream, stream_handle, sounds }
+        for &sound in SoundType::ALL {
+            let data = Self::load_custom(&custom_dir, sound)
+                .unwrap_or_else(|| std::fs::read(base_path.join(sound.builtin_path())).unwrap_or_default());
+            sounds.insert(sound, data);
+        }
+        Self { _stream, stream_handle, sounds, volume: Cell::new(1.0), dnd: Cell::new(false) }
+    }
+
+    /// Directory whose files override the built-in sounds: `sounds_dir` from the
+    /// preferences, else `~/.nexus_sounds`
+    fn custom_sounds_dir() -> PathBuf {
+        if let Some(dir) = crate::global_prefs::global_prefs().sounds_dir.clone() {
+            return PathBuf::from(dir);
+        }
+        crate::global_prefs::GlobalPrefs::config_file("sounds")
+    }
+
+    /// `<name>.wav`, `.ogg` or `.mp3` from the custom directory, if present and decodable.
+    /// A bad file is logged and skipped so the built-in sound plays instead.
+    fn load_custom(dir: &std::path::Path, sound: SoundType) -> Option<Vec<u8>> {
+        for ext in ["wav", "ogg", "mp3"] {
+            let path = dir.join(format!("{}.{}", sound.file_stem(), ext));
+            let Ok(data) = std::fs::read(&path) else { continue };
+            match Decoder::new(std::io::Cursor::new(data.clone())) {
+                Ok(_) => return Some(data),
+                Err(e) => tracing::warn!("Ignoring custom sound {}: {}", path.display(), e),
+            }
+        }
+        None
+    }
+
+    /// Volume as a percentage; 0 silences every sound
+    pub fn set_volume(&self, percent: u8) {
+        self.volume.set(percent.min(100) as f32 / 100.0);
+    }
+
+    pub fn set_dnd(&self, enabled: bool) {
+        self.dnd.set(enabled);
     }
 
     pub fn play(&self, sound: SoundType) {
-        if !global_prefs().sound_effects_enabled {
+        let volume = self.volume.get();
+        if volume <= 0.0 || (self.dnd.get() && sound.is_alert()) {
             return;
         }
         if let Some(data) = self.sounds.get(&sound) {
@@ -79,6 +152,7 @@ impl SoundManager {
                 let cursor = std::io::Cursor::new(data.clone());
                 if let Ok(decoder) = Decoder::new(cursor) {
                     if let Ok(sink) = Sink::try_new(&self.stream_handle) {
+                        sink.set_volume(volume);
                         sink.append(decoder);
                         sink.detach(); // Play in background
                     }
@@ -95,5 +169,7 @@ pub struct SoundManager;
 #[cfg(target_env = "musl")]
 impl SoundManager {
     pub fn new() -> Self { SoundManager }
+    pub fn set_volume(&self, _percent: u8) {}
+    pub fn set_dnd(&self, _enabled: bool) {}
     pub fn play(&self, _sound: SoundType) {}
 }
@@ -14,6 +14,7 @@ pub enum InputMode {
     NewForumName,
     NewForumDescription,
     UpdatePassword,
+    SearchMessages,
 }
 
 /// State management for authentication
@@ -22,6 +23,10 @@ pub struct AuthState {
     pub current_input: String,
     pub password_input: String,
     pub input_mode: Option<InputMode>,
+    /// What this session logged in with, to sign back in after a reconnect
+    pub session_credentials: Option<(String, String)>,
+    /// A Login was sent on a new connection and hasn't been answered yet
+    pub reauthenticating: bool,
 }
 
 impl Default for AuthState {
@@ -31,6 +36,8 @@ impl Default for AuthState {
             current_input: String::new(),
             password_input: String::new(),
             input_mode: Some(InputMode::LoginUsername),
+            session_credentials: None,
+            reauthenticating: false,
         }
     }
 }
@@ -47,6 +54,8 @@ impl AuthState {
     
     pub fn logout(&mut self) {
         self.current_user = None;
+        self.session_credentials = None;
+        self.reauthenticating = false;
         self.clear_inputs();
         self.input_mode = Some(InputMode::LoginUsername);
     }
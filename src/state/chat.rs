@@ -1,7 +1,12 @@
-use nexus_tui_common::{User, DirectMessage, Server, ChannelMessage};
+use nexus_tui_common::{User, UserRole, UserStatus, DirectMessage, Server, ChannelMessage};
+use crate::global_prefs::{OfflineUsersMode, UserListSort};
+use crate::unread_store::UnreadSnapshot;
 use uuid::Uuid;
 use std::collections::{HashMap, HashSet};
+use std::cell::RefCell;
+use crate::model::ChatMessageWithMeta;
 use ratatui::widgets::ListState;
+use serde::{Serialize, Deserialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ChatFocus {
@@ -11,18 +16,93 @@ pub enum ChatFocus {
     Sidebar,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SidebarTab {
     Servers,
     DMs,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ChatTarget {
     Channel { server_id: Uuid, channel_id: Uuid },
     DM { user_id: Uuid },
 }
 
+/// A message sent while offline, shown dimmed until the server echoes it back
+#[derive(Debug, Clone)]
+pub struct PendingSend {
+    pub id: Uuid,
+    pub target_id: Uuid, // Channel id, or the other user's id for a DM
+    pub content: String,
+    pub queued_at: i64,
+}
+
+/// The emoji picker (Ctrl+E): a search line over the whole emoji grid
+#[derive(Debug, Clone, Default)]
+pub struct EmojiPicker {
+    pub query: String,
+    pub matches: Vec<String>,
+    pub selected: usize,
+}
+
+impl EmojiPicker {
+    pub const COLS: usize = 8;
+    pub const ROWS: usize = 6;
+
+    pub fn new(favorites: &[String], recent: &[String]) -> Self {
+        let mut picker = Self::default();
+        picker.refilter(favorites, recent);
+        picker
+    }
+
+    /// Match the query again, back on the first emoji
+    pub fn refilter(&mut self, favorites: &[String], recent: &[String]) {
+        self.matches = crate::services::ChatService::emoji_matches(&self.query, favorites, recent);
+        self.selected = 0;
+    }
+
+    /// Move the highlight by `delta` cells; rows run on into the next/previous page
+    pub fn move_by(&mut self, delta: isize) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let target = self.selected as isize + delta;
+        self.selected = target.clamp(0, self.matches.len() as isize - 1) as usize;
+    }
+}
+
+/// Group rank of a user under the given sort (lower ranks are listed first).
+pub fn user_list_group_rank(user: &User, sort: UserListSort) -> u8 {
+    match sort {
+        UserListSort::Status => match user.status {
+            UserStatus::Connected => 0,
+            UserStatus::Busy => 1,
+            UserStatus::Away => 2,
+            UserStatus::Offline => 3,
+        },
+        UserListSort::Role => match user.role {
+            UserRole::Admin => 0,
+            UserRole::Moderator => 1,
+            UserRole::User => 2,
+        },
+        UserListSort::Name => 0,
+    }
+}
+
+/// Header shown above a user list group, if the sort groups users at all.
+pub fn user_list_group_label(user: &User, sort: UserListSort) -> Option<String> {
+    match sort {
+        UserListSort::Status => Some(match user.status {
+            UserStatus::Connected => "Online",
+            UserStatus::Busy => "Busy",
+            UserStatus::Away => "Away",
+            UserStatus::Offline => "Offline",
+        }.to_string()),
+        UserListSort::Role => Some(format!("{:?}", user.role)),
+        UserListSort::Name => None,
+    }
+}
+
 /// State management for chat functionality
 pub struct ChatState {
     // Server and channel data
@@ -33,19 +113,59 @@ pub struct ChatState {
     // Chat messages and scrolling
     pub chat_messages: Vec<ChannelMessage>,
     pub chat_scroll_offset: usize,
-    pub last_chat_rows: Option<usize>,
+    pub scroll_target: Option<usize>, // Smooth scrolling eases the offset toward this
+    pub last_visible_messages: Option<usize>, // Messages that fit on screen at last draw
+    pub max_scroll_offset: Option<usize>, // Largest offset that still fills the view, at last draw
+    pub new_messages_below: bool, // Messages arrived while scrolled up
+    
+    // Selected message (index into the current message list) and its id, used to
+    // keep the same message selected as the list changes
+    pub selected_message: Option<usize>,
+    pub selected_message_id: Option<Uuid>,
+    
+    // Message the next send replies to
+    pub reply_target: Option<Uuid>,
+    
+    // Messages queued while disconnected, awaiting their echo
+    pub pending_sends: Vec<PendingSend>,
+    
+    // Built rows of the current conversation and the conversation they're for,
+    // reused across frames until `invalidate_message_cache`
+    pub message_cache: RefCell<Option<(Option<ChatTarget>, Vec<ChatMessageWithMeta>)>>,
+    
+    // Search within the current conversation: query, matching message indices
+    // (into the current message list) and the match being shown
+    pub search_query: String,
+    pub search_matches: Vec<usize>,
+    pub search_index: usize,
     
     // Channel management
     pub channel_userlist: Vec<User>,
     pub channel_history_complete: HashMap<Uuid, bool>,
-    pub unread_channels: HashSet<Uuid>,
+    pub unread_channels: HashMap<Uuid, usize>, // Unread message count per channel
     
     // Direct messages
     pub dm_user_list: Vec<User>,
     pub selected_dm_user: Option<usize>,
     pub dm_messages: Vec<DirectMessage>,
     pub dm_history_complete: bool,
-    pub unread_dm_conversations: HashSet<Uuid>,
+    /// Tick an older-history request was sent on, until its page arrives
+    pub history_fetch_started: Option<u64>,
+    /// Conversation to load once sidebar navigation settles, and the tick it was selected on
+    pub pending_fetch: Option<(ChatTarget, u64)>,
+    /// The oldest loaded message was on screen in the last frame
+    pub history_top_visible: bool,
+    pub unread_dm_conversations: HashMap<Uuid, usize>, // Unread message count per DM partner
+    // Channels/DM partners already announced (sound/notification) since last viewed
+    pub notified_unread: HashSet<Uuid>,
+    // Newest message id seen per conversation (channel id or DM partner id)
+    pub last_read_message: HashMap<Uuid, Uuid>,
+    // Fetch read conversations again once the channel/DM lists arrive, to find what
+    // came in while this client wasn't connected
+    pub catch_up_channels: bool,
+    pub catch_up_dms: bool,
+    // Conversations fetched for that, whose reply counts as unread instead of being shown
+    pub awaiting_catch_up: HashSet<Uuid>,
     pub dm_input: String,
     pub dm_target: Option<Uuid>,
     
@@ -54,18 +174,29 @@ pub struct ChatState {
     pub sidebar_tab: SidebarTab,
     pub show_user_list: bool,
     pub user_list_state: ListState,
+    /// Username filter over the user list (`/` in the list)
+    pub user_filter: Option<crate::state::forum::ListFilter>,
     
     // Input drafts per chat target
     pub chat_input_drafts: HashMap<ChatTarget, String>,
     pub current_chat_target: Option<ChatTarget>,
+    // Conversation from the last session, reopened once the server/DM lists arrive
+    pub pending_restore: Option<ChatTarget>,
+    // An invite was just accepted; the server that shows up next gets opened
+    pub awaiting_invite_join: bool,
+    // Open conversation whose unread state waits on the mark-read preference, and the tick it was opened
+    pub pending_mark_read: Option<(ChatTarget, u64)>,
+    // Cursor within the current input, in chars
+    pub cursor_pos: usize,
     
     // Mention system
-    pub mention_suggestions: Vec<usize>,
+    pub mention_suggestions: Vec<String>,
     pub mention_selected: usize,
     pub mention_prefix: Option<String>,
     
     // Emoji system
     pub emoji_suggestions: Vec<String>,
+    pub emoji_picker: Option<EmojiPicker>,
     pub emoji_selected: usize,
     pub emoji_prefix: Option<String>,
 }
@@ -78,36 +209,183 @@ impl Default for ChatState {
             selected_channel: None,
             chat_messages: Vec::new(),
             chat_scroll_offset: 0,
-            last_chat_rows: None,
+            scroll_target: None,
+            last_visible_messages: None,
+            max_scroll_offset: None,
+            new_messages_below: false,
+            selected_message: None,
+            selected_message_id: None,
+            reply_target: None,
+            pending_sends: Vec::new(),
+            message_cache: RefCell::new(None),
+            search_query: String::new(),
+            search_matches: Vec::new(),
+            search_index: 0,
             channel_userlist: Vec::new(),
             channel_history_complete: HashMap::new(),
-            unread_channels: HashSet::new(),
+            unread_channels: HashMap::new(),
             dm_user_list: Vec::new(),
             selected_dm_user: None,
             dm_messages: Vec::new(),
             dm_history_complete: false,
-            unread_dm_conversations: HashSet::new(),
+            history_fetch_started: None,
+            pending_fetch: None,
+            history_top_visible: false,
+            unread_dm_conversations: HashMap::new(),
+            notified_unread: HashSet::new(),
+            last_read_message: HashMap::new(),
+            catch_up_channels: false,
+            catch_up_dms: false,
+            awaiting_catch_up: HashSet::new(),
             dm_input: String::new(),
             dm_target: None,
             chat_focus: ChatFocus::Messages,
             sidebar_tab: SidebarTab::Servers,
             show_user_list: true,
             user_list_state: ListState::default(),
+            user_filter: None,
             chat_input_drafts: HashMap::new(),
             current_chat_target: None,
+            pending_restore: None,
+            awaiting_invite_join: false,
+            cursor_pos: 0,
             mention_suggestions: Vec::new(),
             mention_selected: 0,
             mention_prefix: None,
             emoji_suggestions: Vec::new(),
+            emoji_picker: None,
             emoji_selected: 0,
             emoji_prefix: None,
+            pending_mark_read: None,
         }
     }
 }
 
 impl ChatState {
+    /// Drop the first pending copy of a message, once echoed back or dropped from the queue
+    pub fn remove_pending(&mut self, target_id: Uuid, content: &str) {
+        if let Some(idx) = self.pending_sends.iter().position(|p| p.target_id == target_id && p.content == content) {
+            self.pending_sends.remove(idx);
+            self.invalidate_message_cache();
+        }
+    }
+    
+    /// Drop the built message list so the next draw rebuilds it. Call after loaded
+    /// messages, queued sends or their authors change; switching conversations is
+    /// noticed without it.
+    pub fn invalidate_message_cache(&mut self) {
+        *self.message_cache.get_mut() = None;
+    }
+
     pub fn set_current_chat_target(&mut self, target: ChatTarget) {
         self.current_chat_target = Some(target);
+        self.history_fetch_started = None;
+        self.clear_message_selection();
+        self.reply_target = None;
+        self.clear_search();
+        self.cursor_pos = self.get_current_input().chars().count();
+    }
+    
+    /// Unread messages in a channel (0 when read)
+    pub fn unread_channel_count(&self, channel_id: &Uuid) -> usize {
+        self.unread_channels.get(channel_id).copied().unwrap_or(0)
+    }
+    
+    /// Unread messages from a DM partner (0 when read)
+    pub fn unread_dm_count(&self, user_id: &Uuid) -> usize {
+        self.unread_dm_conversations.get(user_id).copied().unwrap_or(0)
+    }
+    
+    /// Record an unread message in a channel or DM conversation (keyed by channel id
+    /// or DM partner id). Returns true only for the first one since it was last viewed,
+    /// so bursts produce a single sound/notification.
+    pub fn note_unread(&mut self, target: &ChatTarget) -> bool {
+        let id = match target {
+            ChatTarget::Channel { channel_id, .. } => {
+                *self.unread_channels.entry(*channel_id).or_insert(0) += 1;
+                *channel_id
+            }
+            ChatTarget::DM { user_id } => {
+                *self.unread_dm_conversations.entry(*user_id).or_insert(0) += 1;
+                *user_id
+            }
+        };
+        self.notified_unread.insert(id)
+    }
+    
+    /// Clear unread state for a conversation that is being viewed.
+    pub fn mark_read(&mut self, target: &ChatTarget) {
+        match target {
+            ChatTarget::Channel { channel_id, .. } => {
+                self.unread_channels.remove(channel_id);
+                self.notified_unread.remove(channel_id);
+            }
+            ChatTarget::DM { user_id } => {
+                self.unread_dm_conversations.remove(user_id);
+                self.notified_unread.remove(user_id);
+            }
+        }
+    }
+    
+    /// Remember the newest message seen in a conversation.
+    pub fn record_read(&mut self, conversation_id: Uuid, message_id: Uuid) {
+        self.last_read_message.insert(conversation_id, message_id);
+    }
+    
+    /// Count a freshly fetched page (oldest first) of a channel or DM conversation that isn't open:
+    /// what came after the last message read there is unread. A last-read message
+    /// older than the page means the whole page is. Conversations never read are left alone.
+    pub fn catch_up_unread(&mut self, id: Uuid, is_dm: bool, message_ids: &[Uuid]) {
+        let counts = if is_dm { &mut self.unread_dm_conversations } else { &mut self.unread_channels };
+        let Some(last_read) = self.last_read_message.get(&id) else { return };
+        let unread = match message_ids.iter().rposition(|m| m == last_read) {
+            Some(pos) => message_ids.len() - pos - 1,
+            None => message_ids.len(),
+        };
+        if unread > 0 {
+            let count = counts.entry(id).or_insert(0);
+            *count = (*count).max(unread);
+        }
+    }
+    
+    /// Unread state to persist. Last-read ids are limited to known conversations
+    /// once the server and DM lists have been loaded.
+    pub fn unread_snapshot(&self) -> UnreadSnapshot {
+        let mut last_read = self.last_read_message.clone();
+        if !self.servers.is_empty() || !self.dm_user_list.is_empty() {
+            let known: HashSet<Uuid> = self.servers.iter()
+                .flat_map(|s| s.channels.iter().map(|c| c.id))
+                .chain(self.dm_user_list.iter().map(|u| u.id))
+                .collect();
+            last_read.retain(|id, _| known.contains(id));
+        }
+        UnreadSnapshot {
+            channels: self.unread_channels.clone(),
+            dms: self.unread_dm_conversations.clone(),
+            last_read,
+            threads: HashMap::new(),
+        }
+    }
+    
+    pub fn restore_unread(&mut self, snapshot: UnreadSnapshot) {
+        self.unread_channels = snapshot.channels;
+        self.unread_dm_conversations = snapshot.dms;
+        self.last_read_message = snapshot.last_read;
+        self.notified_unread.clear();
+    }
+    
+    /// Drop unread entries for channels that no longer exist on any server.
+    pub fn prune_unread_channels(&mut self) {
+        let existing: HashSet<Uuid> = self.servers.iter()
+            .flat_map(|s| s.channels.iter().map(|c| c.id))
+            .collect();
+        self.unread_channels.retain(|id, _| existing.contains(id));
+    }
+    
+    /// Drop unread entries for DM partners no longer in the DM list.
+    pub fn prune_unread_dms(&mut self) {
+        let existing: HashSet<Uuid> = self.dm_user_list.iter().map(|u| u.id).collect();
+        self.unread_dm_conversations.retain(|id, _| existing.contains(id));
     }
     
     pub fn get_current_input(&self) -> &str {
@@ -122,16 +400,274 @@ impl ChatState {
         if let Some(target) = &self.current_chat_target {
             self.chat_input_drafts.insert(target.clone(), value);
         }
+        self.cursor_pos = self.input_cursor();
+    }
+    
+    /// Replace the current input, keeping the cursor the same distance from the end
+    /// so text auto-inserted before it (mentions, emoji) pushes it along.
+    pub fn replace_current_input(&mut self, value: String) {
+        let from_end = self.get_current_input().chars().count().saturating_sub(self.input_cursor());
+        let new_len = value.chars().count();
+        self.set_current_input(value);
+        self.cursor_pos = new_len.saturating_sub(from_end);
+    }
+    
+    /// Cursor position clamped to the current input, in chars.
+    pub fn input_cursor(&self) -> usize {
+        self.cursor_pos.min(self.get_current_input().chars().count())
+    }
+    
+    pub fn cursor_byte_index(&self) -> usize {
+        let input = self.get_current_input();
+        input.char_indices().nth(self.input_cursor()).map(|(i, _)| i).unwrap_or(input.len())
+    }
+    
+    pub fn insert_at_cursor(&mut self, c: char) {
+        let mut input = self.get_current_input().to_string();
+        let at = self.cursor_byte_index();
+        let cursor = self.input_cursor();
+        input.insert(at, c);
+        self.set_current_input(input);
+        self.cursor_pos = cursor + 1;
+    }
+    
+    pub fn insert_str_at_cursor(&mut self, text: &str) {
+        let mut input = self.get_current_input().to_string();
+        let at = self.cursor_byte_index();
+        let cursor = self.input_cursor();
+        input.insert_str(at, text);
+        self.set_current_input(input);
+        self.cursor_pos = cursor + text.chars().count();
+    }
+    
+    /// Delete the char before the cursor; a no-op at the start of the input.
+    pub fn backspace_at_cursor(&mut self) {
+        let cursor = self.input_cursor();
+        if cursor == 0 {
+            return;
+        }
+        let mut input = self.get_current_input().to_string();
+        self.cursor_pos = cursor - 1;
+        let at = self.cursor_byte_index();
+        input.remove(at);
+        self.set_current_input(input);
+    }
+    
+    /// Delete the word before the cursor (Ctrl+W / Ctrl+Backspace).
+    pub fn delete_word_before_cursor(&mut self) {
+        let end = self.cursor_byte_index();
+        let mut input = self.get_current_input().to_string();
+        let start = crate::services::MessageService::word_start_before(&input, end);
+        let removed = input[start..end].chars().count();
+        input.replace_range(start..end, "");
+        let cursor = self.input_cursor();
+        self.set_current_input(input);
+        self.cursor_pos = cursor - removed;
+    }
+    
+    pub fn move_cursor_left(&mut self) {
+        self.cursor_pos = self.input_cursor().saturating_sub(1);
+    }
+    
+    pub fn move_cursor_right(&mut self) {
+        self.cursor_pos = (self.input_cursor() + 1).min(self.get_current_input().chars().count());
+    }
+    
+    pub fn move_cursor_home(&mut self) {
+        self.cursor_pos = 0;
+    }
+    
+    pub fn move_cursor_end(&mut self) {
+        self.cursor_pos = self.get_current_input().chars().count();
+    }
+    
+    /// Whether a conversation other than the current one holds unsent text.
+    pub fn has_pending_draft(&self, target: &ChatTarget) -> bool {
+        self.current_chat_target.as_ref() != Some(target)
+            && self.chat_input_drafts.get(target).map(|d| !d.trim().is_empty()).unwrap_or(false)
     }
     
     pub fn clear_current_input(&mut self) {
         if let Some(target) = &self.current_chat_target {
             self.chat_input_drafts.insert(target.clone(), String::new());
         }
+        self.cursor_pos = 0;
+    }
+    
+    /// Sort `channel_userlist` in display order, keeping the highlighted user selected.
+    pub fn sort_user_list(&mut self, sort: UserListSort, offline: OfflineUsersMode) {
+        let previous = self.user_list_state.selected();
+        let selected_id = self.selected_user_list_index(offline).map(|i| self.channel_userlist[i].id);
+        self.channel_userlist.sort_by(|a, b| {
+            user_list_group_rank(a, sort).cmp(&user_list_group_rank(b, sort))
+                .then_with(|| a.username.to_lowercase().cmp(&b.username.to_lowercase()))
+        });
+        let visible = self.visible_user_indices(offline);
+        let row = selected_id
+            .and_then(|id| visible.iter().position(|&i| self.channel_userlist[i].id == id))
+            .or_else(|| previous.map(|row| row.min(visible.len().saturating_sub(1))))
+            .filter(|_| !visible.is_empty());
+        self.user_list_state.select(row);
+    }
+    
+    /// Indices into `channel_userlist` of the selectable user list rows, in display order.
+    pub fn visible_user_indices(&self, offline: OfflineUsersMode) -> Vec<usize> {
+        let query = self.user_filter.as_ref()
+            .map(|filter| filter.query.to_lowercase())
+            .filter(|query| !query.is_empty());
+        self.channel_userlist.iter().enumerate()
+            .filter(|(_, u)| offline == OfflineUsersMode::Show || u.status != UserStatus::Offline)
+            .filter(|(_, u)| query.as_ref().is_none_or(|q| u.username.to_lowercase().contains(q)))
+            .map(|(i, _)| i)
+            .collect()
+    }
+    
+    pub fn offline_user_count(&self) -> usize {
+        self.channel_userlist.iter().filter(|u| u.status == UserStatus::Offline).count()
+    }
+    
+    /// The `channel_userlist` index of the highlighted user list row.
+    pub fn selected_user_list_index(&self, offline: OfflineUsersMode) -> Option<usize> {
+        let row = self.user_list_state.selected()?;
+        self.visible_user_indices(offline).get(row).copied()
     }
     
     pub fn reset_scroll_offset(&mut self) {
         self.chat_scroll_offset = 0;
+        self.scroll_target = None;
+        self.new_messages_below = false;
+    }
+    
+    /// Keep the view steady after a message is appended to the current conversation:
+    /// follow it at the bottom, otherwise stay put and flag that there's more below.
+    pub fn on_message_appended(&mut self) {
+        self.resync_message_selection();
+        if self.final_scroll_offset() > 0 {
+            self.chat_scroll_offset += 1;
+            if let Some(target) = self.scroll_target.as_mut() {
+                *target += 1;
+            }
+            self.new_messages_below = true;
+        } else if self.selected_message.is_some() {
+            self.ensure_selected_message_visible();
+        } else {
+            self.reset_scroll_offset();
+        }
+    }
+    
+    /// Ids of the current conversation's messages, in message list order.
+    pub fn current_message_ids(&self) -> Vec<Uuid> {
+        match &self.current_chat_target {
+            Some(ChatTarget::Channel { .. }) => self.chat_messages.iter().map(|m| m.id).collect(),
+            Some(ChatTarget::DM { .. }) => self.dm_messages.iter().map(|m| m.id).collect(),
+            None => Vec::new(),
+        }
+    }
+    
+    pub fn clear_search(&mut self) {
+        self.search_query.clear();
+        self.search_matches.clear();
+        self.search_index = 0;
+    }
+    
+    /// Whether the whole history of the current conversation has been loaded.
+    pub fn history_complete(&self) -> bool {
+        match &self.current_chat_target {
+            Some(ChatTarget::Channel { channel_id, .. }) => {
+                self.channel_history_complete.get(channel_id).copied().unwrap_or(false)
+            }
+            Some(ChatTarget::DM { .. }) => self.dm_history_complete,
+            None => true,
+        }
+    }
+    
+    pub fn clear_message_selection(&mut self) {
+        self.selected_message = None;
+        self.selected_message_id = None;
+    }
+    
+    /// Move the selection by `delta` messages (negative is older). Starts from the
+    /// newest message; moving past the newest clears the selection.
+    pub fn move_message_selection(&mut self, delta: isize) {
+        let ids = self.current_message_ids();
+        if ids.is_empty() {
+            self.clear_message_selection();
+            return;
+        }
+        let next = match self.selected_message {
+            None if delta < 0 => Some(ids.len() - 1),
+            None => None,
+            Some(idx) => {
+                let moved = idx as isize + delta;
+                if moved >= ids.len() as isize {
+                    None
+                } else {
+                    Some(moved.max(0) as usize)
+                }
+            }
+        };
+        self.selected_message = next;
+        self.selected_message_id = next.map(|i| ids[i]);
+        self.ensure_selected_message_visible();
+    }
+    
+    /// Re-find the selected message after the list changed (new or older messages).
+    pub fn resync_message_selection(&mut self) {
+        if let Some(id) = self.selected_message_id {
+            self.selected_message = self.current_message_ids().iter().position(|&m| m == id);
+            if self.selected_message.is_none() {
+                self.selected_message_id = None;
+            }
+        }
+    }
+    
+    /// Adjust the scroll offset so the selected message is on screen.
+    pub fn ensure_selected_message_visible(&mut self) {
+        let Some(idx) = self.selected_message else { return };
+        let total = self.current_message_ids().len();
+        let from_bottom = total.saturating_sub(idx + 1);
+        let visible = self.last_visible_messages.unwrap_or(1).max(1);
+        let offset = self.final_scroll_offset();
+        let target = if from_bottom < offset {
+            from_bottom
+        } else if from_bottom >= offset + visible {
+            from_bottom + 1 - visible
+        } else {
+            offset
+        };
+        self.chat_scroll_offset = target;
+        self.scroll_target = None;
+    }
+    
+    /// Where the viewport is headed: the smooth-scroll target, or the current offset.
+    pub fn final_scroll_offset(&self) -> usize {
+        self.scroll_target.unwrap_or(self.chat_scroll_offset)
+    }
+    
+    /// Scroll to `target`, jumping immediately or easing there over the next ticks.
+    pub fn scroll_to(&mut self, target: usize, smooth: bool) {
+        if smooth && target != self.chat_scroll_offset {
+            self.scroll_target = Some(target);
+        } else {
+            self.chat_scroll_offset = target;
+            self.scroll_target = None;
+        }
+    }
+    
+    /// Move a third of the remaining distance toward the smooth-scroll target.
+    pub fn step_smooth_scroll(&mut self) {
+        if let Some(target) = self.scroll_target {
+            let distance = target.abs_diff(self.chat_scroll_offset);
+            let step = distance.div_ceil(3);
+            if target > self.chat_scroll_offset {
+                self.chat_scroll_offset += step;
+            } else {
+                self.chat_scroll_offset -= step;
+            }
+            if self.chat_scroll_offset == target {
+                self.scroll_target = None;
+            }
+        }
     }
     
     // pub fn update_scroll_offset(&mut self, offset: usize, max_rows: usize) {
@@ -156,4 +692,58 @@ impl ChatState {
         self.emoji_prefix = None;
         self.emoji_selected = 0;
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_input(text: &str) -> ChatState {
+        let mut chat = ChatState {
+            current_chat_target: Some(ChatTarget::DM { user_id: Uuid::new_v4() }),
+            ..ChatState::default()
+        };
+        chat.set_current_input(text.to_string());
+        chat.move_cursor_end();
+        chat
+    }
+
+    #[test]
+    fn cursor_moves_by_chars_over_multibyte_names() {
+        let mut chat = with_input("@山田 héllo");
+        assert_eq!(chat.input_cursor(), 9);
+        assert_eq!(chat.cursor_byte_index(), "@山田 héllo".len());
+        chat.move_cursor_home();
+        chat.move_cursor_right();
+        chat.move_cursor_right();
+        assert_eq!(chat.input_cursor(), 2);
+        assert_eq!(chat.cursor_byte_index(), "@山".len());
+        chat.insert_at_cursor('本');
+        assert_eq!(chat.get_current_input(), "@山本田 héllo");
+        assert_eq!(chat.input_cursor(), 3);
+    }
+
+    #[test]
+    fn editing_around_cjk_and_emoji_keeps_char_boundaries() {
+        let mut chat = with_input("こんにちは🦀");
+        chat.backspace_at_cursor();
+        assert_eq!(chat.get_current_input(), "こんにちは");
+        chat.move_cursor_left();
+        chat.insert_str_at_cursor("、世界");
+        assert_eq!(chat.get_current_input(), "こんにち、世界は");
+        assert_eq!(chat.input_cursor(), 7);
+        chat.move_cursor_end();
+        assert_eq!(chat.input_cursor(), "こんにち、世界は".chars().count());
+    }
+
+    #[test]
+    fn replacing_input_keeps_cursor_distance_from_end_in_chars() {
+        let mut chat = with_input("@Zo hi");
+        chat.move_cursor_left();
+        chat.move_cursor_left();
+        chat.replace_current_input("@Zoë Ångström hi".to_string());
+        assert_eq!(chat.input_cursor(), "@Zoë Ångström hi".chars().count() - 2);
+        assert_eq!(chat.cursor_byte_index(), "@Zoë Ångström ".len());
+    }
+}
+
@@ -1,6 +1,16 @@
 use nexus_tui_common::{Forum, Thread};
 use uuid::Uuid;
+use std::collections::HashMap;
 use ratatui::widgets::ListState;
+use crate::services::MessageService;
+
+/// Search-as-you-type filter over the forum or thread list
+#[derive(Debug, Clone, Default)]
+pub struct ListFilter {
+    pub query: String,
+    /// Selection before the filter was opened, restored on Esc
+    pub prior_selection: Option<usize>,
+}
 
 /// State management for forum functionality
 pub struct ForumState {
@@ -12,15 +22,26 @@ pub struct ForumState {
     // UI state
     pub forum_list_state: ListState,
     pub thread_list_state: ListState,
+    pub forum_filter: Option<ListFilter>,
+    pub thread_filter: Option<ListFilter>,
     
     // Post navigation state
     pub selected_post_index: Option<usize>,
     pub selected_reply_index: Option<usize>,
     pub reply_to_post_id: Option<Uuid>,
-    pub scroll_offset: usize,
+    /// Post view scroll in screen rows, clamped to the content when drawn
+    pub post_scroll: u16,
+    /// Rows the post view showed last frame, for paging
+    pub post_view_height: u16,
+    /// Scroll the selected post into view on the next draw
+    pub follow_selected_post: bool,
     pub show_reply_context: bool,
     pub show_thread_navigation: bool,
     pub thread_nav_selection: Option<usize>,
+    /// Post waiting on a yes/no before it's deleted
+    pub confirm_delete_post: Option<Uuid>,
+    /// Thread id -> newest post id read, persisted with the chat unread state
+    pub thread_last_read: HashMap<Uuid, Uuid>,
 }
 
 impl Default for ForumState {
@@ -32,13 +53,19 @@ impl Default for ForumState {
             pending_new_thread_title: None,
             forum_list_state: ListState::default(),
             thread_list_state: ListState::default(),
+            forum_filter: None,
+            thread_filter: None,
             selected_post_index: None,
             selected_reply_index: None,
             reply_to_post_id: None,
-            scroll_offset: 0,
+            post_scroll: 0,
+            post_view_height: 0,
+            follow_selected_post: false,
             show_reply_context: false,
             show_thread_navigation: false,
             thread_nav_selection: None,
+            confirm_delete_post: None,
+            thread_last_read: HashMap::new(),
         }
     }
 }
@@ -55,18 +82,55 @@ impl ForumState {
                 .and_then(|id| forum.threads.iter().find(|t| t.id == id)))
     }
     
+    /// Forum indices to show, best match first while filtering
+    pub fn visible_forum_indices(&self) -> Vec<usize> {
+        let names: Vec<&str> = self.forums.iter().map(|f| f.name.as_str()).collect();
+        filter_indices(&names, self.forum_filter.as_ref())
+    }
+
+    /// Thread indices of the current forum to show, best match first while filtering
+    pub fn visible_thread_indices(&self) -> Vec<usize> {
+        let Some(forum) = self.get_current_forum() else { return Vec::new() };
+        let titles: Vec<&str> = forum.threads.iter().map(|t| t.title.as_str()).collect();
+        filter_indices(&titles, self.thread_filter.as_ref())
+    }
+
     pub fn select_forum(&mut self, forum_id: Uuid) {
         self.current_forum_id = Some(forum_id);
         self.thread_list_state.select(Some(0));
+        self.thread_filter = None;
     }
     
     pub fn select_thread(&mut self, thread_id: Uuid) {
         self.current_thread_id = Some(thread_id);
+        self.mark_current_thread_read();
         // Reset post navigation when entering a thread
         self.selected_post_index = Some(0);
         self.selected_reply_index = None;
         self.reply_to_post_id = None;
-        self.scroll_offset = 0;
+        self.post_scroll = 0;
+        self.follow_selected_post = false;
+    }
+    
+    /// Whether the thread has posts newer than the last one read
+    pub fn is_thread_unread(&self, thread: &Thread) -> bool {
+        thread.posts.last().is_some_and(|post| self.thread_last_read.get(&thread.id) != Some(&post.id))
+    }
+    
+    /// Record the open thread's newest post as read
+    pub fn mark_current_thread_read(&mut self) {
+        if let Some((thread_id, post_id)) = self.get_current_thread().and_then(|t| Some((t.id, t.posts.last()?.id))) {
+            self.thread_last_read.insert(thread_id, post_id);
+        }
+    }
+    
+    /// Read markers to persist, limited to known threads once the forums have loaded
+    pub fn thread_read_snapshot(&self) -> HashMap<Uuid, Uuid> {
+        let mut read = self.thread_last_read.clone();
+        if !self.forums.is_empty() {
+            read.retain(|id, _| self.forums.iter().any(|f| f.threads.iter().any(|t| t.id == *id)));
+        }
+        read
     }
     
     pub fn clear_pending_thread(&mut self) {
@@ -92,42 +156,18 @@ impl ForumState {
         }
     }
     
-    pub fn scroll_posts(&mut self, direction: i32, amount: usize) {
-        if let Some(thread) = self.get_current_thread() {
-            if thread.posts.is_empty() { return; }
-            
-            if direction > 0 {
-                // Scroll down (increase offset)
-                let max_offset = thread.posts.len().saturating_sub(1);
-                self.scroll_offset = (self.scroll_offset + amount).min(max_offset);
-            } else {
-                // Scroll up (decrease offset)
-                self.scroll_offset = self.scroll_offset.saturating_sub(amount);
-            }
+    /// Scroll the post view by `amount` rows, down for a positive direction
+    pub fn scroll_posts(&mut self, direction: i32, amount: u16) {
+        self.follow_selected_post = false;
+        if direction > 0 {
+            self.post_scroll = self.post_scroll.saturating_add(amount);
+        } else {
+            self.post_scroll = self.post_scroll.saturating_sub(amount);
         }
     }
     
     pub fn auto_scroll_to_selected_post(&mut self) {
-        if let (Some(thread), Some(selected_idx)) = (self.get_current_thread(), self.selected_post_index) {
-            let posts_len = thread.posts.len();
-            if posts_len == 0 { return; }
-            
-            // Estimate how many posts fit on screen (rough calculation)
-            let visible_posts = 5; // Approximate posts visible at once
-            
-            // If selected post is above visible area, scroll up
-            if selected_idx < self.scroll_offset {
-                self.scroll_offset = selected_idx;
-            }
-            // If selected post is below visible area, scroll down
-            else if selected_idx >= self.scroll_offset + visible_posts {
-                self.scroll_offset = selected_idx.saturating_sub(visible_posts - 1);
-            }
-            
-            // Keep scroll offset within bounds
-            let max_offset = posts_len.saturating_sub(visible_posts);
-            self.scroll_offset = self.scroll_offset.min(max_offset);
-        }
+        self.follow_selected_post = true;
     }
     
     pub fn get_replies_to_post(&self, post_id: Uuid) -> Vec<(usize, &nexus_tui_common::Post)> {
@@ -163,10 +203,6 @@ impl ForumState {
         self.reply_to_post_id = post_id;
     }
     
-    pub fn scroll_to_post(&mut self, post_index: usize) {
-        self.scroll_offset = post_index;
-    }
-    
     pub fn get_selected_post(&self) -> Option<&nexus_tui_common::Post> {
         if let (Some(thread), Some(idx)) = (self.get_current_thread(), self.selected_post_index) {
             thread.posts.get(idx)
@@ -192,6 +228,16 @@ impl ForumState {
         }
     }
     
+    /// Whether `user` may delete `post`: their own posts, or any post for admins
+    pub fn can_delete_post(post: &nexus_tui_common::Post, user: Option<&nexus_tui_common::User>) -> bool {
+        user.is_some_and(|u| u.id == post.author.id || u.role == nexus_tui_common::UserRole::Admin)
+    }
+    
+    /// A post of the current thread by id
+    pub fn get_post(&self, post_id: Uuid) -> Option<&nexus_tui_common::Post> {
+        self.get_current_thread()?.posts.iter().find(|p| p.id == post_id)
+    }
+    
     /// Find the post that the currently selected post replied to
     pub fn get_replied_to_post(&self) -> Option<(&nexus_tui_common::Post, usize)> {
         if let Some(selected_post) = self.get_selected_post() {
@@ -321,4 +367,16 @@ pub enum ThreadNavItem<'a> {
         post_index: usize,
         reply_index: usize,
     },
-}
\ No newline at end of file
+}
+
+fn filter_indices(texts: &[&str], filter: Option<&ListFilter>) -> Vec<usize> {
+    let query = match filter {
+        Some(filter) if !filter.query.is_empty() => &filter.query,
+        _ => return (0..texts.len()).collect(),
+    };
+    let mut scored: Vec<(i64, usize)> = texts.iter().enumerate()
+        .filter_map(|(i, text)| MessageService::fuzzy_match(query, text).map(|(score, _)| (score, i)))
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    scored.into_iter().map(|(_, i)| i).collect()
+}
@@ -5,9 +5,9 @@ pub mod auth;
 pub mod notification;
 pub mod ui;
 
-pub use chat::{ChatState, ChatFocus, SidebarTab, ChatTarget};
+pub use chat::{ChatState, ChatFocus, SidebarTab, ChatTarget, PendingSend};
 pub use forum::ForumState;
-pub use profile::{ProfileState, ProfileEditFocus};
+pub use profile::{ProfileState, ProfileEditFocus, ProfileImageField, DownscalePrompt};
 pub use auth::{AuthState, InputMode};
 pub use notification::NotificationState;
 pub use ui::{UiState, AppMode};
@@ -19,7 +19,17 @@ pub struct AppConfig {
     pub scroll_lines_per_page: usize,
     pub notification_timeout_ms: u64,
     pub min_two_column_width: u16,
-    pub avatar_pixel_size: u32,
+    pub profile_tooltip_delay_ticks: u64,
+    pub profile_request_timeout_ticks: u64,
+    pub reconnect_base_delay_ticks: u64,
+    pub reconnect_max_delay_ticks: u64,
+    pub idle_pause_ticks: u64,
+    pub power_check_interval_ticks: u64,
+    pub double_esc_window_ticks: u64,
+    pub history_fetch_timeout_ticks: u64,
+    pub chat_fetch_debounce_ticks: u64,
+    pub timed_dnd_ticks: u64,
+    pub outbox_capacity: usize,
 }
 
 impl Default for AppConfig {
@@ -29,7 +39,17 @@ impl Default for AppConfig {
             scroll_lines_per_page: 20,
             notification_timeout_ms: 4000,
             min_two_column_width: 110,
-            avatar_pixel_size: 32,
+            profile_tooltip_delay_ticks: 10, // ~500ms at the 50ms tick rate
+            profile_request_timeout_ticks: 100, // ~5s at the 50ms tick rate
+            reconnect_base_delay_ticks: 20, // ~1s at the 50ms tick rate, doubling per attempt
+            reconnect_max_delay_ticks: 600, // ~30s
+            idle_pause_ticks: 1200, // ~60s at the 50ms tick rate
+            power_check_interval_ticks: 600, // ~30s at the 50ms tick rate
+            double_esc_window_ticks: 16, // ~800ms at the 50ms tick rate
+            history_fetch_timeout_ticks: 100, // ~5s at the 50ms tick rate
+            chat_fetch_debounce_ticks: 3, // ~150ms at the 50ms tick rate
+            timed_dnd_ticks: 72_000, // ~1h at the 50ms tick rate
+            outbox_capacity: 100,
         }
     }
 }
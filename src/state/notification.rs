@@ -1,10 +1,16 @@
 use nexus_tui_common::Notification;
+use ratatui::widgets::ListState;
+use crate::state::AppMode;
 
 /// State management for notifications
 pub struct NotificationState {
-    pub notifications: Vec<Notification>,
+    pub notifications: Vec<Notification>, // Newest first
     pub notification_history_complete: bool,
     pub current_notification: Option<(String, Option<u64>, bool)>, // message, close_tick, minimal
+    // Notification center
+    pub list_state: ListState,
+    pub return_mode: Option<AppMode>,
+    pub fetching_older: bool,
 }
 
 impl Default for NotificationState {
@@ -13,6 +19,9 @@ impl Default for NotificationState {
             notifications: Vec::new(),
             notification_history_complete: false,
             current_notification: None,
+            list_state: ListState::default(),
+            return_mode: None,
+            fetching_older: false,
         }
     }
 }
@@ -33,6 +42,32 @@ impl NotificationState {
     //     }
     // }
 
+    /// Take in a page from the server: an older page is appended, anything else replaces the list
+    pub fn receive_page(&mut self, page: Vec<Notification>, history_complete: bool) {
+        if self.fetching_older {
+            let new: Vec<Notification> = page.into_iter()
+                .filter(|n| !self.notifications.iter().any(|existing| existing.id == n.id))
+                .collect();
+            self.notifications.extend(new);
+        } else {
+            self.notifications = page;
+        }
+        self.notifications.sort_by_key(|n| std::cmp::Reverse(n.created_at));
+        self.notification_history_complete = history_complete;
+        self.fetching_older = false;
+        let last = self.notifications.len().checked_sub(1);
+        let selected = self.list_state.selected().map(|i| i.min(last.unwrap_or(0)));
+        self.list_state.select(selected.filter(|_| last.is_some()));
+    }
+
+    pub fn unread_count(&self) -> usize {
+        self.notifications.iter().filter(|n| !n.read).count()
+    }
+
+    pub fn selected_notification(&self) -> Option<&Notification> {
+        self.list_state.selected().and_then(|i| self.notifications.get(i))
+    }
+
     pub fn should_close_notification(&self, tick_count: u64) -> bool {
         if let Some((_, Some(close_tick), _)) = &self.current_notification {
             tick_count >= *close_tick
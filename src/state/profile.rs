@@ -1,7 +1,8 @@
 use nexus_tui_common::UserProfile;
 use uuid::Uuid;
-use ratatui_image::{picker::Picker, protocol::StatefulProtocol};
+use ratatui_image::{picker::{Picker, ProtocolType}, protocol::StatefulProtocol};
 use std::collections::HashMap;
+use crate::ui::avatar::{AvatarDecoder, HalfblockAvatar};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProfileEditFocus {
@@ -18,6 +19,40 @@ pub enum ProfileEditFocus {
     Cancel,
 }
 
+/// One of the two images on the edit profile page
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileImageField {
+    ProfilePic,
+    CoverBanner,
+}
+
+impl ProfileImageField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProfileImageField::ProfilePic => "profile picture",
+            ProfileImageField::CoverBanner => "cover banner",
+        }
+    }
+
+    /// Size to offer when the server doesn't say what it accepts
+    pub fn default_max_dimension(&self) -> u32 {
+        match self {
+            ProfileImageField::ProfilePic => 256,
+            ProfileImageField::CoverBanner => 1024,
+        }
+    }
+}
+
+/// "Downscale and retry" dialog, opened when the server rejects an image as too large
+#[derive(Debug, Clone)]
+pub struct DownscalePrompt {
+    pub field: ProfileImageField,
+    /// Longest side in pixels, as typed
+    pub max_dimension: String,
+    /// The server's reason, shown in the dialog
+    pub reason: String,
+}
+
 /// State management for user profile functionality
 pub struct ProfileState {
     // Profile editing
@@ -31,6 +66,11 @@ pub struct ProfileState {
     pub profile_edit_focus: ProfileEditFocus,
     pub profile_edit_error: Option<String>,
     pub profile_requested_by_user: bool,
+    /// An `UpdateProfile` was sent and the server hasn't answered yet
+    pub profile_save_pending: bool,
+    pub downscale_prompt: Option<DownscalePrompt>,
+    /// In-flight "View Profile" request: (user id, tick it was sent on)
+    pub pending_profile_request: Option<(Uuid, u64)>,
     
     // Profile viewing
     pub profile_view: Option<UserProfile>,
@@ -38,14 +78,29 @@ pub struct ProfileState {
     
     // Image rendering
     pub picker: Picker,
+    /// Protocol found by the terminal query, restored when nothing is forced
+    pub detected_protocol: ProtocolType,
+    /// Whether `picker` is using a protocol forced by the user
+    pub protocol_forced: bool,
     pub profile_image_state: Option<StatefulProtocol>,
     pub profile_banner_image_state: Option<StatefulProtocol>,
     pub avatar_protocol_cache: HashMap<(Uuid, u32), StatefulProtocol>,
+    /// Half-block avatars keyed by user and cell size (width, height)
+    pub halfblock_avatar_cache: HashMap<(Uuid, u16, u16), HalfblockAvatar>,
+    /// Background decoding feeding both avatar caches
+    pub avatar_decoder: AvatarDecoder,
+    /// Profile editor previews of the image fields, keyed by the field text they were decoded from
+    pub edit_pic_preview: Option<(String, Result<StatefulProtocol, String>)>,
+    pub edit_banner_preview: Option<(String, Result<StatefulProtocol, String>)>,
     
     // User actions
     pub show_user_actions: bool,
     pub user_actions_selected: usize,
     pub user_actions_target: Option<usize>,
+    
+    // Hover tooltip (compact profile preview)
+    pub hover_user_id: Option<Uuid>,
+    pub hover_started_tick: u64,
 }
 
 impl ProfileState {
@@ -57,6 +112,7 @@ impl ProfileState {
             );
             Picker::from_fontsize((16, 16))
         });
+        let detected_protocol = picker.protocol_type();
 
         Self {
             edit_bio: String::new(),
@@ -69,18 +125,42 @@ impl ProfileState {
             profile_edit_focus: ProfileEditFocus::Bio,
             profile_edit_error: None,
             profile_requested_by_user: false,
+            profile_save_pending: false,
+            downscale_prompt: None,
+            pending_profile_request: None,
             profile_view: None,
             show_profile_view_popup: false,
             picker,
+            detected_protocol,
+            protocol_forced: false,
             profile_image_state: None,
             profile_banner_image_state: None,
             avatar_protocol_cache: HashMap::new(),
+            halfblock_avatar_cache: HashMap::new(),
+            avatar_decoder: AvatarDecoder::new(),
+            edit_pic_preview: None,
+            edit_banner_preview: None,
             show_user_actions: false,
             user_actions_selected: 0,
             user_actions_target: None,
+            hover_user_id: None,
+            hover_started_tick: 0,
         }
     }
     
+    /// Switch the picker to a forced protocol (or back to the detected one).
+    /// Cached avatars were encoded for the old protocol, so they are dropped.
+    pub fn apply_image_protocol(&mut self, forced: Option<ProtocolType>) {
+        let protocol = forced.unwrap_or(self.detected_protocol);
+        self.protocol_forced = forced.is_some();
+        if self.picker.protocol_type() != protocol {
+            self.picker.set_protocol_type(protocol);
+            self.avatar_protocol_cache.clear();
+            self.profile_image_state = None;
+            self.profile_banner_image_state = None;
+        }
+    }
+
     pub fn load_profile_for_editing(&mut self, profile: &UserProfile) {
         self.edit_bio = profile.bio.as_deref().unwrap_or("").to_string();
         self.edit_url1 = profile.url1.as_deref().unwrap_or("").to_string();
@@ -92,6 +172,20 @@ impl ProfileState {
         self.profile_edit_error = None;
     }
     
+    pub fn edit_image(&self, field: ProfileImageField) -> &str {
+        match field {
+            ProfileImageField::ProfilePic => &self.edit_profile_pic,
+            ProfileImageField::CoverBanner => &self.edit_cover_banner,
+        }
+    }
+
+    pub fn edit_image_mut(&mut self, field: ProfileImageField) -> &mut String {
+        match field {
+            ProfileImageField::ProfilePic => &mut self.edit_profile_pic,
+            ProfileImageField::CoverBanner => &mut self.edit_cover_banner,
+        }
+    }
+
     // pub fn clear_edit_state(&mut self) {
     //     self.edit_bio.clear();
     //     self.edit_url1.clear();
@@ -104,6 +198,22 @@ impl ProfileState {
     //     self.profile_edit_focus = ProfileEditFocus::Bio;
     // }
     
+    pub fn begin_profile_request(&mut self, user_id: Uuid, tick: u64) {
+        self.profile_requested_by_user = true;
+        self.pending_profile_request = Some((user_id, tick));
+    }
+    
+    pub fn finish_profile_request(&mut self) {
+        self.profile_requested_by_user = false;
+        self.pending_profile_request = None;
+    }
+    
+    pub fn is_profile_request_timed_out(&self, tick: u64, timeout_ticks: u64) -> bool {
+        self.pending_profile_request
+            .map(|(_, sent)| tick.saturating_sub(sent) >= timeout_ticks)
+            .unwrap_or(false)
+    }
+    
     pub fn set_profile_for_viewing(&mut self, profile: UserProfile) {
         self.profile_view = Some(profile);
         self.show_profile_view_popup = true;
@@ -116,8 +226,22 @@ impl ProfileState {
         self.profile_view = None;
     }
     
+    /// Track which user is hovered; the timer restarts whenever the target changes.
+    pub fn update_hover(&mut self, user_id: Option<Uuid>, tick: u64) {
+        if self.hover_user_id != user_id {
+            self.hover_user_id = user_id;
+            self.hover_started_tick = tick;
+        }
+    }
+    
+    pub fn is_hover_tooltip_ready(&self, tick: u64, delay_ticks: u64) -> bool {
+        self.hover_user_id.is_some() && tick.saturating_sub(self.hover_started_tick) >= delay_ticks
+    }
+    
     pub fn invalidate_avatar_cache(&mut self, user_id: Uuid) {
         self.avatar_protocol_cache.retain(|(uid, _), _| *uid != user_id);
+        self.halfblock_avatar_cache.retain(|(uid, _, _), _| *uid != user_id);
+        self.avatar_decoder.forget_user(user_id);
     }
 }
 
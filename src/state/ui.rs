@@ -1,7 +1,9 @@
+use ratatui::layout::{Position, Rect};
 use ratatui::widgets::ListState;
+use std::time::Instant;
 use uuid::Uuid;
 
-#[derive(PartialEq, Debug, Clone)]
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub enum AppMode {
     Login, 
     Register, 
@@ -15,6 +17,83 @@ pub enum AppMode {
     EditProfile, 
     ColorPicker, 
     Preferences,
+    NotificationCenter,
+    ServerPicker,
+    AppearancePicker,
+}
+
+/// A sidebar row that can be clicked
+#[derive(Debug, Clone, Copy)]
+pub enum SidebarClick {
+    Server(usize),
+    Channel(usize, usize),
+    Dm(usize), // Index into `dm_user_list`
+}
+
+/// A popup entry that can be clicked
+#[derive(Debug, Clone, Copy)]
+pub enum PopupClick {
+    QuitYes,
+    QuitNo,
+    UserAction(usize),
+    ServerInvite(usize),
+}
+
+/// Where clickable things were drawn in the last frame, for mapping mouse clicks
+#[derive(Debug, Default)]
+pub struct ClickMap {
+    pub message_list: Option<Rect>,
+    pub post_view: Option<Rect>,
+    pub sidebar_tabs: Option<Rect>,
+    pub sidebar_rows: Vec<(Rect, SidebarClick)>,
+    pub user_rows: Vec<(Rect, usize)>, // Row in the displayed user list
+    pub popup_rows: Vec<(Rect, PopupClick)>,
+}
+
+impl ClickMap {
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn sidebar_at(&self, pos: Position) -> Option<SidebarClick> {
+        self.sidebar_rows.iter().find(|(r, _)| r.contains(pos)).map(|(_, c)| *c)
+    }
+
+    pub fn user_at(&self, pos: Position) -> Option<usize> {
+        self.user_rows.iter().find(|(r, _)| r.contains(pos)).map(|(_, i)| *i)
+    }
+
+    pub fn popup_at(&self, pos: Position) -> Option<PopupClick> {
+        self.popup_rows.iter().find(|(r, _)| r.contains(pos)).map(|(_, c)| *c)
+    }
+}
+
+/// What the server last reported about itself (shown in the debug overlay)
+#[derive(Debug, Default, Clone)]
+pub struct ServerMetrics {
+    pub query_time_ms: Option<u64>,
+    pub query_cache_hit_rate: Option<f64>,
+    pub message_count: Option<usize>,
+    /// Server image cache: entries, MB, hit ratio, expired entries
+    pub cache: Option<(usize, f64, f64, usize)>,
+}
+
+/// Entries of the server actions popup (F5)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerAction {
+    ViewUsers,
+    InviteUser,
+    CopyInviteCode,
+}
+
+impl ServerAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ServerAction::ViewUsers => "View full user list",
+            ServerAction::InviteUser => "Invite user",
+            ServerAction::CopyInviteCode => "Copy invite code",
+        }
+    }
 }
 
 /// State management for UI-specific state
@@ -22,6 +101,22 @@ pub struct UiState {
     pub mode: AppMode,
     pub should_quit: bool,
     pub tick_count: u64,
+    // Animation clock for backgrounds; stands still while they're paused
+    pub background_tick: u64,
+    /// Something on screen changed during a tick; the render loop redraws even when idle
+    pub needs_redraw: bool,
+    pub last_input_tick: u64,
+    // First `g` of a vim `g g` (jump to top) was pressed in a list
+    pub pending_g: bool,
+    /// Tick of the last Esc, if it was the last key pressed (Esc-Esc to quit)
+    pub last_esc_tick: Option<u64>,
+    /// Tick a timed Do Not Disturb turns itself off on
+    pub dnd_expires_tick: Option<u64>,
+    pub on_battery: bool,
+    /// The terminal window is focused (terminals without focus reporting always count as focused)
+    pub has_focus: bool,
+    // Screen the Input popup was opened from, drawn behind it
+    pub input_return_mode: Option<AppMode>,
     
     // List states for various UI components
     pub main_menu_state: ListState,
@@ -50,6 +145,58 @@ pub struct UiState {
     pub show_server_error: bool,
     pub server_error_message: String,
     pub should_retry_connection: bool,
+    pub connecting: bool, // A connection attempt is running in the background
+    
+    // Untrusted server certificate waiting on the user (accept / reject)
+    pub trust_prompt: Option<crate::known_hosts::PendingTrust>,
+    pub trust_prompt_selected: usize,
+    
+    // Automatic reconnection
+    pub auto_reconnect: bool,
+    pub reconnect_attempt: u32,
+    pub next_reconnect_tick: Option<u64>,
+    
+    // Connection is plain TCP (no TLS)
+    pub insecure_connection: bool,
+    
+    // Servers from ~/.nexus_servers.toml, the one connected to (if picked from
+    // there), a pick waiting to be connected and a request to go back to the picker
+    pub server_profiles: Vec<crate::server_profiles::ServerProfile>,
+    pub server_picker_state: ListState,
+    pub active_profile: Option<usize>,
+    pub connect_profile: Option<usize>,
+    pub should_disconnect: bool,
+    
+    // About popup and the connection details it shows
+    pub show_about: bool,
+    pub show_shortcuts: bool,
+    pub server_address: String,
+    pub proxy_address: Option<String>,
+    /// `--image-protocol` from the command line; takes precedence over preferences
+    pub image_protocol_override: Option<crate::global_prefs::ImageProtocol>,
+    /// `--theme`/`--background`, until changed with F8/F7
+    pub theme_override: Option<String>,
+    pub background_override: Option<String>,
+    /// `--no-sound`
+    pub sound_disabled: bool,
+    // Appearance picker (F3): backgrounds and themes side by side, previewed live
+    pub appearance_return_mode: Option<AppMode>,
+    pub appearance_backgrounds: ListState,
+    pub appearance_themes: ListState,
+    pub appearance_themes_focused: bool,
+    /// Theme and background to go back to if the picker is cancelled
+    pub appearance_original: Option<(String, String)>,
+    pub server_version: Option<String>, // Not reported by the current protocol
+    
+    // Debug overlay (F12): frame rate and the server's last reported metrics
+    pub show_debug_overlay: bool,
+    pub fps: u32,
+    fps_window_start: Option<Instant>,
+    fps_window_frames: u32,
+    pub server_metrics: ServerMetrics,
+    
+    // Clickable regions from the last draw (mouse support)
+    pub click_map: ClickMap,
     
     // Connected users (for legacy compatibility)
     pub connected_users: Vec<nexus_tui_common::User>,
@@ -61,6 +208,14 @@ impl Default for UiState {
             mode: AppMode::Login,
             should_quit: false,
             tick_count: 0,
+            background_tick: 0,
+            needs_redraw: false,
+            last_input_tick: 0,
+            pending_g: false,
+            last_esc_tick: None,
+            dnd_expires_tick: None,
+            on_battery: false,
+            has_focus: true,
             main_menu_state: ListState::default(),
             settings_list_state: ListState::default(),
             color_picker_selected: 0,
@@ -75,6 +230,39 @@ impl Default for UiState {
             show_server_error: false,
             server_error_message: String::new(),
             should_retry_connection: false,
+            connecting: false,
+            trust_prompt: None,
+            trust_prompt_selected: 0,
+            auto_reconnect: false,
+            reconnect_attempt: 0,
+            next_reconnect_tick: None,
+            insecure_connection: false,
+            server_profiles: Vec::new(),
+            server_picker_state: ListState::default(),
+            active_profile: None,
+            connect_profile: None,
+            should_disconnect: false,
+            show_about: false,
+            show_shortcuts: false,
+            input_return_mode: None,
+            server_address: String::new(),
+            proxy_address: None,
+            image_protocol_override: None,
+            theme_override: None,
+            background_override: None,
+            sound_disabled: false,
+            appearance_return_mode: None,
+            appearance_backgrounds: ListState::default(),
+            appearance_themes: ListState::default(),
+            appearance_themes_focused: false,
+            appearance_original: None,
+            server_version: None,
+            show_debug_overlay: false,
+            fps: 0,
+            fps_window_start: None,
+            fps_window_frames: 0,
+            server_metrics: ServerMetrics::default(),
+            click_map: ClickMap::default(),
             connected_users: Vec::new(),
         }
     }
@@ -93,6 +281,27 @@ impl UiState {
         self.tick_count += 1;
     }
     
+    /// Count a drawn frame; `fps` is updated once a second
+    pub fn record_frame(&mut self) {
+        let start = *self.fps_window_start.get_or_insert_with(Instant::now);
+        self.fps_window_frames += 1;
+        let elapsed = start.elapsed();
+        if elapsed.as_secs() >= 1 {
+            self.fps = (self.fps_window_frames as f64 / elapsed.as_secs_f64()).round() as u32;
+            self.fps_window_frames = 0;
+            self.fps_window_start = Some(Instant::now());
+        }
+    }
+    
+    /// Record user input, resuming idle-paused animations
+    pub fn note_input(&mut self) {
+        self.last_input_tick = self.tick_count;
+    }
+    
+    pub fn is_idle(&self, idle_ticks: u64) -> bool {
+        self.tick_count.saturating_sub(self.last_input_tick) >= idle_ticks
+    }
+    
     pub fn reset_selections(&mut self) {
         self.main_menu_state.select(Some(0));
         self.settings_list_state.select(Some(0));
@@ -103,6 +312,36 @@ impl UiState {
         self.server_error_message = message;
     }
     
+    /// Queue another connection attempt, waiting `base_ticks` doubled for every attempt
+    /// so far (capped at `max_ticks`). Returns false once `max_attempts` have been made.
+    pub fn schedule_reconnect(&mut self, base_ticks: u64, max_ticks: u64, max_attempts: u32) -> bool {
+        if max_attempts != 0 && self.reconnect_attempt >= max_attempts {
+            self.next_reconnect_tick = None;
+            return false;
+        }
+        let delay = base_ticks.saturating_mul(1 << self.reconnect_attempt.min(16)).min(max_ticks);
+        self.reconnect_attempt += 1;
+        self.next_reconnect_tick = Some(self.tick_count + delay);
+        true
+    }
+    
+    /// Fire a scheduled reconnect once its tick arrives. Returns true when it fired.
+    pub fn poll_reconnect(&mut self) -> bool {
+        match self.next_reconnect_tick {
+            Some(tick) if self.tick_count >= tick => {
+                self.next_reconnect_tick = None;
+                self.should_retry_connection = true;
+                true
+            }
+            _ => false,
+        }
+    }
+    
+    pub fn reset_reconnect(&mut self) {
+        self.reconnect_attempt = 0;
+        self.next_reconnect_tick = None;
+    }
+    
     pub fn hide_server_error(&mut self) {
         self.show_server_error = false;
         self.server_error_message.clear();
@@ -0,0 +1,55 @@
+//! Appearance picker: backgrounds and themes, with the highlighted background drawn behind.
+
+use ratatui::{Frame, layout::{Rect, Layout, Constraint, Direction}, style::{Style, Color, Modifier}, widgets::{Block, Borders, BorderType, Clear, List, ListItem, Paragraph}};
+use crate::app::App;
+use crate::ui::popups::draw_centered_rect;
+
+pub fn draw_appearance_picker(f: &mut Frame, app: &mut App, area: Rect) {
+    // Live preview: the selected background fills the screen
+    if let Some(bg) = app.background_manager.get_current_background() {
+        bg.draw_background(f, app, area);
+    }
+
+    let popup = draw_centered_rect(area, 60, 70);
+    f.render_widget(Clear, popup);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(app.theme_manager.get_current_theme().colors().primary))
+        .title("Appearance");
+    let inner = block.inner(popup);
+    f.render_widget(block, popup);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .split(inner);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[0]);
+
+    let column = |title: &'static str, names: Vec<&str>, focused: bool| {
+        let border = if focused { Style::default().fg(Color::Cyan) } else { Style::default().fg(Color::DarkGray) };
+        let highlight = if focused {
+            Style::default().bg(Color::Cyan).fg(Color::Black).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+        };
+        let items: Vec<ListItem> = names.into_iter().map(|name| ListItem::new(name.to_string())).collect();
+        List::new(items)
+            .block(Block::default().borders(Borders::ALL).border_style(border).title(title))
+            .highlight_style(highlight)
+            .highlight_symbol(">> ")
+    };
+    let backgrounds = column("Background", app.background_manager.names(), !app.ui.appearance_themes_focused);
+    let themes = column("Theme", app.theme_manager.names(), app.ui.appearance_themes_focused);
+    f.render_stateful_widget(backgrounds, columns[0], &mut app.ui.appearance_backgrounds);
+    f.render_stateful_widget(themes, columns[1], &mut app.ui.appearance_themes);
+
+    f.render_widget(
+        Paragraph::new("[↑↓] Preview | [←→/Tab] Switch Column | [Enter] Apply | [Esc] Cancel")
+            .style(Style::default().fg(Color::DarkGray)),
+        rows[1],
+    );
+}
@@ -1,7 +1,8 @@
 //! Authentication (login/register) UI screens.
 
-use ratatui::{Frame, layout::{Rect, Layout, Constraint}, style::{Style, Color}, widgets::{Block, Paragraph, Borders}, text::{Span}};
+use ratatui::{Frame, layout::{Rect, Layout, Constraint}, style::{Style, Color, Modifier}, widgets::{Block, Paragraph, Borders, List, ListItem}, text::{Line, Span}};
 use crate::app::{App, InputMode};
+use crate::services::{AuthService, PasswordStrength};
 use ratatui::prelude::{Alignment, Direction};
 
 pub fn draw_login(f: &mut Frame, app: &mut App, area: Rect) {
@@ -23,7 +24,7 @@ pub fn draw_login(f: &mut Frame, app: &mut App, area: Rect) {
         Style::default().fg(Color::Yellow)
     } else { Style::default() };
     f.render_widget(
-        Paragraph::new("*".repeat(app.auth.password_input.len()))
+        Paragraph::new("*".repeat(app.auth.password_input.chars().count()))
             .block(Block::default().borders(Borders::ALL).title("Password")).style(password_style),
         chunks[1],
     );
@@ -43,9 +44,9 @@ pub fn draw_login(f: &mut Frame, app: &mut App, area: Rect) {
     f.render_widget(Paragraph::new(Span::styled("[ To Register ]", switch_style)).alignment(Alignment::Center), button_chunks[1]);
 
     if let Some(InputMode::LoginUsername) = &app.auth.input_mode {
-        f.set_cursor_position((chunks[0].x + app.auth.current_input.len() as u16 + 1, chunks[0].y + 1));
+        f.set_cursor_position((chunks[0].x + app.auth.current_input.chars().count() as u16 + 1, chunks[0].y + 1));
     } else if let Some(InputMode::LoginPassword) = &app.auth.input_mode {
-        f.set_cursor_position((chunks[1].x + app.auth.password_input.len() as u16 + 1, chunks[1].y + 1));
+        f.set_cursor_position((chunks[1].x + app.auth.password_input.chars().count() as u16 + 1, chunks[1].y + 1));
     }
 }
 
@@ -53,7 +54,7 @@ pub fn draw_register(f: &mut Frame, app: &mut App, area: Rect) {
     let outer_block = Block::default().title("Register").borders(Borders::ALL);
     f.render_widget(outer_block, area);
     let chunks = Layout::default().margin(2).constraints([
-        Constraint::Length(3), Constraint::Length(3), Constraint::Min(1)
+        Constraint::Length(3), Constraint::Length(3), Constraint::Length(1), Constraint::Min(1)
     ]).split(area);
     let username_style = if matches!(app.auth.input_mode, Some(InputMode::RegisterUsername)) {
         Style::default().fg(Color::Yellow)
@@ -67,12 +68,16 @@ pub fn draw_register(f: &mut Frame, app: &mut App, area: Rect) {
         Style::default().fg(Color::Yellow)
     } else { Style::default() };
     f.render_widget(
-        Paragraph::new("*".repeat(app.auth.password_input.len()))
+        Paragraph::new("*".repeat(app.auth.password_input.chars().count()))
             .block(Block::default().borders(Borders::ALL).title("Choose Password")).style(password_style),
         chunks[1],
     );
+    if !app.auth.password_input.is_empty() {
+        let strength = AuthService::password_strength(&app.auth.password_input, &app.auth.current_input);
+        f.render_widget(Paragraph::new(password_strength_line(strength)), chunks[2]);
+    }
 
-    let button_area = Layout::default().margin(1).constraints([Constraint::Length(3)]).split(chunks[2])[0];
+    let button_area = Layout::default().margin(1).constraints([Constraint::Length(3)]).split(chunks[3])[0];
     let button_chunks = Layout::default().direction(Direction::Horizontal)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)]).split(button_area);
 
@@ -87,8 +92,39 @@ pub fn draw_register(f: &mut Frame, app: &mut App, area: Rect) {
     f.render_widget(Paragraph::new(Span::styled("[ To Login ]", switch_style)).alignment(Alignment::Center), button_chunks[1]);
 
     if let Some(InputMode::RegisterUsername) = &app.auth.input_mode {
-        f.set_cursor_position((chunks[0].x + app.auth.current_input.len() as u16 + 1, chunks[0].y + 1));
+        f.set_cursor_position((chunks[0].x + app.auth.current_input.chars().count() as u16 + 1, chunks[0].y + 1));
     } else if let Some(InputMode::RegisterPassword) = &app.auth.input_mode {
-        f.set_cursor_position((chunks[1].x + app.auth.password_input.len() as u16 + 1, chunks[1].y + 1));
+        f.set_cursor_position((chunks[1].x + app.auth.password_input.chars().count() as u16 + 1, chunks[1].y + 1));
     }
 }
+
+/// Colored strength bar shown under the registration password field
+fn password_strength_line(strength: PasswordStrength) -> Line<'static> {
+    let (filled, color, hint) = match strength {
+        PasswordStrength::Weak => (3, Color::Red, " - try a longer password with mixed characters"),
+        PasswordStrength::Fair => (6, Color::Yellow, ""),
+        PasswordStrength::Strong => (10, Color::Green, ""),
+    };
+    Line::from(vec![
+        Span::raw(" Strength: "),
+        Span::styled("█".repeat(filled), Style::default().fg(color)),
+        Span::styled("░".repeat(10 - filled), Style::default().fg(Color::DarkGray)),
+        Span::styled(format!(" {}", strength.label()), Style::default().fg(color)),
+        Span::styled(hint, Style::default().fg(Color::DarkGray)),
+    ])
+}
+
+/// Servers from the servers file, shown before login when there's more than one
+pub fn draw_server_picker(f: &mut Frame, app: &mut App, area: Rect) {
+    let items: Vec<ListItem> = app.ui.server_profiles.iter().map(|profile| {
+        ListItem::new(Line::from(vec![
+            Span::styled(format!("{:<24}", profile.name), Style::default().fg(Color::Cyan)),
+            Span::styled(profile.address.clone(), Style::default().fg(Color::Gray)),
+        ]))
+    }).collect();
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).title("Connect to Server"))
+        .highlight_style(Style::default().bg(Color::Cyan).fg(Color::Black).add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(list, area, &mut app.ui.server_picker_state);
+}
@@ -1,37 +1,240 @@
 //! Avatar protocol and image helpers for the UI.
 
+use std::collections::{HashMap, HashSet};
+use std::sync::{mpsc, Arc};
 use base64::Engine;
 use image::{DynamicImage, RgbaImage, GenericImageView};
+use ratatui::{Frame, buffer::Buffer, layout::Rect, style::Color, widgets::Widget};
+use ratatui_image::{picker::ProtocolType, StatefulImage};
+use tokio::sync::Semaphore;
+use uuid::Uuid;
 use crate::app::App;
+use crate::services::image::{ImageCache, ImageCacheKey};
 
-// Returns a mutable reference to a cached StatefulProtocol for the user's avatar, creating it if needed.
+/// Decodes running at once; the rest wait their turn
+const MAX_CONCURRENT_DECODES: usize = 4;
+
+/// Decoded size of the source image for half-block avatars
+const HALFBLOCK_SOURCE_SIZE: u32 = 64;
+
+/// Decodes avatars on blocking threads, so opening a busy channel doesn't stall frames.
+/// Each (user, size) is decoded once at a time; finished images wait in `ready` until
+/// the next draw turns them into something renderable.
+pub struct AvatarDecoder {
+    tx: mpsc::Sender<DecodeResult>,
+    rx: mpsc::Receiver<DecodeResult>,
+    permits: Arc<Semaphore>,
+    in_flight: HashSet<(Uuid, u32)>,
+    ready: HashMap<(Uuid, u32), RgbaImage>,
+    /// Pictures that didn't decode, so they aren't retried every frame
+    failed: HashSet<(Uuid, u32)>,
+    /// Bumped when a user's picture changes; results from older requests are stale
+    generations: HashMap<Uuid, u64>,
+}
+
+/// A finished decode: its key, the user's picture generation it was requested under, the image
+type DecodeResult = ((Uuid, u32), u64, Option<RgbaImage>);
+
+impl AvatarDecoder {
+    pub fn new() -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self {
+            tx,
+            rx,
+            permits: Arc::new(Semaphore::new(MAX_CONCURRENT_DECODES)),
+            in_flight: HashSet::new(),
+            ready: HashMap::new(),
+            failed: HashSet::new(),
+            generations: HashMap::new(),
+        }
+    }
+
+    /// The decoded avatar for `key` if it's ready; otherwise start decoding `pic`
+    /// (unless that's already under way) and return None for now. Without a picture
+    /// the user's avatar is decoded from `cache` if it holds one.
+    pub fn take_or_request(&mut self, key: (Uuid, u32), pic: Option<&str>, cache: &Arc<ImageCache>) -> Option<RgbaImage> {
+        if let Some(image) = self.ready.remove(&key) {
+            return Some(image);
+        }
+        if pic.is_none() && !cache.contains_key(&ImageCacheKey::user_avatar(key.0)) {
+            return None;
+        }
+        if self.failed.contains(&key) || !self.in_flight.insert(key) {
+            return None;
+        }
+        let pic = pic.map(str::to_string);
+        let cache = cache.clone();
+        let tx = self.tx.clone();
+        let generation = self.generations.get(&key.0).copied().unwrap_or(0);
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                let permits = self.permits.clone();
+                runtime.spawn(async move {
+                    let Ok(_permit) = permits.acquire_owned().await else { return };
+                    let image = tokio::task::spawn_blocking(move || decode_avatar(pic.as_deref(), &cache, key)).await.ok().flatten();
+                    let _ = tx.send((key, generation, image));
+                });
+            }
+            // Outside the runtime there's nowhere to offload to
+            Err(_) => {
+                let _ = tx.send((key, generation, decode_avatar(pic.as_deref(), &cache, key)));
+            }
+        }
+        None
+    }
+
+    /// Pick up finished decodes. True when any arrived, so the next frame can show them.
+    pub fn collect_ready(&mut self) -> bool {
+        let mut arrived = false;
+        while let Ok((key, generation, image)) = self.rx.try_recv() {
+            // Requested before the picture changed; a decode of the new one may be under way
+            if generation != self.generations.get(&key.0).copied().unwrap_or(0) || !self.in_flight.remove(&key) {
+                continue;
+            }
+            match image {
+                Some(image) => { self.ready.insert(key, image); }
+                None => { self.failed.insert(key); }
+            }
+            arrived = true;
+        }
+        arrived
+    }
+
+    /// Drop everything decoded or pending for a user whose picture changed
+    pub fn forget_user(&mut self, user_id: Uuid) {
+        *self.generations.entry(user_id).or_insert(0) += 1;
+        self.in_flight.retain(|(uid, _)| *uid != user_id);
+        self.ready.retain(|(uid, _), _| *uid != user_id);
+        self.failed.retain(|(uid, _)| *uid != user_id);
+    }
+}
+
+impl Default for AvatarDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Returns a mutable reference to a cached StatefulProtocol for the user's avatar. Until the
+// background decode finishes this is None, and callers draw their fallback marker.
 pub fn get_avatar_protocol<'a>(app: &'a mut App, user: &nexus_tui_common::User, size: u32) -> Option<&'a mut ratatui_image::protocol::StatefulProtocol> {
     let key = (user.id, size);
     if !app.profile.avatar_protocol_cache.contains_key(&key) {
-        let pic = user.profile_pic.as_ref()?;
-        let b64 = if let Some(idx) = pic.find(',') {
-            if idx + 1 >= pic.len() { return None; }
-            &pic[idx + 1..]
-        } else { pic };
-        let bytes = base64::engine::general_purpose::STANDARD.decode(b64).ok()?;
-        let img = image::load_from_memory(&bytes).ok()?;
-        let (orig_w, orig_h) = img.dimensions();
-        let scale = f32::max(size as f32 / orig_w as f32, size as f32 / orig_h as f32);
-        let new_w = (orig_w as f32 * scale).ceil() as u32;
-        let new_h = (orig_h as f32 * scale).ceil() as u32;
-        let resized = img.resize_exact(new_w, new_h, image::imageops::FilterType::Lanczos3).to_rgba8();
-        // Crop the center square
-        let x_offset = ((new_w as i32 - size as i32) / 2).max(0) as u32;
-        let y_offset = ((new_h as i32 - size as i32) / 2).max(0) as u32;
-        let cropped = image::imageops::crop_imm(&resized, x_offset, y_offset, size, size).to_image();
-        let mut square = cropped;
-        apply_circular_mask(&mut square);
+        let square = app.profile.avatar_decoder.take_or_request(key, user.profile_pic.as_deref(), &app.image_cache)?;
         let protocol = app.profile.picker.new_resize_protocol(DynamicImage::ImageRgba8(square));
         app.profile.avatar_protocol_cache.insert(key, protocol);
     }
     app.profile.avatar_protocol_cache.get_mut(&key)
 }
 
+// Decode a base64 profile picture, or the user's cached avatar when there's none,
+// into a circular `size`x`size` square (center crop).
+fn decode_avatar(pic: Option<&str>, cache: &ImageCache, (user_id, size): (Uuid, u32)) -> Option<RgbaImage> {
+    let bytes = match pic {
+        Some(pic) => {
+            let b64 = if let Some(idx) = pic.find(',') {
+                if idx + 1 >= pic.len() { return None; }
+                &pic[idx + 1..]
+            } else { pic };
+            base64::engine::general_purpose::STANDARD.decode(b64).ok()?
+        }
+        None => cache.get(&ImageCacheKey::user_avatar(user_id)).ok()??.data,
+    };
+    let img = image::load_from_memory(&bytes).ok()?;
+    let (orig_w, orig_h) = img.dimensions();
+    let scale = f32::max(size as f32 / orig_w as f32, size as f32 / orig_h as f32);
+    let new_w = (orig_w as f32 * scale).ceil() as u32;
+    let new_h = (orig_h as f32 * scale).ceil() as u32;
+    let resized = img.resize_exact(new_w, new_h, image::imageops::FilterType::Lanczos3).to_rgba8();
+    // Crop the center square
+    let x_offset = ((new_w as i32 - size as i32) / 2).max(0) as u32;
+    let y_offset = ((new_h as i32 - size as i32) / 2).max(0) as u32;
+    let mut square = image::imageops::crop_imm(&resized, x_offset, y_offset, size, size).to_image();
+    apply_circular_mask(&mut square);
+    Some(square)
+}
+
+/// Whether avatars should be drawn with half blocks: the terminal has no graphics
+/// protocol (or one was never detected), so the picker would fall back to halfblocks.
+pub fn uses_halfblocks(app: &App) -> bool {
+    app.profile.picker.protocol_type() == ProtocolType::Halfblocks
+}
+
+/// An avatar downscaled to a pixel grid and drawn with `▀`/`▄` in 24-bit color,
+/// two pixels per cell. Fully transparent pixels leave the background alone.
+pub struct HalfblockAvatar {
+    width: u16,
+    height: u16,
+    /// (top, bottom) pixel color per cell, row-major
+    cells: Vec<(Option<Color>, Option<Color>)>,
+}
+
+impl HalfblockAvatar {
+    fn from_image(img: &RgbaImage, width: u16, height: u16) -> Self {
+        let pixels = image::imageops::resize(img, width as u32, height as u32 * 2, image::imageops::FilterType::Triangle);
+        let color = |x: u32, y: u32| {
+            let p = pixels.get_pixel(x, y);
+            (p[3] >= 128).then(|| Color::Rgb(p[0], p[1], p[2]))
+        };
+        let cells = (0..height as u32)
+            .flat_map(|row| (0..width as u32).map(move |col| (col, row)))
+            .map(|(col, row)| (color(col, row * 2), color(col, row * 2 + 1)))
+            .collect();
+        Self { width, height, cells }
+    }
+}
+
+impl Widget for &HalfblockAvatar {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for row in 0..self.height.min(area.height) {
+            for col in 0..self.width.min(area.width) {
+                let (symbol, fg, bg) = match self.cells[(row * self.width + col) as usize] {
+                    (Some(top), Some(bottom)) => ("▀", top, bottom),
+                    (Some(top), None) => ("▀", top, Color::Reset),
+                    (None, Some(bottom)) => ("▄", bottom, Color::Reset),
+                    (None, None) => continue,
+                };
+                if let Some(cell) = buf.cell_mut((area.x + col, area.y + row)) {
+                    cell.set_symbol(symbol).set_fg(fg).set_bg(bg);
+                }
+            }
+        }
+    }
+}
+
+// Returns the cached half-block avatar for the user at the given cell size, once its image is decoded.
+pub fn get_halfblock_avatar<'a>(app: &'a mut App, user: &nexus_tui_common::User, width: u16, height: u16) -> Option<&'a HalfblockAvatar> {
+    let key = (user.id, width, height);
+    if !app.profile.halfblock_avatar_cache.contains_key(&key) {
+        // Decode at a modest resolution; the grid is only a handful of pixels anyway
+        let square = app.profile.avatar_decoder.take_or_request((user.id, HALFBLOCK_SOURCE_SIZE), user.profile_pic.as_deref(), &app.image_cache)?;
+        app.profile.halfblock_avatar_cache.insert(key, HalfblockAvatar::from_image(&square, width, height));
+    }
+    app.profile.halfblock_avatar_cache.get(&key)
+}
+
+/// Draw the user's avatar into `area` with whichever renderer the terminal supports.
+/// Returns false when the user has no usable profile picture, or it's still decoding.
+pub fn render_avatar(f: &mut Frame, app: &mut App, user: &nexus_tui_common::User, pixel_size: u32, area: Rect) -> bool {
+    if uses_halfblocks(app) {
+        match get_halfblock_avatar(app, user, area.width, area.height) {
+            Some(avatar) => {
+                f.render_widget(avatar, area);
+                true
+            }
+            None => false,
+        }
+    } else {
+        match get_avatar_protocol(app, user, pixel_size) {
+            Some(state) => {
+                f.render_stateful_widget(StatefulImage::default(), area, state);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
 // Helper: Apply a circular alpha mask to an RgbaImage in-place
 pub fn apply_circular_mask(img: &mut RgbaImage) {
     let (w, h) = (img.width() as i32, img.height() as i32);
@@ -10,7 +10,7 @@ impl Background for CyberpunkBackground {
     }
     
     fn draw_background(&self, f: &mut Frame, app: &App, area: Rect) {
-        let tick = app.ui.tick_count;
+        let tick = app.ui.background_tick;
         
         // Create animated grid pattern
         for y in 0..area.height {
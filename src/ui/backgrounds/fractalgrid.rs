@@ -8,7 +8,7 @@ impl Background for FractalGridBackground {
     fn name(&self) -> &'static str { "FractalGrid" }
     fn draw_background(&self, f: &mut Frame, app: &App, area: Rect) {
         // Deep animated fractal tunnel with recursive geometry and color cycling
-        let tick = app.ui.tick_count;
+        let tick = app.ui.background_tick;
         let w = area.width as f32;
         let h = area.height as f32;
         let t = tick as f32 * 0.045;
@@ -15,7 +15,7 @@ impl Background for GeometryBackground {
     }
 
     fn draw_background(&self, f: &mut Frame, app: &App, area: Rect) {
-        let tick = app.ui.tick_count;
+        let tick = app.ui.background_tick;
         let w = area.width as f32;
         let h = area.height as f32;
         let cx = area.x as f32 + w / 2.0;
@@ -17,7 +17,7 @@ impl Background for Geometry2Background {
     fn draw_background(&self, f: &mut Frame, app: &App, area: Rect) {
         use rand::{Rng, SeedableRng};
         use rand::rngs::StdRng;
-        let tick = app.ui.tick_count;
+        let tick = app.ui.background_tick;
         let w = area.width as f32;
         let h = area.height as f32;
         let cx = area.x as f32 + w / 2.0;
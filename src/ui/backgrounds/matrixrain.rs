@@ -7,7 +7,7 @@ pub struct MatrixRainBackground;
 impl Background for MatrixRainBackground {
     fn name(&self) -> &'static str { "MatrixRain" }
     fn draw_background(&self, f: &mut Frame, app: &App, area: Rect) {
-        let tick = app.ui.tick_count;
+        let tick = app.ui.background_tick;
         let charset = ["7", "3", "A", "E", "F", "C", "9", "1", "0", "B", "D", "4", "5", "2", "8", "6"];
         for x in 0..area.width {
             let col_seed = (x as u64 * 31 + tick / 2) % 1000;
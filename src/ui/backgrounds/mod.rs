@@ -31,6 +31,10 @@ pub mod none;
 pub trait Background {
     fn name(&self) -> &'static str;
     fn draw_background(&self, f: &mut Frame, app: &App, area: Rect);
+    /// Whether it changes over time; still backgrounds aren't redrawn every frame
+    fn is_animated(&self) -> bool {
+        true
+    }
 }
 
 pub struct BackgroundManager {
@@ -71,9 +75,17 @@ impl BackgroundManager {
     pub fn get_background_name(&self) -> &str {
         self.get_current_background().map(|b| b.name()).unwrap_or("None")
     }
+    pub fn is_animated(&self) -> bool {
+        self.get_current_background().is_some_and(|b| b.is_animated())
+    }
+    pub fn names(&self) -> Vec<&str> {
+        self.backgrounds.iter().map(|b| b.name()).collect()
+    }
     pub fn set_background_by_name(&mut self, name: &str) {
-        if let Some(idx) = self.backgrounds.iter().position(|b| b.name().eq_ignore_ascii_case(name)) {
-            self.current_index = idx;
-        }
+        // A saved name that no longer exists (e.g. a removed theme file) falls back to the default
+        self.current_index = self.backgrounds.iter().position(|b| b.name().eq_ignore_ascii_case(name)).unwrap_or_else(|| {
+            tracing::warn!("Unknown background '{}', using the default", name);
+            0
+        });
     }
 }
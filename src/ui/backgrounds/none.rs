@@ -11,4 +11,8 @@ impl Background for NoneBackground {
     
     fn draw_background(&self, f: &mut Frame, app: &App, area: Rect) {
     }
+
+    fn is_animated(&self) -> bool {
+        false
+    }
 }
\ No newline at end of file
@@ -7,7 +7,7 @@ pub struct PlasmaWaveBackground;
 impl Background for PlasmaWaveBackground {
     fn name(&self) -> &'static str { "PlasmaWave" }
     fn draw_background(&self, f: &mut Frame, app: &App, area: Rect) {
-        let tick = app.ui.tick_count;
+        let tick = app.ui.background_tick;
         let w = area.width as f32;
         let h = area.height as f32;
         for y in 0..area.height {
@@ -7,7 +7,7 @@ pub struct WireframeEarthBackground;
 impl Background for WireframeEarthBackground {
     fn name(&self) -> &'static str { "WireframeEarth" }
     fn draw_background(&self, f: &mut Frame, app: &App, area: Rect) {
-        let tick = app.ui.tick_count;
+        let tick = app.ui.background_tick;
         let w = area.width as f32;
         let h = area.height as f32;
         // Ensure the globe stays centered in the background
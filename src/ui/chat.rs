@@ -2,11 +2,13 @@
 
 use ratatui::{Frame, layout::{Rect, Layout, Constraint, Direction}, style::{Style, Color, Modifier}, widgets::{Block, Paragraph, Borders, List, ListItem, Wrap, Clear}, text::{Line, Span}};
 use crate::app::{App, ChatFocus};
-use crate::ui::avatar::get_avatar_protocol;
-use ratatui_image::StatefulImage;
+use crate::services::{ChatService, MessageService};
+use crate::model::ChatMessageWithMeta;
+use crate::services::message::MessageSegment;
 use ratatui::widgets::ListState;
 use ratatui::widgets::{Tabs};
 use crate::ui::time_format::{format_date_delimiter, format_message_timestamp};
+use crate::ui::text_width::{char_width, pad_center, str_width};
 use chrono::TimeZone;
 
 pub fn draw_chat(f: &mut Frame, app: &mut App, area: Rect) {
@@ -33,17 +35,18 @@ pub fn draw_chat(f: &mut Frame, app: &mut App, area: Rect) {
             .split(area)
     };
     // Tabs at the top of the sidebar
+    let unread_servers: usize = app.chat.unread_channels.values().sum();
+    let unread_dms: usize = app.chat.unread_dm_conversations.values().sum();
+    let tab_title = |name: &'static str, unread: usize| {
+        let mut spans = vec![Span::raw(name)];
+        if unread > 0 {
+            spans.push(unread_badge(unread));
+        }
+        Line::from(spans)
+    };
     let tab_titles = vec![
-        if app.chat.unread_channels.is_empty() {
-            Line::from("Servers")
-        } else {
-            Line::from(vec![Span::raw("Servers "), Span::styled("○", Style::default().fg(Color::Red))])
-        },
-        if app.chat.unread_dm_conversations.is_empty() {
-            Line::from("DMs")
-        } else {
-            Line::from(vec![Span::raw("DMs "), Span::styled("○", Style::default().fg(Color::Red))])
-        },
+        tab_title("Servers", unread_servers),
+        tab_title("DMs", unread_dms),
     ];
     let tab_idx = match app.chat.sidebar_tab {
         crate::state::SidebarTab::Servers => 0,
@@ -68,6 +71,7 @@ pub fn draw_chat(f: &mut Frame, app: &mut App, area: Rect) {
         ])
         .split(chunks[0]);
     f.render_widget(tabs, sidebar_chunks[0]);
+    app.ui.click_map.sidebar_tabs = Some(sidebar_chunks[0]);
     match app.chat.sidebar_tab {
         crate::state::SidebarTab::Servers => {
             draw_sidebar_servers(f, app, sidebar_chunks[1], focus == ChatFocus::Sidebar);
@@ -86,6 +90,119 @@ pub fn draw_chat(f: &mut Frame, app: &mut App, area: Rect) {
     }
 }
 
+/// Whether `msg` gets its own author/timestamp header after `prev`. Compact density
+/// groups runs from one author within five minutes under a single header.
+fn show_message_header(prev: &ChatMessageWithMeta, msg: &ChatMessageWithMeta, compact: bool) -> bool {
+    if !compact {
+        return true;
+    }
+    let close_in_time = match (prev.timestamp, msg.timestamp) {
+        (Some(a), Some(b)) => (b - a).abs() < 300,
+        _ => false,
+    };
+    prev.author_id != msg.author_id || !close_in_time
+}
+
+/// How many messages of `heights` fit in `rows`, taken in order, each followed by
+/// `spacing` blank rows. Taken newest first this is a screenful, the paging step.
+fn messages_that_fit(heights: impl IntoIterator<Item = u16>, rows: u16, spacing: u16) -> usize {
+    let mut used = 0u16;
+    let mut count = 0;
+    for height in heights {
+        used = used.saturating_add(height).saturating_add(spacing);
+        if used > rows { break; }
+        count += 1;
+    }
+    count
+}
+
+/// The furthest the view can scroll up: everything above the oldest page, given the
+/// heights of the oldest messages. That page keeps its top row for the history indicator.
+fn max_scroll_offset(total: usize, oldest_heights: &[u16], view_height: u16, spacing: u16) -> usize {
+    let top_fit = messages_that_fit(oldest_heights.iter().copied(), view_height.saturating_sub(1), spacing);
+    total.saturating_sub(top_fit.max(1))
+}
+
+/// Rows a message's text takes when wrapped to `text_width`, plus its header line;
+/// the caller applies the avatar's minimum height.
+fn message_height(msg: &ChatMessageWithMeta, show_header: bool, text_width: u16, wide_emoji: bool) -> u16 {
+    if text_width == 0 {
+        return 1 + show_header as u16;
+    }
+    let (quote, content) = MessageService::split_quote(&msg.content);
+    let mut lines = quote.is_some() as usize;
+    for line in MessageService::display_lines(content) {
+        // Empty lines still take a row
+        lines += str_width(&line, wide_emoji).div_ceil(text_width as usize).max(1);
+    }
+    (lines + show_header as usize).min(u16::MAX as usize) as u16
+}
+
+/// Split text into spans with @mentions styled by the mentioned user's color
+fn mention_spans<'a>(text: &'a str, userlist: &[nexus_tui_common::User], mention_re: &regex::Regex) -> Vec<Span<'a>> {
+    let mut spans = Vec::new();
+    let mut last = 0;
+    for m in mention_re.find_iter(text) {
+        let start = m.start();
+        let end = m.end();
+        if start > last {
+            spans.push(Span::raw(&text[last..start]));
+        }
+        let mention = &text[start+1..end];
+        let mention_color = userlist.iter().find(|u| u.username == mention).map(|u| u.color.clone().into());
+        if ChatService::is_special_mention(mention) {
+            spans.push(Span::styled(format!("@{}", mention), special_mention_style()));
+        } else if let Some(mcolor) = mention_color {
+            spans.push(Span::styled(format!("@{}", mention), Style::default().fg(Color::Black).bg(mcolor).add_modifier(Modifier::BOLD)));
+        } else {
+            spans.push(Span::styled(format!("@{}", mention), Style::default().add_modifier(Modifier::BOLD)));
+        }
+        last = end;
+    }
+    if last < text.len() {
+        spans.push(Span::raw(&text[last..]));
+    }
+    spans
+}
+
+/// Split spans so case-insensitive matches of `query` get the search highlight.
+fn highlight_query<'a>(spans: Vec<Span<'a>>, query: &str) -> Vec<Span<'a>> {
+    let query = query.to_lowercase();
+    let highlight = Style::default().fg(Color::Black).bg(Color::Yellow);
+    let mut result = Vec::new();
+    for span in spans {
+        let text = span.content.to_string();
+        let lower = text.to_lowercase();
+        // Byte offsets only line up when lowercasing doesn't change lengths
+        if lower.len() != text.len() || !lower.contains(&query) {
+            result.push(span);
+            continue;
+        }
+        let mut last = 0;
+        for (start, _) in lower.match_indices(&query) {
+            if start < last {
+                continue;
+            }
+            if start > last {
+                result.push(Span::styled(text[last..start].to_string(), span.style));
+            }
+            let end = start + query.len();
+            result.push(Span::styled(text[start..end].to_string(), span.style.patch(highlight)));
+            last = end;
+        }
+        if last < text.len() {
+            result.push(Span::styled(text[last..].to_string(), span.style));
+        }
+    }
+    result
+}
+
+/// Unread message count shown next to a name, e.g. " (3)"
+fn unread_badge(count: usize) -> Span<'static> {
+    let text = if count > 99 { " (99+)".to_string() } else { format!(" ({})", count) };
+    Span::styled(text, Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+}
+
 // Draw server/channel list with unread indicators
 pub fn draw_sidebar_servers(f: &mut Frame, app: &mut App, area: Rect, focused: bool) {
     let border_style = if focused {
@@ -98,36 +215,45 @@ pub fn draw_sidebar_servers(f: &mut Frame, app: &mut App, area: Rect, focused: b
     let inner = block.inner(area);
     if inner.width == 0 || inner.height == 0 { return; }
     let mut items = Vec::new();
+    let mut clicks = Vec::new();
     for (si, server) in app.chat.servers.iter().enumerate() {
         let selected_server = app.chat.selected_server == Some(si);
-        // Unread indicator for server: any channel in this server is unread
-        let has_unread = server.channels.iter().any(|c| app.chat.unread_channels.contains(&c.id));
+        // Unread count for server: all of its channels together
+        let unread: usize = server.channels.iter().map(|c| app.chat.unread_channel_count(&c.id)).sum();
         let mut server_spans = vec![Span::styled(format!("● {}", server.name), if selected_server {
             Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
         } else { Style::default().fg(Color::Gray) })];
-        if has_unread {
-            server_spans.push(Span::raw(" "));
-            server_spans.push(Span::styled("○", Style::default().fg(Color::Red)));
+        if unread > 0 {
+            server_spans.push(unread_badge(unread));
+        }
+        // Draft indicator: unsent text is waiting in one of this server's channels
+        let channel_target = |channel: &nexus_tui_common::Channel| crate::state::ChatTarget::Channel {
+            server_id: server.id,
+            channel_id: channel.id,
+        };
+        if !selected_server && server.channels.iter().any(|c| app.chat.has_pending_draft(&channel_target(c))) {
+            server_spans.push(Span::styled(" ✎", Style::default().fg(Color::Yellow)));
         }
         items.push(ListItem::new(Line::from(server_spans)));
+        clicks.push(crate::state::ui::SidebarClick::Server(si));
         if selected_server {
             for (ci, channel) in server.channels.iter().enumerate() {
                 let selected_channel = app.chat.selected_channel == Some(ci);
                 let channel_name = format!("  #{}", channel.name);
-                if app.chat.unread_channels.contains(&channel.id) {
-                    items.push(ListItem::new(Line::from(vec![
-                        Span::styled(channel_name, if selected_channel {
-                            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
-                        } else { Style::default() }),
-                        Span::styled(" ○", Style::default().fg(Color::Red)),
-                    ])));
-                } else {
-                    items.push(ListItem::new(Line::from(vec![
-                        Span::styled(channel_name, if selected_channel {
-                            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
-                        } else { Style::default() }),
-                    ])));
+                let mut channel_spans = vec![
+                    Span::styled(channel_name, if selected_channel {
+                        Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+                    } else { Style::default() }),
+                ];
+                let unread = app.chat.unread_channel_count(&channel.id);
+                if unread > 0 {
+                    channel_spans.push(unread_badge(unread));
+                }
+                if app.chat.has_pending_draft(&channel_target(channel)) {
+                    channel_spans.push(Span::styled(" ✎", Style::default().fg(Color::Yellow)));
                 }
+                items.push(ListItem::new(Line::from(channel_spans)));
+                clicks.push(crate::state::ui::SidebarClick::Channel(si, ci));
             }
         }
     }
@@ -147,6 +273,15 @@ pub fn draw_sidebar_servers(f: &mut Frame, app: &mut App, area: Rect, focused: b
         .highlight_style(Style::default().bg(Color::Cyan).fg(Color::Black).add_modifier(Modifier::BOLD))
         .highlight_symbol(">> ");
     f.render_stateful_widget(list, inner, &mut list_state);
+    record_sidebar_rows(app, inner, list_state.offset(), clicks);
+}
+
+/// Remember which sidebar entry each visible row shows, for mouse clicks
+fn record_sidebar_rows(app: &mut App, inner: Rect, offset: usize, clicks: Vec<crate::state::ui::SidebarClick>) {
+    for (row, click) in clicks.into_iter().skip(offset).take(inner.height as usize).enumerate() {
+        let rect = Rect::new(inner.x, inner.y + row as u16, inner.width, 1);
+        app.ui.click_map.sidebar_rows.push((rect, click));
+    }
 }
 
 // Draw DM conversation list, ordered by most recent, with unread indicators
@@ -164,7 +299,7 @@ pub fn draw_sidebar_dms(f: &mut Frame, app: &mut App, area: Rect, focused: bool)
     // Create a list of (original_index, user) pairs to track original indices
     let mut indexed_users: Vec<(usize, &nexus_tui_common::User)> = app.chat.dm_user_list.iter().enumerate().collect();
     // Sort by unread first, then by username
-    indexed_users.sort_by_key(|(_, u)| (!app.chat.unread_dm_conversations.contains(&u.id), u.username.clone()));
+    indexed_users.sort_by_key(|(_, u)| (!app.chat.unread_dm_conversations.contains_key(&u.id), u.username.clone()));
     
     let items: Vec<ListItem> = indexed_users.iter().map(|(_original_idx, u)| {
             let status_symbol = if u.status == nexus_tui_common::UserStatus::Connected { "●" } else { "○" };
@@ -180,9 +315,12 @@ pub fn draw_sidebar_dms(f: &mut Frame, app: &mut App, area: Rect, focused: bool)
                 Span::raw(" "),
                 Span::styled(&u.username, Style::default().fg(u.color.clone().into()))
             ];
-            if app.chat.unread_dm_conversations.contains(&u.id) {
-                spans.push(Span::raw(" "));
-                spans.push(Span::styled("○", Style::default().fg(Color::Red)));
+            let unread = app.chat.unread_dm_count(&u.id);
+            if unread > 0 {
+                spans.push(unread_badge(unread));
+            }
+            if app.chat.has_pending_draft(&crate::state::ChatTarget::DM { user_id: u.id }) {
+                spans.push(Span::styled(" ✎", Style::default().fg(Color::Yellow)));
             }
             ListItem::new(Line::from(spans))
         }).collect();
@@ -194,6 +332,8 @@ pub fn draw_sidebar_dms(f: &mut Frame, app: &mut App, area: Rect, focused: bool)
         None
     };
     
+    let clicks = indexed_users.iter().map(|(original_idx, _)| crate::state::ui::SidebarClick::Dm(*original_idx)).collect();
+    
     let mut list_state = ListState::default();
     list_state.select(display_selection);
     let list = List::new(items)
@@ -201,13 +341,13 @@ pub fn draw_sidebar_dms(f: &mut Frame, app: &mut App, area: Rect, focused: bool)
         .highlight_style(Style::default().bg(Color::Cyan).fg(Color::Black).add_modifier(Modifier::BOLD))
         .highlight_symbol(">> ");
     f.render_stateful_widget(list, inner, &mut list_state);
+    record_sidebar_rows(app, inner, list_state.offset(), clicks);
 }
 
 fn draw_message_list(f: &mut Frame, app: &mut App, area: Rect, focused: bool, title: &str) {
     use ratatui::widgets::{Block, Borders, Paragraph};
     use ratatui::text::{Span, Line};
-    use crate::ui::avatar::get_avatar_protocol;
-    use ratatui_image::StatefulImage;
+    use crate::ui::avatar::{render_avatar, uses_halfblocks};
 
     let border_style = if focused {
         Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
@@ -218,86 +358,93 @@ fn draw_message_list(f: &mut Frame, app: &mut App, area: Rect, focused: bool, ti
     f.render_widget(block.clone(), area);
     let inner_area = block.inner(area);
     if inner_area.width == 0 || inner_area.height == 0 { return; }
+    app.ui.click_map.message_list = Some(inner_area);
 
-    const AVATAR_PIXEL_SIZE: u32 = 32;
+    let avatar_pixel_size = app.prefs.avatar_pixel_size;
+    let text_only = app.prefs.text_only_avatars;
     let (font_w, font_h) = app.profile.picker.font_size();
     let (font_w, font_h) = if font_w == 0 || font_h == 0 { (8, 16) } else { (font_w, font_h) };
-    let avatar_cell_width = (AVATAR_PIXEL_SIZE as f32 / font_w as f32).ceil() as u16;
-    let avatar_cell_height = (AVATAR_PIXEL_SIZE as f32 / font_h as f32).ceil() as u16;
-    let min_row_height = avatar_cell_height.max(2);
+    // Compact density drops avatars and blank lines and groups runs by one author
+    let compact = app.prefs.message_density == crate::global_prefs::MessageDensity::Compact;
+    // Half-block avatars get a fixed 6x3 cell grid (6x6 pixels) so they stay recognizable
+    let halfblocks = uses_halfblocks(app);
+    let (avatar_cell_width, avatar_gap) = if compact {
+        (0, 0)
+    } else if text_only {
+        (1, 1)
+    } else if halfblocks {
+        (6, 1)
+    } else {
+        ((avatar_pixel_size as f32 / font_w as f32).ceil() as u16, 1)
+    };
+    let avatar_cell_height = if text_only {
+        1
+    } else if halfblocks {
+        3
+    } else {
+        (avatar_pixel_size as f32 / font_h as f32).ceil() as u16
+    };
+    let min_row_height = if compact { 1 } else { avatar_cell_height.max(2) };
+    let spacing: u16 = if compact { 0 } else { 1 };
 
-    let messages = app.get_current_message_list();
-    
-    // Calculate how many messages we can fit by working backwards from the bottom
-    // For scrolling calculation, use average row height estimation
-    let estimated_avg_row_height = min_row_height + 2; // +2 for spacing and potential wrapping
-    let max_rows_estimate = (inner_area.height as usize) / (estimated_avg_row_height as usize + 1);
-    app.chat.last_chat_rows = Some(max_rows_estimate); // Store for scroll calculations
+    let now = chrono::Local::now();
+    let text_area_width = inner_area.width.saturating_sub(avatar_cell_width + avatar_gap);
+    let wide_emoji = app.prefs.wide_emoji;
+    let height_of = |msg: &ChatMessageWithMeta, show_header: bool| {
+        message_height(msg, show_header, text_area_width, wide_emoji).max(min_row_height)
+    };
+    // Every message takes at least a row, so a screenful is at most this many; only
+    // that much of the history is copied, from the top and from the bottom of the view
+    let screenful = inner_area.height as usize + 1;
+    let total_msgs = app.current_message_count();
     
-    let total_msgs = messages.len();
-    let max_scroll = total_msgs.saturating_sub(max_rows_estimate);
+    // Measure the oldest page with real heights to know how far up the view can go
+    let oldest = app.get_current_message_range(0..screenful);
+    let oldest_heights: Vec<u16> = oldest.iter().enumerate()
+        .map(|(i, msg)| height_of(msg, i == 0 || show_message_header(&oldest[i - 1], msg, compact)))
+        .collect();
+    let max_scroll = max_scroll_offset(total_msgs, &oldest_heights, inner_area.height, spacing);
+    app.chat.max_scroll_offset = Some(max_scroll);
     let scroll_offset = app.chat.chat_scroll_offset.min(max_scroll);
+    if scroll_offset == 0 {
+        app.chat.new_messages_below = false;
+    }
     let end_idx = total_msgs.saturating_sub(scroll_offset);
-    let start_idx = end_idx.saturating_sub(max_rows_estimate * 2); // Get more messages than estimated to account for varying heights
-    let display_items = &messages[start_idx.max(0)..end_idx];
-
-    let now = chrono::Local::now();
-    let _last_date: Option<chrono::NaiveDate> = None;
-    let text_area_width = inner_area.width.saturating_sub(avatar_cell_width + 1);
-    
-    // Render messages from bottom up to handle dynamic heights properly
-    let mut message_heights = Vec::new();
-    
-    // First pass: calculate heights for all messages
-    for msg in display_items.iter() {
-        // Calculate content height more accurately
-        let content_str = &msg.content;
-        let lines_needed = if text_area_width > 0 {
-            // Split content by explicit newlines first
-            let content_lines: Vec<&str> = content_str.split('\n').collect();
-            let mut total_lines = 0;
-            
-            for line in content_lines {
-                if line.is_empty() {
-                    total_lines += 1; // Empty lines still take space
-                } else {
-                    // Calculate how many wrapped lines this content line will take
-                    let line_len = line.chars().count();
-                    let wrapped_lines = if line_len == 0 {
-                        1
-                    } else {
-                        (line_len + text_area_width as usize - 1) / text_area_width as usize
-                    };
-                    total_lines += wrapped_lines;
-                }
-            }
-            total_lines
-        } else {
-            1
-        };
-        
-        // Message height = max(avatar_height, content_lines + header_line)
-        let content_height = (lines_needed + 1) as u16; // +1 for author/timestamp line
-        let message_height = content_height.max(min_row_height);
-        message_heights.push(message_height);
-    }
-    
-    // Find how many messages actually fit, working backwards
-    let mut total_height = 0u16;
-    let mut visible_count = 0;
-    for &height in message_heights.iter().rev() {
-        if total_height + height + 1 <= inner_area.height { // +1 for spacing
-            total_height += height + 1;
-            visible_count += 1;
-        } else {
-            break;
-        }
+    let window_start = end_idx.saturating_sub(screenful);
+    let window = app.get_current_message_range(window_start..end_idx);
+    
+    // Take enough messages to fill the view, going back from the bottom. Heights without
+    // headers can only be smaller than the real ones, so this never takes too few.
+    let mut first = window.len();
+    let mut used = 0u16;
+    while first > 0 && used <= inner_area.height {
+        first -= 1;
+        used = used.saturating_add(height_of(&window[first], false) + spacing);
     }
+    let start_idx = window_start + first;
+    let display_items = &window[first..];
+    
+    // Whether each message gets an author/timestamp header (compact groups runs
+    // from the same author within five minutes under one header)
+    let show_headers: Vec<bool> = display_items.iter().enumerate()
+        .map(|(i, msg)| i == 0 || show_message_header(&display_items[i - 1], msg, compact))
+        .collect();
+    
+    let message_heights: Vec<u16> = display_items.iter().zip(show_headers.iter())
+        .map(|(msg, &show_header)| height_of(msg, show_header))
+        .collect();
+    
+    // Find how many messages actually fit, working backwards. When the oldest loaded
+    // message may come into view, keep the top row for the history indicator.
+    let available_height = if start_idx == 0 { inner_area.height.saturating_sub(1) } else { inner_area.height };
+    let visible_count = messages_that_fit(message_heights.iter().rev().copied(), available_height, spacing);
     
     // Render the visible messages
     let visible_start = display_items.len().saturating_sub(visible_count);
     let visible_messages = &display_items[visible_start..];
     let visible_heights = &message_heights[visible_start..];
+    let visible_headers = &show_headers[visible_start..];
+    app.chat.last_visible_messages = Some(visible_count);
     
     // Pre-calculate date delimiter positions to avoid interrupting message rendering
     let mut date_delimiters = Vec::new();
@@ -325,9 +472,11 @@ fn draw_message_list(f: &mut Frame, app: &mut App, area: Rect, focused: bool, ti
     
     // Start from bottom and work up
     let mut current_y = inner_area.y + inner_area.height;
+    let mut selected_author: Option<(uuid::Uuid, Rect)> = None;
     
     for (msg_idx, (msg, &msg_height)) in visible_messages.iter().zip(visible_heights.iter()).enumerate().rev() {
-        current_y = current_y.saturating_sub(msg_height + 1);
+        current_y = current_y.saturating_sub(msg_height + spacing);
+        let show_header = visible_headers[msg_idx];
         
         if current_y < inner_area.y { break; }
         
@@ -340,101 +489,183 @@ fn draw_message_list(f: &mut Frame, app: &mut App, area: Rect, focused: bool, ti
                 .border_style(Style::default().fg(Color::DarkGray))
                 .style(Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC));
             f.render_widget(header, Rect::new(inner_area.x, current_y, inner_area.width, min_row_height));
-            current_y = current_y.saturating_sub(min_row_height + 1);
+            current_y = current_y.saturating_sub(min_row_height + spacing);
         }
         
         let row_area = Rect::new(inner_area.x, current_y, inner_area.width, msg_height);
         let avatar_area = Rect::new(row_area.x, row_area.y, avatar_cell_width, avatar_cell_height);
-        let text_area = Rect::new(row_area.x + avatar_cell_width + 1, row_area.y, text_area_width, msg_height);
+        let text_area = Rect::new(row_area.x + avatar_cell_width + avatar_gap, row_area.y, text_area_width, msg_height);
         
-        // Avatar/profile pic rendering
-        let user_for_avatar = match &app.chat.current_chat_target {
-            Some(crate::state::ChatTarget::Channel { channel_id: _, server_id: _ }) => {
-                // Clone the user to avoid borrowing issues
-                app.chat.channel_userlist.iter().find(|u| u.username == msg.author).cloned()
-            }
-            Some(crate::state::ChatTarget::DM { user_id: _ }) => {
-                if let Some(dm_user) = app.chat.dm_user_list.iter().find(|u| u.username == msg.author) {
-                    Some(dm_user.clone())
-                } else if let Some(current) = &app.auth.current_user {
-                    if &current.username == &msg.author {
-                        Some(current.clone())
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            }
-            _ => None
-        };
-        if let Some(user) = user_for_avatar {
-            if let Some(state) = get_avatar_protocol(app, &user, AVATAR_PIXEL_SIZE) {
-                let image_widget = StatefulImage::default();
-                f.render_stateful_widget(image_widget, avatar_area, state);
-            }
-        } else if let Some(ref pic) = msg.profile_pic {
-            // fallback: build a User with just the info from the message
-            let fallback_user = nexus_tui_common::User {
-                id: uuid::Uuid::nil(),
-                username: msg.author.clone(),
-                color: msg.color.clone().into(),
-                role: nexus_tui_common::UserRole::User,
-                profile_pic: Some(pic.clone()),
-                cover_banner: None,
-                status: nexus_tui_common::UserStatus::Offline,
-            };
-            if let Some(state) = get_avatar_protocol(app, &fallback_user, AVATAR_PIXEL_SIZE) {
-                let image_widget = StatefulImage::default();
-                f.render_stateful_widget(image_widget, avatar_area, state);
+        if app.chat.selected_message_id == Some(msg.id) {
+            f.render_widget(Block::default().style(Style::default().bg(Color::Rgb(40, 40, 60))), row_area);
+            // Anchor the author tooltip beside the avatar column
+            selected_author = Some((msg.author_id, Rect::new(row_area.x, row_area.y, avatar_cell_width + avatar_gap, 1)));
+        }
+        
+        // Avatar/profile pic rendering (none in compact density)
+        if !compact && text_only {
+            let marker = Line::from(Span::styled("○", Style::default().fg(Color::Gray)));
+            f.render_widget(Paragraph::new(marker), avatar_area);
+        } else if !compact {
+            // Cloned to avoid borrowing issues
+            let user_for_avatar = app.message_author(msg.author_id).cloned();
+            if let Some(user) = user_for_avatar {
+                render_avatar(f, app, &user, avatar_pixel_size, avatar_area);
+            } else if let Some(ref pic) = msg.profile_pic {
+                // fallback: build a User with just the info from the message
+                let fallback_user = nexus_tui_common::User {
+                    id: msg.author_id,
+                    username: msg.author.clone(),
+                    color: msg.color.clone().into(),
+                    role: nexus_tui_common::UserRole::User,
+                    profile_pic: Some(pic.clone()),
+                    cover_banner: None,
+                    status: nexus_tui_common::UserStatus::Offline,
+                };
+                render_avatar(f, app, &fallback_user, avatar_pixel_size, avatar_area);
+            } else {
+                let fallback = Line::from(Span::styled("○", Style::default().fg(Color::Gray)));
+                f.render_widget(Paragraph::new(fallback), avatar_area);
             }
-        } else {
-            let fallback = Line::from(Span::styled("○", Style::default().fg(Color::Gray)));
-            f.render_widget(Paragraph::new(fallback), avatar_area);
         }
         
         // Mention parsing and coloring
-        let mut spans = Vec::new();
-        let mut last = 0;
-        let content_str = &msg.content;
+        let (quote, content_str) = MessageService::split_quote(&msg.content);
         let mention_re = regex::Regex::new(r"@([a-zA-Z0-9_]+)").unwrap();
-        for m in mention_re.find_iter(content_str) {
-            let start = m.start();
-            let end = m.end();
-            if start > last {
-                spans.push(Span::raw(&content_str[last..start]));
-            }
-            let mention = &content_str[start+1..end];
-            let mention_color = app.chat.channel_userlist.iter().find(|u| u.username == mention).map(|u| u.color.clone().into());
-            if let Some(mcolor) = mention_color {
-                spans.push(Span::styled(format!("@{}", mention), Style::default().fg(Color::Black).bg(mcolor).add_modifier(Modifier::BOLD)));
-            } else {
-                spans.push(Span::styled(format!("@{}", mention), Style::default().add_modifier(Modifier::BOLD)));
+
+        // Inline code and fenced blocks; the line structure matches `MessageService::display_lines`
+        let code_style = Style::default().fg(Color::Gray).bg(Color::Black);
+        let mut content_lines: Vec<Vec<Span>> = vec![Vec::new()];
+        let mut has_code_block = false;
+        for segment in MessageService::parse_code_segments(content_str) {
+            match segment {
+                MessageSegment::Text(text) => {
+                    for (i, part) in text.split('\n').enumerate() {
+                        if i > 0 {
+                            content_lines.push(Vec::new());
+                        }
+                        // Links are styled as a whole; mentions are parsed around them
+                        let line = content_lines.last_mut().unwrap();
+                        let mut last = 0;
+                        for (start, end) in MessageService::find_urls(part) {
+                            line.extend(mention_spans(&part[last..start], &app.chat.channel_userlist, &mention_re));
+                            line.push(Span::styled(&part[start..end], link_style()));
+                            last = end;
+                        }
+                        line.extend(mention_spans(&part[last..], &app.chat.channel_userlist, &mention_re));
+                    }
+                }
+                MessageSegment::InlineCode(code) => {
+                    content_lines.last_mut().unwrap().push(Span::styled(code, code_style));
+                }
+                MessageSegment::CodeBlock(code) => {
+                    has_code_block = true;
+                    if content_lines.last().is_some_and(|l| l.is_empty()) {
+                        content_lines.pop();
+                    }
+                    // Blank code lines get a space so the block background stays continuous
+                    content_lines.extend(code.split('\n').map(|line| {
+                        vec![Span::styled(if line.is_empty() { " " } else { line }, code_style)]
+                    }));
+                    content_lines.push(Vec::new());
+                }
             }
-            last = end;
         }
-        if last < content_str.len() {
-            spans.push(Span::raw(&content_str[last..]));
+        if content_lines.len() > 1 && content_lines.last().is_some_and(|l| l.is_empty()) {
+            content_lines.pop();
         }
         
         let author = &msg.author;
-        let timestamp_str = msg.timestamp.map(|ts| format_message_timestamp(ts, now.clone())).unwrap_or_default();
-        let text = if !timestamp_str.is_empty() {
-            vec![
-                Line::from(vec![
-                    Span::styled(format!("<{}>", author), Style::default().fg(msg.color).add_modifier(Modifier::BOLD)),
-                    Span::raw(" "),
-                    Span::styled(timestamp_str, Style::default().fg(Color::DarkGray)),
-                ]),
-                Line::from(spans),
-            ]
+        let timestamp_str = if msg.pending {
+            "⏳ pending".to_string()
         } else {
-            vec![
-                Line::from(Span::styled(format!("<{}>", author), Style::default().fg(msg.color).add_modifier(Modifier::BOLD))),
-                Line::from(spans),
-            ]
+            msg.timestamp.map(|ts| format_message_timestamp(ts, now.clone(), app.prefs.clock_format())).unwrap_or_default()
         };
-        f.render_widget(Paragraph::new(text).wrap(ratatui::widgets::Wrap { trim: true }), text_area);
+        let mut text = Vec::new();
+        if show_header {
+            let author_label = format!("<{}>", author);
+            let author_width = str_width(&author_label, app.prefs.wide_emoji);
+            let mut header = vec![Span::styled(author_label, Style::default().fg(msg.color).add_modifier(Modifier::BOLD))];
+            if !timestamp_str.is_empty() {
+                let gap = match app.prefs.timestamp_position {
+                    crate::global_prefs::TimestampPosition::AfterAuthor => Some(1),
+                    // Pad out to the right edge; fall back to inline when it doesn't fit
+                    crate::global_prefs::TimestampPosition::RightAligned => Some(
+                        (text_area.width as usize)
+                            .saturating_sub(author_width + str_width(&timestamp_str, app.prefs.wide_emoji))
+                            .max(1),
+                    ),
+                    crate::global_prefs::TimestampPosition::Hidden => None,
+                };
+                if let Some(gap) = gap {
+                    header.push(Span::raw(" ".repeat(gap)));
+                    header.push(Span::styled(timestamp_str, Style::default().fg(Color::DarkGray)));
+                }
+            }
+            text.push(Line::from(header));
+        }
+        if let Some(quote) = quote {
+            text.push(Line::from(Span::styled(
+                format!("┌ {}", quote),
+                Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+            )));
+        }
+        for spans in content_lines {
+            if app.chat.search_query.is_empty() {
+                text.push(Line::from(spans));
+            } else {
+                text.push(Line::from(highlight_query(spans, &app.chat.search_query)));
+            }
+        }
+        // Trimming would eat the indentation inside code blocks; queued messages are dimmed
+        let paragraph_style = if msg.pending { Style::default().add_modifier(Modifier::DIM) } else { Style::default() };
+        f.render_widget(
+            Paragraph::new(text).style(paragraph_style).wrap(ratatui::widgets::Wrap { trim: !has_code_block }),
+            text_area,
+        );
+    }
+    
+    // Top of the loaded history: offer older messages, show the fetch, or mark the start
+    app.chat.history_top_visible = start_idx == 0 && visible_start == 0;
+    if app.chat.history_top_visible && app.chat.current_chat_target.is_some() {
+        const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+        let (label, style) = if app.chat.history_complete() {
+            ("── Beginning of conversation ──".to_string(), Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC))
+        } else if app.history_fetch_pending() {
+            let frame = SPINNER[(app.ui.tick_count / 2) as usize % SPINNER.len()];
+            (format!("{} Loading older messages...", frame), Style::default().fg(Color::Yellow))
+        } else {
+            ("▲ Load older messages [Enter / PgUp]".to_string(), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        };
+        let top_area = Rect::new(inner_area.x, inner_area.y, inner_area.width, 1);
+        f.render_widget(Clear, top_area);
+        f.render_widget(Paragraph::new(Span::styled(label, style)).alignment(ratatui::layout::Alignment::Center), top_area);
+    }
+
+    // Floating hint while newer messages are waiting below the viewport
+    if app.chat.new_messages_below {
+        let hint = " ▼ new messages [End] ";
+        let width = (hint.chars().count() as u16).min(inner_area.width);
+        let hint_area = Rect::new(
+            inner_area.x + (inner_area.width - width) / 2,
+            inner_area.y + inner_area.height - 1,
+            width,
+            1,
+        );
+        f.render_widget(Clear, hint_area);
+        f.render_widget(
+            Paragraph::new(Span::styled(hint, Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD))),
+            hint_area,
+        );
+    }
+    
+    // Profile tooltip for the selected message's author, drawn over everything else
+    if let Some((author_id, anchor)) = selected_author {
+        let show = app.profile.hover_user_id == Some(author_id)
+            && app.profile.is_hover_tooltip_ready(app.ui.tick_count, app.config.profile_tooltip_delay_ticks);
+        if let Some(user) = show.then(|| app.message_author(author_id).cloned()).flatten() {
+            crate::ui::popups::draw_profile_tooltip(f, &user, anchor);
+        }
     }
 }
 
@@ -445,7 +676,7 @@ pub fn draw_chat_main(f: &mut Frame, app: &mut App, area: Rect, focused: bool) {
     let input_inner_width = area.width.saturating_sub(2); // Account for borders
     let estimated_lines = if input_inner_width > 0 && !input_str.is_empty() {
         // Simple estimation: count characters and divide by width, plus count newlines
-        let char_lines = (input_str.len() as u16 + input_inner_width - 1) / input_inner_width;
+        let char_lines = (str_width(&input_str, app.prefs.wide_emoji) as u16 + input_inner_width - 1) / input_inner_width;
         let newline_count = input_str.matches('\n').count() as u16;
         (char_lines + newline_count).max(1)
     } else {
@@ -455,20 +686,50 @@ pub fn draw_chat_main(f: &mut Frame, app: &mut App, area: Rect, focused: bool) {
     // Constrain input height to reasonable bounds (min 3, max 8 lines + borders)
     let input_height = (estimated_lines + 2).clamp(3, 10);
     
-    // Split area vertically: messages above, input below
+    // Split area vertically: messages above, reply bar (when replying), input below
+    let reply_to = app.reply_target_message();
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Min(3), // message list
+            Constraint::Length(reply_to.is_some() as u16), // reply bar
             Constraint::Length(input_height), // dynamic input box
         ])
         .split(area);
 
     // Use the clean helper method to get the title
-    let title = app.get_current_chat_title();
+    let mut title = app.get_current_chat_title();
+    if !app.chat.search_query.is_empty() {
+        let position = if app.chat.search_matches.is_empty() {
+            "no matches".to_string()
+        } else {
+            format!("{}/{}", app.chat.search_matches.len() - app.chat.search_index, app.chat.search_matches.len())
+        };
+        title.push_str(&format!(" | Search \"{}\": {} [n/N, Esc]", app.chat.search_query, position));
+        if !app.chat.history_complete() {
+            title.push_str(" — scroll up for older");
+        }
+    }
 
     draw_message_list(f, app, chunks[0], focused, &title);
 
+    if let Some(reply_to) = reply_to {
+        let (_, body) = MessageService::split_quote(&reply_to.content);
+        let snippet = MessageService::truncate_with_ellipsis(
+            MessageService::flatten_newlines(body).trim(),
+            (chunks[1].width as usize).saturating_sub(reply_to.author.chars().count() + 24),
+        );
+        f.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled(" Replying to ", Style::default().fg(Color::DarkGray)),
+                Span::styled(reply_to.author.clone(), Style::default().fg(reply_to.color).add_modifier(Modifier::BOLD)),
+                Span::styled(format!(": {}", snippet), Style::default().fg(Color::Gray)),
+                Span::styled("  [Esc] cancel", Style::default().fg(Color::DarkGray)),
+            ])),
+            chunks[1],
+        );
+    }
+
     // Create styled input text with white text and colored @mentions
     let mut input_spans = Vec::new();
     let mut last = 0;
@@ -486,7 +747,9 @@ pub fn draw_chat_main(f: &mut Frame, app: &mut App, area: Rect, focused: bool) {
         // Add the mention with user color or default styling
         let mention = &input_str[start+1..end];
         let mention_color = app.chat.channel_userlist.iter().find(|u| u.username == mention).map(|u| u.color.clone().into());
-        if let Some(mcolor) = mention_color {
+        if ChatService::is_special_mention(mention) {
+            input_spans.push(Span::styled(format!("@{}", mention), special_mention_style()));
+        } else if let Some(mcolor) = mention_color {
             input_spans.push(Span::styled(format!("@{}", mention), Style::default().fg(Color::Black).bg(mcolor).add_modifier(Modifier::BOLD)));
         } else {
             input_spans.push(Span::styled(format!("@{}", mention), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)));
@@ -516,16 +779,16 @@ pub fn draw_chat_main(f: &mut Frame, app: &mut App, area: Rect, focused: bool) {
             }
         ))
         .wrap(Wrap { trim: true });
-    f.render_widget(input, chunks[1]);
+    f.render_widget(input, chunks[2]);
     
     if focused {
         // Improved cursor positioning for multiline input
-        let input_area = chunks[1];
+        let input_area = chunks[2];
         let inner_area = Block::default().borders(Borders::ALL).inner(input_area);
         
         if inner_area.width > 0 {
-            let cursor_pos = input_str.len();
-            let text_up_to_cursor = &input_str[..cursor_pos];
+            let cursor_byte = input_str.char_indices().nth(app.chat.input_cursor()).map(|(i, _)| i).unwrap_or(input_str.len());
+            let text_up_to_cursor = &input_str[..cursor_byte];
             
             // More accurate cursor positioning that accounts for wrapping
             let mut current_line = 0u16;
@@ -536,7 +799,13 @@ pub fn draw_chat_main(f: &mut Frame, app: &mut App, area: Rect, focused: bool) {
                     current_line += 1;
                     current_col = 0;
                 } else {
-                    current_col += 1;
+                    let ch_width = char_width(ch, app.prefs.wide_emoji) as u16;
+                    // A wide glyph that doesn't fit moves to the next line whole
+                    if current_col + ch_width > inner_area.width {
+                        current_line += 1;
+                        current_col = 0;
+                    }
+                    current_col += ch_width;
                     // Handle wrapping when line exceeds width
                     if current_col >= inner_area.width {
                         current_line += 1;
@@ -558,18 +827,17 @@ pub fn draw_chat_main(f: &mut Frame, app: &mut App, area: Rect, focused: bool) {
         }
     }
     // Draw scrollbar if there are more messages than fit - use message area only
-    let messages = app.get_current_message_list();
-    let total_msgs = messages.len();
-    let max_rows = app.chat.last_chat_rows.unwrap_or(0);
+    let total_msgs = app.current_message_count();
+    let visible = app.chat.last_visible_messages.unwrap_or(0);
+    let max_offset = app.chat.max_scroll_offset.unwrap_or(0);
     
-    if total_msgs > max_rows && chunks[0].width > 2 {
+    if max_offset > 0 && chunks[0].width > 2 {
         let msg_area = chunks[0]; // Use message area, not full area
         let bar_x = msg_area.x + msg_area.width - 1;
         let bar_y = msg_area.y;
         let bar_height = msg_area.height;
         
-        let thumb_height = ((max_rows as f32 / total_msgs as f32) * bar_height as f32).ceil().max(1.0) as u16;
-        let max_offset = total_msgs.saturating_sub(max_rows);
+        let thumb_height = ((visible as f32 / total_msgs as f32) * bar_height as f32).ceil().max(1.0) as u16;
         let offset = app.chat.chat_scroll_offset.min(max_offset);
         
         // Invert thumb position: offset=0 => bottom, max_offset => top
@@ -591,8 +859,9 @@ pub fn draw_chat_main(f: &mut Frame, app: &mut App, area: Rect, focused: bool) {
     }
     // Move popups to the very end so they draw on top of everything
     if focused {
-        draw_mention_suggestion_popup(f, app, chunks[1], area);
-        draw_emoji_suggestion_popup(f, app, chunks[1], area);
+        draw_mention_suggestion_popup(f, app, chunks[2], area);
+        draw_emoji_suggestion_popup(f, app, chunks[2], area);
+        draw_emoji_picker(f, app, chunks[2], area);
     }
 }
 
@@ -602,117 +871,183 @@ pub fn draw_user_list(f: &mut Frame, app: &mut App, area: Rect, focused: bool) {
     } else {
         Style::default()
     };
-    let block = Block::default().borders(Borders::ALL).title("Users [Ctrl+U]").border_style(border_style);
+    let title = match &app.chat.user_filter {
+        Some(filter) => format!(
+            "Users: {}_ ({})",
+            filter.query,
+            app.chat.visible_user_indices(app.prefs.offline_users).len()
+        ),
+        None => format!("Users [Ctrl+U] [/] [S]ort: {}", app.prefs.user_list_sort.label()),
+    };
+    let block = Block::default().borders(Borders::ALL).title(title).border_style(border_style);
     f.render_widget(block.clone(), area);
 
     let inner_area = block.inner(area);
     if inner_area.width == 0 || inner_area.height == 0 { return; }
 
-    const AVATAR_PIXEL_SIZE: u32 = 16;
+    let avatar_pixel_size = app.prefs.avatar_pixel_size / 2;
+    let text_only = app.prefs.text_only_avatars;
     let (font_w, font_h) = app.profile.picker.font_size();
     let (font_w, font_h) = if font_w == 0 || font_h == 0 { (8, 16) } else { (font_w, font_h) };
-    let avatar_cell_width = (AVATAR_PIXEL_SIZE as f32 / font_w as f32).ceil() as u16;
-    let avatar_cell_height = (AVATAR_PIXEL_SIZE as f32 / font_h as f32).ceil() as u16;
+    // Text-only rows already lead with the status marker, so they get no avatar column
+    let (avatar_cell_width, avatar_cell_height) = if text_only {
+        (0, 1)
+    } else if crate::ui::avatar::uses_halfblocks(app) {
+        (2, 1)
+    } else {
+        (
+            (avatar_pixel_size as f32 / font_w as f32).ceil() as u16,
+            (avatar_pixel_size as f32 / font_h as f32).ceil() as u16,
+        )
+    };
     let row_height = avatar_cell_height.max(1);
 
     let mut current_y = inner_area.y;
-    use std::collections::BTreeMap;
-    use nexus_tui_common::UserRole;
-    // Group users by role
-    let mut role_map: BTreeMap<UserRole, Vec<_>> = BTreeMap::new();
-    for user in app.chat.channel_userlist.iter() {
-        role_map.entry(user.role.clone()).or_default().push(user.clone()); // Clone users to avoid borrowing issues
-    }
-    // Collect roles and reverse
-    let mut roles: Vec<_> = role_map.keys().cloned().collect();
-    roles.reverse();
-    // Status order: Connected, Busy, Away, Offline
-    // For selection logic
-    let list_state = app.chat.user_list_state.clone();
-    let selected_index = list_state.selected();
-    let mut idx = 0;
-    for role in roles {
-        let users = role_map.get(&role).cloned().unwrap_or_default();
-        // Draw role header
+    // `channel_userlist` is kept sorted in display order (see `ChatState::sort_user_list`),
+    // so rows map directly onto list indices and group headers appear where the label changes.
+    let sort = app.prefs.user_list_sort;
+    let offline_mode = app.prefs.offline_users;
+    let users: Vec<_> = app.chat.visible_user_indices(offline_mode).into_iter()
+        .map(|i| app.chat.channel_userlist[i].clone()) // Clone users to avoid borrowing issues
+        .collect();
+    let selected_index = app.chat.user_list_state.selected();
+    let mut current_group: Option<String> = None;
+    let mut hovered_row: Option<(nexus_tui_common::User, Rect)> = None;
+    for (idx, user) in users.into_iter().enumerate() {
+        if let Some(label) = crate::state::chat::user_list_group_label(&user, sort) {
+            if current_group.as_deref() != Some(label.as_str()) {
+                // Draw group header
+                if current_y + row_height > inner_area.y + inner_area.height { break; }
+                let header = Block::default()
+                    .borders(Borders::TOP)
+                    .title_alignment(ratatui::layout::Alignment::Center)
+                    .title(label.clone())
+                    .border_style(Style::default().fg(Color::DarkGray)) // Set border color to gray
+                    .style(Style::default().fg(Color::DarkGray).add_modifier(Modifier::BOLD)); // Set text color to gray
+                f.render_widget(header, Rect::new(inner_area.x, current_y, inner_area.width, row_height));
+                current_y += row_height;
+                current_group = Some(label);
+            }
+        }
         if current_y + row_height > inner_area.y + inner_area.height { break; }
+        let row_area = Rect::new(inner_area.x, current_y, inner_area.width, row_height);
+        app.ui.click_map.user_rows.push((row_area, idx));
+        let is_selected = focused && selected_index == Some(idx);
+        if is_selected {
+            hovered_row = Some((user.clone(), row_area));
+        }
+        let text_style = if is_selected {
+            Style::default().fg(Color::Black).add_modifier(Modifier::BOLD)
+        } else if user.status == nexus_tui_common::UserStatus::Offline {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default().fg(user.color.clone().into())
+        };
+        if is_selected {
+            f.render_widget(Block::default().style(Style::default().bg(Color::Cyan)), row_area);
+        }
+        let status_symbol = match user.status {
+            nexus_tui_common::UserStatus::Connected => "●",
+            nexus_tui_common::UserStatus::Away => "◐",
+            nexus_tui_common::UserStatus::Busy => "■",
+            nexus_tui_common::UserStatus::Offline => "○",
+        };
+        let status_color = match user.status {
+            nexus_tui_common::UserStatus::Connected => Color::Green,
+            nexus_tui_common::UserStatus::Away => Color::Yellow,
+            nexus_tui_common::UserStatus::Busy => Color::Red,
+            nexus_tui_common::UserStatus::Offline => Color::DarkGray,
+        };
+        // Users without a profile picture get a blank avatar area for alignment
+        let row_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(avatar_cell_width), Constraint::Min(0)])
+            .split(row_area);
+        if !text_only {
+            crate::ui::avatar::render_avatar(f, app, &user, avatar_pixel_size, row_chunks[0]);
+        }
+        let text = Line::from(vec![
+            Span::styled(format!(" {} ", status_symbol), Style::default().fg(status_color)),
+            Span::styled(&user.username, text_style),
+        ]);
+        f.render_widget(Paragraph::new(text).alignment(ratatui::layout::Alignment::Left), row_chunks[1]);
+        current_y += row_height;
+    }
+
+    // Offline users folded into a single row at the bottom
+    let offline_count = app.chat.offline_user_count();
+    if offline_mode == crate::global_prefs::OfflineUsersMode::Collapse && offline_count > 0
+        && current_y + row_height <= inner_area.y + inner_area.height
+    {
         let header = Block::default()
             .borders(Borders::TOP)
             .title_alignment(ratatui::layout::Alignment::Center)
-            .title(format!("{:?}", role))
-            .border_style(Style::default().fg(Color::DarkGray)) // Set border color to gray
-            .style(Style::default().fg(Color::DarkGray).add_modifier(Modifier::BOLD)); // Set text color to gray
+            .title(format!("Offline — {} [o]", offline_count))
+            .border_style(Style::default().fg(Color::DarkGray))
+            .style(Style::default().fg(Color::DarkGray).add_modifier(Modifier::BOLD));
         f.render_widget(header, Rect::new(inner_area.x, current_y, inner_area.width, row_height));
-        current_y += row_height;
-        for user in users {
-            if current_y + row_height > inner_area.y + inner_area.height { break; }
-            let row_area = Rect::new(inner_area.x, current_y, inner_area.width, row_height);
-            let is_selected = focused && selected_index == Some(idx);
-            let text_style = if is_selected {
-                Style::default().fg(Color::Black).add_modifier(Modifier::BOLD)
-            } else {
-                Style::default().fg(user.color.clone().into())
-            };
-            if is_selected {
-                f.render_widget(Block::default().style(Style::default().bg(Color::Cyan)), row_area);
-            }
-            let status_symbol = match user.status {
-                nexus_tui_common::UserStatus::Connected => "●",
-                nexus_tui_common::UserStatus::Away => "◐",
-                nexus_tui_common::UserStatus::Busy => "■",
-                nexus_tui_common::UserStatus::Offline => "○",
-            };
-            let status_color = match user.status {
-                nexus_tui_common::UserStatus::Connected => Color::Green,
-                nexus_tui_common::UserStatus::Away => Color::Yellow,
-                nexus_tui_common::UserStatus::Busy => Color::Red,
-                nexus_tui_common::UserStatus::Offline => Color::DarkGray,
-            };
-            if let Some(state) = get_avatar_protocol(app, &user, AVATAR_PIXEL_SIZE) {
-                let row_chunks = Layout::default()
-                    .direction(Direction::Horizontal)
-                    .constraints([Constraint::Length(avatar_cell_width), Constraint::Min(0)])
-                    .split(row_area);
-                let image_widget = StatefulImage::default();
-                f.render_stateful_widget(image_widget, row_chunks[0], state);
-                let text = Line::from(vec![
-                    Span::styled(format!(" {} ", status_symbol), Style::default().fg(status_color)),
-                    Span::styled(&user.username, text_style),
-                ]);
-                f.render_widget(Paragraph::new(text).alignment(ratatui::layout::Alignment::Left), row_chunks[1]);
-            } else {
-                // Render a blank avatar area for alignment
-                let row_chunks = Layout::default()
-                    .direction(Direction::Horizontal)
-                    .constraints([Constraint::Length(avatar_cell_width), Constraint::Min(0)])
-                    .split(row_area);
-                // Optionally, render a placeholder avatar here instead of leaving blank
-                // f.render_widget(Paragraph::new(" "), row_chunks[0]);
-                let text = Line::from(vec![
-                    Span::styled(format!(" {} ", status_symbol), Style::default().fg(status_color)),
-                    Span::styled(&user.username, text_style),
-                ]);
-                f.render_widget(Paragraph::new(text).alignment(ratatui::layout::Alignment::Left), row_chunks[1]);
-            }
-            current_y += row_height;
-            idx += 1;
+    }
+
+    // Compact profile preview once the selection has rested on a user for a moment
+    if let Some((user, row_area)) = hovered_row {
+        let ready = app.profile.hover_user_id == Some(user.id)
+            && app.profile.is_hover_tooltip_ready(app.ui.tick_count, app.config.profile_tooltip_delay_ticks);
+        if ready {
+            crate::ui::popups::draw_profile_tooltip(f, &user, row_area);
+        }
+    }
+}
+
+/// Style for http(s) links in messages and profiles
+pub fn link_style() -> Style {
+    Style::default().fg(Color::LightBlue).add_modifier(Modifier::UNDERLINED)
+}
+
+/// Plain text with its http(s) links styled
+pub fn linkify(text: &str) -> Vec<Span<'_>> {
+    let mut spans = Vec::new();
+    let mut last = 0;
+    for (start, end) in MessageService::find_urls(text) {
+        if start > last {
+            spans.push(Span::raw(&text[last..start]));
         }
+        spans.push(Span::styled(&text[start..end], link_style()));
+        last = end;
+    }
+    if last < text.len() {
+        spans.push(Span::raw(&text[last..]));
     }
+    spans
+}
+
+/// Group mentions (`@everyone`, `@here`, ...) stand out from user mentions.
+fn special_mention_style() -> Style {
+    Style::default().fg(Color::Black).bg(Color::LightRed).add_modifier(Modifier::BOLD)
 }
 
 /// Draw the mention suggestion popup below (or above) the input area.
 pub fn draw_mention_suggestion_popup(f: &mut Frame, app: &App, input_area: Rect, chat_area: Rect) {
     if app.chat.mention_suggestions.is_empty() { return; }
-    let max_name_len = app.chat.mention_suggestions.iter().map(|&i| app.chat.channel_userlist[i].username.len()).max().unwrap_or(8).max(8);
+    let label = |name: &str| match ChatService::special_mention_description(name) {
+        Some(description) => format!("@{} — {}", name, description),
+        None => name.to_string(),
+    };
+    let max_name_len = app.chat.mention_suggestions.iter().map(|name| label(name).chars().count()).max().unwrap_or(8).max(8);
     let popup_width = (max_name_len + 12).min(chat_area.width as usize) as u16;
     let mut lines = vec![];
-    for (i, &user_idx) in app.chat.mention_suggestions.iter().enumerate() {
-        let user = &app.chat.channel_userlist[user_idx];
+    for (i, name) in app.chat.mention_suggestions.iter().enumerate() {
         let style = if i == app.chat.mention_selected {
             Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+        } else if ChatService::is_special_mention(name) {
+            Style::default().fg(Color::LightRed).bg(Color::Black).add_modifier(Modifier::BOLD)
         } else {
-            Style::default().fg(user.color.clone().into()).bg(Color::Black)
+            let color = app.chat.channel_userlist.iter()
+                .find(|u| &u.username == name)
+                .map(|u| u.color.clone().into())
+                .unwrap_or(Color::White);
+            Style::default().fg(color).bg(Color::Black)
         };
-        lines.push(Line::from(Span::styled(format!("{}", user.username), style)));
+        lines.push(Line::from(Span::styled(label(name), style)));
     }
     let popup_height = (lines.len() as u16).saturating_add(2);
     // Default: below input
@@ -782,30 +1117,109 @@ pub fn draw_emoji_suggestion_popup(f: &mut Frame, app: &App, input_area: Rect, c
         inner_area
     );
     
-    // Draw the emoji grid
-    for (i, emoji) in visible_emojis.iter().enumerate() {
-        let row = i / GRID_COLS;
-        let col = i % GRID_COLS;
-        
-        let x = inner_area.x + (col * cell_width) as u16;
-        let y = inner_area.y + (row * cell_height) as u16;
-        let cell_area = Rect::new(x, y, cell_width as u16, cell_height as u16);
-        
-        // Check if this is the selected emoji
-        let global_index = start_idx + i;
-        let is_selected = global_index == selected_index;
-        
-        let style = if is_selected {
+    draw_emoji_grid(f, inner_area, visible_emojis, selected_index - start_idx, GRID_COLS, cell_width, app);
+}
+
+/// Emojis laid out in `cols` columns of `cell_width` cells, `selected` highlighted and
+/// pinned favorites tinted
+fn draw_emoji_grid(f: &mut Frame, area: Rect, emojis: &[String], selected: usize, cols: usize, cell_width: usize, app: &App) {
+    for (i, emoji) in emojis.iter().enumerate() {
+        let row = i / cols;
+        let col = i % cols;
+        if row as u16 >= area.height {
+            break;
+        }
+        let cell_area = Rect::new(area.x + (col * cell_width) as u16, area.y + row as u16, cell_width as u16, 1);
+        let style = if i == selected {
             Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else if app.prefs.favorite_emoji.contains(emoji) {
+            Style::default().fg(Color::White).bg(Color::Rgb(60, 50, 0))
         } else {
             Style::default().fg(Color::White)
         };
-        
         // Center the emoji in its cell
-        let emoji_text = format!("{:^width$}", emoji, width = cell_width);
-        f.render_widget(
-            Paragraph::new(emoji_text).style(style),
-            cell_area
-        );
+        f.render_widget(Paragraph::new(pad_center(emoji, cell_width, app.prefs.wide_emoji)).style(style), cell_area);
+    }
+}
+
+/// The emoji picker (Ctrl+E) above the input: search line, a page of the grid, page count
+pub fn draw_emoji_picker(f: &mut Frame, app: &App, input_area: Rect, chat_area: Rect) {
+    use crate::state::chat::EmojiPicker;
+    let Some(picker) = &app.chat.emoji_picker else { return };
+    let cell_width = 6;
+    let per_page = EmojiPicker::COLS * EmojiPicker::ROWS;
+    let width = ((EmojiPicker::COLS * cell_width + 2) as u16).min(chat_area.width);
+    let height = (EmojiPicker::ROWS as u16 + 5).min(input_area.y.saturating_sub(chat_area.y));
+    let area = Rect::new(input_area.x, input_area.y.saturating_sub(height), width, height);
+    f.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Emoji ({})", picker.matches.len()))
+        .style(Style::default().bg(Color::Black));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(1), Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("Search: ", Style::default().fg(Color::Cyan)),
+            Span::raw(format!("{}_", picker.query)),
+        ])),
+        rows[0],
+    );
+    if picker.matches.is_empty() {
+        f.render_widget(Paragraph::new(Span::styled("No matching emoji", Style::default().fg(Color::DarkGray))), rows[2]);
+    } else {
+        let start = picker.selected / per_page * per_page;
+        let end = (start + per_page).min(picker.matches.len());
+        draw_emoji_grid(f, rows[2], &picker.matches[start..end], picker.selected - start, EmojiPicker::COLS, cell_width, app);
     }
+    let pages = picker.matches.len().div_ceil(per_page).max(1);
+    f.render_widget(
+        Paragraph::new(Span::styled(
+            format!("Page {}/{} | Enter: insert | Ctrl+F: favorite", picker.selected / per_page + 1, pages),
+            Style::default().fg(Color::DarkGray),
+        )),
+        rows[3],
+    );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paging_step_counts_what_fits_from_the_bottom() {
+        // Oldest to newest: one-liners around a 6-row and an 8-row message
+        let heights = [1, 6, 1, 1, 1, 8, 1, 1];
+        // 12 rows with a blank line after each: 1+1, 1+1, then the 8-row one overflows
+        assert_eq!(messages_that_fit(heights.iter().rev().copied(), 12, 1), 2);
+        // Compact density has no spacing: 1, 1, 8, 1, 1 takes all 12 rows
+        assert_eq!(messages_that_fit(heights.iter().rev().copied(), 12, 0), 5);
+        // Exactly filling the view still fits
+        assert_eq!(messages_that_fit([3, 3, 3], 12, 1), 3);
+        assert_eq!(messages_that_fit([1; 40], 12, 1), 6);
+    }
+
+    #[test]
+    fn max_offset_leaves_the_oldest_page_filling_the_view() {
+        let oldest = [1, 6, 1, 1, 1, 8, 1, 1];
+        // 11 rows below the history indicator: 1+1, 6+1, 1+1 fit and the next doesn't
+        assert_eq!(max_scroll_offset(50, &oldest, 12, 1), 47);
+        // Without spacing 1, 6, 1, 1, 1 take 10 rows and the 8-row message doesn't fit
+        assert_eq!(max_scroll_offset(50, &oldest, 12, 0), 45);
+        // Fewer messages than a page can't scroll at all
+        assert_eq!(max_scroll_offset(3, &[1, 1, 1], 12, 1), 0);
+    }
+
+    #[test]
+    fn message_taller_than_the_view_still_scrolls_into_view() {
+        // Nothing fits, but the oldest message must stay reachable
+        assert_eq!(messages_that_fit([30, 1], 12, 1), 0);
+        assert_eq!(max_scroll_offset(10, &[30, 1], 12, 1), 9);
+    }
+}
+
@@ -3,32 +3,75 @@
 use ratatui::{Frame, layout::{Rect, Layout, Constraint, Direction}, style::{Style, Color, Modifier}, widgets::{Block, List, ListItem, Paragraph, Borders, Wrap}, text::{Line, Span}};
 use ratatui::prelude::Stylize;
 use crate::app::App;
-use crate::ui::time_format::{format_message_timestamp, format_date_delimiter};
+use crate::services::MessageService;
+use crate::ui::time_format::{format_message_timestamp, format_date_delimiter, format_relative_time};
 use chrono::Local;
 
+/// Split `text` so the chars matched by the list filter are highlighted
+fn filter_match_spans(text: &str, query: Option<&str>, style: Style) -> Vec<Span<'static>> {
+    let indices = match query.and_then(|q| MessageService::fuzzy_match(q, text)) {
+        Some((_, indices)) if !indices.is_empty() => indices,
+        _ => return vec![Span::styled(text.to_string(), style)],
+    };
+    let highlight = style.fg(Color::Black).bg(Color::Yellow);
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (i, ch) in text.chars().enumerate() {
+        let matched = indices.binary_search(&i).is_ok();
+        if matched != run_matched && !run.is_empty() {
+            spans.push(Span::styled(std::mem::take(&mut run), if run_matched { highlight } else { style }));
+        }
+        run_matched = matched;
+        run.push(ch);
+    }
+    if !run.is_empty() {
+        spans.push(Span::styled(run, if run_matched { highlight } else { style }));
+    }
+    spans
+}
+
+/// " | Filter: query_" while a list filter is open
+fn filter_title(filter: Option<&crate::state::forum::ListFilter>, matches: usize) -> String {
+    match filter {
+        Some(filter) => format!(" | Filter: {}_ ({} found, Esc to clear)", filter.query, matches),
+        None => " | [/] Search".to_string(),
+    }
+}
+
 pub fn draw_forum_list(f: &mut Frame, app: &mut App, area: Rect) {
-    let items: Vec<ListItem> = app.forum.forums.iter().map(|forum| {
-        ListItem::new(Line::from(vec![
-            Span::styled(format!("{:<30}", forum.name), Style::default().fg(Color::Cyan)),
-            Span::raw(forum.description.clone())
-        ]))
+    let visible = app.forum.visible_forum_indices();
+    let query = app.forum.forum_filter.as_ref().map(|filter| filter.query.as_str());
+    let items: Vec<ListItem> = visible.iter().map(|&i| {
+        let forum = &app.forum.forums[i];
+        let mut spans = filter_match_spans(&forum.name, query, Style::default().fg(Color::Cyan));
+        let pad = 30usize.saturating_sub(forum.name.chars().count());
+        spans.push(Span::raw(" ".repeat(pad)));
+        spans.push(Span::raw(forum.description.clone()));
+        ListItem::new(Line::from(spans))
     }).collect();
 
-    let title = if let Some(user) = &app.auth.current_user {
+    let mut title = if let Some(user) = &app.auth.current_user {
         if user.role == nexus_tui_common::UserRole::Admin {
-            "Forums | [N]ew Forum | [D]elete Forum"
+            "Forums | [N]ew Forum | [D]elete Forum | New [T]hread"
         } else {
-            "Forums"
+            "Forums | New [T]hread"
         }
     } else {
         "Forums"
-    };
+    }.to_string();
+    title.push_str(&filter_title(app.forum.forum_filter.as_ref(), visible.len()));
 
+    // The list only holds the visible forums, so map the selection into it
+    let mut list_state = ratatui::widgets::ListState::default()
+        .with_offset(app.forum.forum_list_state.offset())
+        .with_selected(app.forum.forum_list_state.selected().and_then(|sel| visible.iter().position(|&i| i == sel)));
     let list = List::new(items)
         .block(Block::default().borders(Borders::ALL).title(title))
         .highlight_style(Style::default().bg(Color::Cyan).fg(Color::Black).add_modifier(Modifier::BOLD))
         .highlight_symbol(">> ");
-    f.render_stateful_widget(list, area, &mut app.forum.forum_list_state);
+    f.render_stateful_widget(list, area, &mut list_state);
+    *app.forum.forum_list_state.offset_mut() = list_state.offset();
 }
 
 pub fn draw_thread_list(f: &mut Frame, app: &mut App, area: Rect) {
@@ -39,9 +82,11 @@ pub fn draw_thread_list(f: &mut Frame, app: &mut App, area: Rect) {
             return;
         }
     };
+    let visible = app.forum.visible_thread_indices();
+    let query = app.forum.thread_filter.as_ref().map(|filter| filter.query.as_str());
     let block = Block::default()
         .borders(Borders::ALL)
-        .title(format!("Threads in '{}' | [N]ew Thread{}", 
+        .title(format!("Threads in '{}' | [N]ew Thread{}{}", 
             forum.name,
             if let Some(user) = &app.auth.current_user {
                 if user.role == nexus_tui_common::UserRole::Admin {
@@ -51,7 +96,8 @@ pub fn draw_thread_list(f: &mut Frame, app: &mut App, area: Rect) {
                 }
             } else {
                 ""
-            }
+            },
+            filter_title(app.forum.thread_filter.as_ref(), visible.len()),
         ));
     f.render_widget(&block, area);
     let inner_area = block.inner(area);
@@ -75,7 +121,7 @@ pub fn draw_thread_list(f: &mut Frame, app: &mut App, area: Rect) {
             height: row_height,
         });
     f.render_widget(
-        Paragraph::new(Span::styled("Title", Style::default().add_modifier(Modifier::BOLD).fg(Color::Cyan)))
+        Paragraph::new(Span::styled("  Title", Style::default().add_modifier(Modifier::BOLD).fg(Color::Cyan)))
             .alignment(ratatui::layout::Alignment::Left),
         header_layout[0],
     );
@@ -90,10 +136,13 @@ pub fn draw_thread_list(f: &mut Frame, app: &mut App, area: Rect) {
         header_layout[2],
     );
 
-    // Thread rows
+    // Thread rows, each followed by a preview line of its first post
+    let now = Local::now();
+    let preview_height = 1;
     let mut y = inner_area.y + row_height;
-    for (i, thread) in forum.threads.iter().enumerate() {
-        if y + row_height > inner_area.y + inner_area.height {
+    for &i in &visible {
+        let thread = &forum.threads[i];
+        if y + row_height + preview_height > inner_area.y + inner_area.height {
             break;
         }
         let row_layout = Layout::default()
@@ -117,9 +166,16 @@ pub fn draw_thread_list(f: &mut Frame, app: &mut App, area: Rect) {
             (Color::Cyan, thread.author.color.clone().into(), Color::Gray)
         };
         // Title
-        let title = thread.title.clone();
+        let unread = app.forum.is_thread_unread(thread);
+        let mut title_style = Style::default().fg(title_fg).bg(bg_style.bg.unwrap_or(Color::Reset));
+        if unread {
+            title_style = title_style.add_modifier(Modifier::BOLD);
+        }
+        let marker_fg = if is_selected { Color::Black } else { Color::Yellow };
+        let mut title = vec![Span::styled(if unread { "● " } else { "  " }, Style::default().fg(marker_fg).bg(bg_style.bg.unwrap_or(Color::Reset)))];
+        title.extend(filter_match_spans(&thread.title, query, title_style));
         f.render_widget(
-            Paragraph::new(Span::styled(title, Style::default().fg(title_fg)).bg(bg_style.bg.unwrap_or(Color::Reset)))
+            Paragraph::new(Line::from(title))
                 .alignment(ratatui::layout::Alignment::Left),
             row_layout[0],
         );
@@ -131,17 +187,35 @@ pub fn draw_thread_list(f: &mut Frame, app: &mut App, area: Rect) {
             row_layout[1],
         );
         // Date
-        let date_str = format_date_delimiter(thread.timestamp);
+        let date_str = format_relative_time(thread.timestamp, now);
         f.render_widget(
             Paragraph::new(Span::styled(date_str, Style::default().fg(date_fg)).bg(bg_style.bg.unwrap_or(Color::Reset)))
                 .alignment(ratatui::layout::Alignment::Left),
             row_layout[2],
         );
         y += row_height;
+        // Preview snippet (lightweight loads keep post content, so this is always available)
+        let preview_area = Rect { x: inner_area.x, y, width: inner_area.width, height: preview_height };
+        let preview_width = (preview_area.width as usize).saturating_sub(2);
+        let preview = match thread.posts.first() {
+            Some(post) => MessageService::truncate_with_ellipsis(
+                MessageService::flatten_newlines(&post.content).trim(),
+                preview_width,
+            ),
+            None => "(no posts)".to_string(),
+        };
+        let preview_fg = if is_selected { Color::Black } else { Color::DarkGray };
+        f.render_widget(
+            Paragraph::new(Span::styled(format!("  {}", preview), Style::default().fg(preview_fg).add_modifier(Modifier::ITALIC)))
+                .style(bg_style),
+            preview_area,
+        );
+        y += preview_height;
     }
 }
 
 pub fn draw_post_view(f: &mut Frame, app: &mut App, area: Rect) {
+    app.ui.click_map.post_view = Some(area);
     let thread = match (app.forum.current_forum_id, app.forum.current_thread_id) {
         (Some(fid), Some(tid)) => app.forum.forums.iter().find(|f| f.id == fid)
             .and_then(|f| f.threads.iter().find(|t| t.id == tid)),
@@ -159,6 +233,11 @@ pub fn draw_post_view(f: &mut Frame, app: &mut App, area: Rect) {
             "".to_string()
         };
         
+        let delete_help = match app.forum.get_selected_post() {
+            Some(post) if crate::state::forum::ForumState::can_delete_post(post, app.auth.current_user.as_ref()) => " | D: Delete",
+            _ => "",
+        };
+        
         let navigation_help = if app.forum.selected_reply_index.is_some() {
             " | ←→ Navigate Replies | Enter: Jump to Reply | Esc: Clear"
         } else if app.forum.show_reply_context {
@@ -167,10 +246,11 @@ pub fn draw_post_view(f: &mut Frame, app: &mut App, area: Rect) {
             " | ↑↓ Select Posts | →: View Replies | R: Reply To | Alt+R: Reply | C: Show Context"
         };
         
-        let title = format!("Reading: {}{}{}", 
+        let title = format!("Reading: {}{}{}{}", 
             thread.title,
             reply_status,
-            navigation_help
+            navigation_help,
+            delete_help
         );
         
         let block = Block::default().borders(Borders::ALL).title(title);
@@ -181,29 +261,34 @@ pub fn draw_post_view(f: &mut Frame, app: &mut App, area: Rect) {
             return;
         }
         
-        // Calculate scrolling for post view
         let posts = &thread.posts;
         if posts.is_empty() {
             f.render_widget(Paragraph::new("No posts in this thread."), inner_area);
             return;
         }
         
+        // Leave the rightmost column for the scrollbar
+        let text_area = Rect { width: inner_area.width.saturating_sub(1), ..inner_area };
         let selected_post_idx = app.forum.selected_post_index.unwrap_or(0);
-        let scroll_offset = app.forum.scroll_offset;
-        
-        // Calculate how many posts can fit on screen (rough estimate)
-        let estimated_lines_per_post = 6; // Header + author + content + separator
-        let visible_posts = (inner_area.height as usize / estimated_lines_per_post).max(1);
-        
-        // Determine which posts to render based on scroll offset
-        let start_post_idx = scroll_offset;
-        let end_post_idx = (start_post_idx + visible_posts * 2).min(posts.len()); // Render extra for smoother scrolling
-        let visible_posts_slice = &posts[start_post_idx..end_post_idx];
         
         let mut text_lines: Vec<Line> = Vec::new();
+        // Index into `text_lines` where each post starts
+        let mut post_starts: Vec<usize> = Vec::with_capacity(posts.len());
         
-        for (post_offset, post) in visible_posts_slice.iter().enumerate() {
-            let post_idx = start_post_idx + post_offset;
+        let now = Local::now();
+        let post_date = |ts: i64| chrono::TimeZone::timestamp_opt(&Local, ts, 0).single().map(|dt| dt.date_naive());
+        for (post_idx, post) in posts.iter().enumerate() {
+            // Date delimiter where the day changes, as in chat
+            if post_idx > 0 && post_date(post.timestamp) != post_date(posts[post_idx - 1].timestamp) {
+                let label = format!(" {} ", format_date_delimiter(post.timestamp));
+                let rule = "─".repeat((text_area.width as usize).saturating_sub(label.chars().count()) / 2);
+                text_lines.push(Line::from(Span::styled(
+                    format!("{}{}{}", rule, label, rule),
+                    Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+                )));
+                text_lines.push(Line::from(""));
+            }
+            post_starts.push(text_lines.len());
             let is_selected = selected_post_idx == post_idx;
             let post_id_short = &post.id.to_string()[..8];
             
@@ -219,7 +304,7 @@ pub fn draw_post_view(f: &mut Frame, app: &mut App, area: Rect) {
             ];
             
             // Add OP indicator for the first post (original poster)
-            if post_idx == start_post_idx && start_post_idx == 0 {
+            if post_idx == 0 {
                 header_spans.push(Span::raw(" "));
                 header_spans.push(Span::styled(
                     "(OP)",
@@ -274,7 +359,9 @@ pub fn draw_post_view(f: &mut Frame, app: &mut App, area: Rect) {
             text_lines.push(Line::from(header_spans));
             
             // Author and timestamp
-            let ts_str = format_message_timestamp(post.timestamp, Local::now());
+            let ts_str = format_message_timestamp(post.timestamp, now, app.prefs.clock_format());
+            // Recent posts already read as "2 minutes ago"
+            let age = (now.timestamp() - post.timestamp >= 300).then(|| format_relative_time(post.timestamp, now));
             let author_line = Line::from(vec![
                 Span::styled(
                     format!("From: {} ", post.author.username),
@@ -284,9 +371,29 @@ pub fn draw_post_view(f: &mut Frame, app: &mut App, area: Rect) {
                     format!("({})", ts_str),
                     Style::default().fg(Color::DarkGray)
                 ),
+                Span::styled(
+                    age.map(|age| format!(" · {}", age)).unwrap_or_default(),
+                    Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC)
+                ),
             ]);
             text_lines.push(author_line);
             
+            // Quote the start of the post this one answers
+            if let Some(reply_to_id) = post.reply_to {
+                let quote_width = (text_area.width as usize).saturating_sub(6);
+                let quote = match app.forum.get_post(reply_to_id) {
+                    Some(parent) => MessageService::truncate_with_ellipsis(
+                        &format!("{}: {}", parent.author.username, parent.content.split_whitespace().collect::<Vec<_>>().join(" ")),
+                        quote_width,
+                    ),
+                    None => format!("#{} (not loaded)", &reply_to_id.to_string()[..8]),
+                };
+                text_lines.push(Line::from(vec![
+                    Span::styled("  │ ", Style::default().fg(Color::Magenta)),
+                    Span::styled(quote, Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC)),
+                ]));
+            }
+            
             // Post content with word wrapping
             let content_style = if is_selected {
                 Style::default().bg(Color::DarkGray)
@@ -297,7 +404,7 @@ pub fn draw_post_view(f: &mut Frame, app: &mut App, area: Rect) {
             // Simple word wrapping for post content
             let content_words: Vec<&str> = post.content.split_whitespace().collect();
             let mut current_content_line = String::new();
-            let line_width = (inner_area.width as usize).saturating_sub(4);
+            let line_width = (text_area.width as usize).saturating_sub(4);
             
             for word in content_words {
                 if current_content_line.len() + word.len() + 1 > line_width {
@@ -331,11 +438,11 @@ pub fn draw_post_view(f: &mut Frame, app: &mut App, area: Rect) {
                     };
                     
                     let reply_id_short = &reply_post.id.to_string()[..8];
-                    let reply_preview = if reply_post.content.len() > 50 {
-                        format!("#{}: {}...", reply_id_short, &reply_post.content[..47])
-                    } else {
-                        format!("#{}: {}", reply_id_short, reply_post.content)
-                    };
+                    let reply_preview = format!(
+                        "#{}: {}",
+                        reply_id_short,
+                        MessageService::truncate_with_ellipsis(&reply_post.content, 50)
+                    );
                     
                     text_lines.push(Line::from(vec![
                         Span::styled("  → ", Style::default().fg(Color::Green)),
@@ -355,11 +462,7 @@ pub fn draw_post_view(f: &mut Frame, app: &mut App, area: Rect) {
                     // Show original post info
                     let original_id_short = &replied_to_post.id.to_string()[..8];
                     let original_author = &replied_to_post.author.username;
-                    let original_preview = if replied_to_post.content.len() > 80 {
-                        format!("{}...", &replied_to_post.content[..77])
-                    } else {
-                        replied_to_post.content.clone()
-                    };
+                    let original_preview = MessageService::truncate_with_ellipsis(&replied_to_post.content, 80);
                     
                     text_lines.push(Line::from(vec![
                         Span::styled("  Original: ", Style::default().fg(Color::Cyan)),
@@ -383,30 +486,81 @@ pub fn draw_post_view(f: &mut Frame, app: &mut App, area: Rect) {
             
             // Separator between posts
             text_lines.push(Line::from(Span::styled(
-                "─".repeat(inner_area.width as usize),
+                "─".repeat(text_area.width as usize),
                 Style::default().fg(Color::DarkGray)
             )));
             text_lines.push(Line::from(Span::raw(""))); // Empty line
         }
         
-        // Add scroll indicator at the bottom
-        if posts.len() > visible_posts {
-            let scroll_info = format!("Posts {}-{} of {} | PgUp/PgDn: Scroll | Home/End: Jump", 
-                start_post_idx + 1, 
-                end_post_idx.min(posts.len()), 
-                posts.len()
-            );
-            text_lines.push(Line::from(Span::styled(
-                scroll_info,
-                Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC)
-            )));
+        // Rows each line takes once wrapped, to scroll by screen rows
+        let width = text_area.width.max(1) as usize;
+        let mut row_starts: Vec<usize> = Vec::with_capacity(text_lines.len() + 1);
+        let mut total_rows = 0;
+        for line in &text_lines {
+            row_starts.push(total_rows);
+            total_rows += line.width().div_ceil(width).max(1);
+        }
+        row_starts.push(total_rows);
+        
+        let height = text_area.height as usize;
+        let max_scroll = total_rows.saturating_sub(height);
+        let mut scroll = (app.forum.post_scroll as usize).min(max_scroll);
+        if app.forum.follow_selected_post {
+            // Bring the selected post into view, its top first when it's taller than the view
+            let start = row_starts[post_starts[selected_post_idx.min(posts.len() - 1)]];
+            let end = post_starts.get(selected_post_idx + 1).map_or(total_rows, |&line| row_starts[line]);
+            if start < scroll {
+                scroll = start;
+            } else if end > scroll + height {
+                scroll = start.min(end.saturating_sub(height));
+            }
+            scroll = scroll.min(max_scroll);
         }
         
         let paragraph = Paragraph::new(text_lines)
             .wrap(Wrap { trim: false })
-            .scroll((0, 0));
+            .scroll((scroll as u16, 0));
+        f.render_widget(paragraph, text_area);
         
-        f.render_widget(paragraph, inner_area);
+        if total_rows > height && inner_area.width > 1 {
+            let bar_x = inner_area.x + inner_area.width - 1;
+            let bar_height = inner_area.height;
+            let thumb_height = ((height as f32 / total_rows as f32) * bar_height as f32).ceil().max(1.0) as u16;
+            let thumb_pos = if max_scroll > 0 {
+                ((scroll as f32 / max_scroll as f32) * (bar_height - thumb_height) as f32).round() as u16
+            } else {
+                0
+            };
+            for i in 0..bar_height {
+                let symbol = if i >= thumb_pos && i < thumb_pos + thumb_height { "█" } else { "│" };
+                f.render_widget(Paragraph::new(symbol), Rect::new(bar_x, inner_area.y + i, 1, 1));
+            }
+        }
+        
+        app.forum.post_scroll = scroll as u16;
+        app.forum.post_view_height = text_area.height;
+        app.forum.follow_selected_post = false;
+        
+        if let Some(post_id) = app.forum.confirm_delete_post {
+            let popup = crate::ui::popups::draw_centered_rect(area, 40, 20);
+            let prompt = Paragraph::new(vec![
+                Line::from(""),
+                Line::from(Span::styled(
+                    format!("Delete post #{}?", &post_id.to_string()[..8]),
+                    Style::default().add_modifier(Modifier::BOLD),
+                )),
+                Line::from(""),
+                Line::from(vec![
+                    Span::styled("[Y]es", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                    Span::raw("  "),
+                    Span::styled("[N]o", Style::default().fg(Color::Green)),
+                ]),
+            ])
+            .alignment(ratatui::layout::Alignment::Center)
+            .block(Block::default().title("Are you sure?").borders(Borders::ALL).border_type(ratatui::widgets::BorderType::Double));
+            f.render_widget(ratatui::widgets::Clear, popup);
+            f.render_widget(prompt, popup);
+        }
     } else {
         f.render_widget(Paragraph::new("Thread not found..."), area);
     }
@@ -11,6 +11,10 @@ pub mod avatar;
 pub mod time_format;
 pub mod themes;
 pub mod backgrounds;
+pub mod text_width;
+pub mod shortcuts;
+pub mod notifications;
+pub mod appearance;
 
 use ratatui::Frame;
 use nexus_tui_common::UserRole;
@@ -24,10 +28,32 @@ use crate::ui::chat::draw_chat;
 use crate::ui::popups::{draw_input_popup, draw_notification_popup, draw_minimal_notification_popup, draw_profile_view_popup, draw_user_actions_popup, draw_server_actions_popup, draw_server_invite_selection_popup, draw_cyberpunk_server_error_popup};
 
 
+/// Draw the full-screen content for a mode (the Input popup is drawn separately).
+fn draw_screen(f: &mut Frame, app: &mut App, mode: AppMode, area: ratatui::layout::Rect) {
+    match mode {
+        AppMode::Login => draw_login(f, app, area),
+        AppMode::Register => draw_register(f, app, area),
+        AppMode::MainMenu => draw_main_menu(f, app, area),
+        AppMode::Settings => draw_settings(f, app, area),
+        AppMode::ForumList => draw_forum_list(f, app, area),
+        AppMode::ThreadList => draw_thread_list(f, app, area),
+        AppMode::PostView => draw_post_view(f, app, area),
+        AppMode::Chat => draw_chat(f, app, area),
+        AppMode::Input => {}
+        AppMode::EditProfile => draw_profile_edit_page(f, app, area),
+        AppMode::ColorPicker => draw_color_picker(f, app, area),
+        AppMode::Preferences => crate::ui::settings::draw_preferences(f, app, area),
+        AppMode::NotificationCenter => crate::ui::notifications::draw_notification_center(f, app, area),
+        AppMode::ServerPicker => crate::ui::auth::draw_server_picker(f, app, area),
+        AppMode::AppearancePicker => crate::ui::appearance::draw_appearance_picker(f, app, area),
+    }
+}
+
 pub fn ui(f: &mut Frame, app: &mut App) {
     let size = f.area();
+    app.ui.click_map.clear();
     let (banner_height, use_full_banner) = match app.ui.mode {
-        AppMode::Login | AppMode::Register => (9, true),
+        AppMode::Login | AppMode::Register | AppMode::ServerPicker => (9, true),
         _ => (3, false),
     };
     
@@ -52,8 +78,10 @@ pub fn ui(f: &mut Frame, app: &mut App) {
     // Only show footer if not in main menu
     if show_footer {
         let help_text = match app.ui.mode {
-            AppMode::Login | AppMode::Register => "[Esc] QUIT | [F2] Preferences\n[Tab]/[Shift+Tab] Change Focus | [Enter] Select/Submit",
-            _ => "[Tab] Change Focus | [F2] Prefs | [↑↓] Nav\n[PgUp/PgDn] Scroll | [Enter] Sel | [Esc] Back"
+            AppMode::Login | AppMode::Register => "[Esc] QUIT | [F1] About | [F2] Preferences\n[Tab]/[Shift+Tab] Change Focus | [Enter] Select/Submit",
+            AppMode::ServerPicker => "[Esc] QUIT | [F1] About | [F2] Preferences\n[↑↓] Select Server | [Enter] Connect",
+            _ if app.prefs.double_esc_quit => "[Tab] Change Focus | [F1] About | [F2] Prefs | [↑↓] Nav\n[PgUp/PgDn] Scroll | [Enter] Sel | [?] Keys | [Esc] Back | [Esc Esc] Quit",
+            _ => "[Tab] Change Focus | [F1] About | [F2] Prefs | [↑↓] Nav\n[PgUp/PgDn] Scroll | [Enter] Sel | [?] Keys | [Esc] Back"
         };
         let status_text = if let Some(user) = &app.auth.current_user {
             if user.role == UserRole::Admin {
@@ -80,12 +108,30 @@ pub fn ui(f: &mut Frame, app: &mut App) {
             footer_chunks[0],
         );
         
-        // Render status text right-aligned
+        // Render status text right-aligned, with a warning when running without TLS
+        let mut status_spans = Vec::new();
+        if app.ui.insecure_connection {
+            status_spans.push(ratatui::text::Span::styled(
+                "⚠ INSECURE (no TLS) ",
+                ratatui::style::Style::default().fg(ratatui::style::Color::Red).add_modifier(ratatui::style::Modifier::BOLD),
+            ));
+        }
+        if app.prefs.dnd_enabled {
+            let label = match app.dnd_remaining() {
+                Some(left) => format!("🌙 DND {} ", left),
+                None => "🌙 DND ".to_string(),
+            };
+            status_spans.push(ratatui::text::Span::styled(
+                label,
+                ratatui::style::Style::default().fg(ratatui::style::Color::Magenta).add_modifier(ratatui::style::Modifier::BOLD),
+            ));
+        }
+        status_spans.push(ratatui::text::Span::styled(
+            status_text,
+            ratatui::style::Style::default().fg(ratatui::style::Color::Yellow)
+        ));
         f.render_widget(
-            ratatui::widgets::Paragraph::new(ratatui::text::Span::styled(
-                status_text,
-                ratatui::style::Style::default().fg(ratatui::style::Color::Yellow)
-            ))
+            ratatui::widgets::Paragraph::new(ratatui::text::Line::from(status_spans))
                 .alignment(ratatui::layout::Alignment::Right)
                 .block(ratatui::widgets::Block::default().borders(ratatui::widgets::Borders::TOP)),
             footer_chunks[1],
@@ -93,36 +139,20 @@ pub fn ui(f: &mut Frame, app: &mut App) {
     }
 
     let main_area = chunks[1];
-    match app.ui.mode {
-        AppMode::Login => draw_login(f, app, main_area),
-        AppMode::Register => draw_register(f, app, main_area),
-        AppMode::MainMenu => draw_main_menu(f, app, main_area),
-        AppMode::Settings => draw_settings(f, app, main_area),
-        AppMode::ForumList => draw_forum_list(f, app, main_area),
-        AppMode::ThreadList => draw_thread_list(f, app, main_area),
-        AppMode::PostView => draw_post_view(f, app, main_area),
-        AppMode::Chat => draw_chat(f, app, main_area),
-        AppMode::Input => {
-            let underlying_mode = match app.auth.input_mode {
-                Some(InputMode::NewForumName) | Some(InputMode::NewForumDescription) => Some(AppMode::ForumList),
-                Some(InputMode::NewThreadTitle) | Some(InputMode::NewThreadContent) => Some(AppMode::ForumList),
-                Some(InputMode::NewPostContent) => Some(AppMode::PostView),
-                Some(InputMode::UpdatePassword) => Some(AppMode::Settings),
-                _ => None,
-            };
-            if let Some(mode) = underlying_mode {
-                match mode {
-                    AppMode::ForumList => draw_forum_list(f, app, main_area),
-                    AppMode::PostView => draw_post_view(f, app, main_area),
-                    AppMode::Settings => draw_settings(f, app, main_area),
-                    _ => {}
-                }
-            }
-            draw_input_popup(f, app);
-        }
-        AppMode::EditProfile => draw_profile_edit_page(f, app, main_area),
-        AppMode::ColorPicker => draw_color_picker(f, app, main_area),
-        AppMode::Preferences => crate::ui::settings::draw_preferences(f, app, main_area),
+    if app.ui.mode == AppMode::Input {
+        // Draw the screen the input was opened from behind the popup, falling back
+        // to a screen that fits the input mode, then to the main menu
+        let underlying_mode = app.ui.input_return_mode.unwrap_or(match app.auth.input_mode {
+            Some(InputMode::NewForumName) | Some(InputMode::NewForumDescription) => AppMode::ForumList,
+            Some(InputMode::NewThreadTitle) | Some(InputMode::NewThreadContent) => AppMode::ForumList,
+            Some(InputMode::NewPostContent) => AppMode::PostView,
+            Some(InputMode::UpdatePassword) => AppMode::Settings,
+            _ => AppMode::MainMenu,
+        });
+        draw_screen(f, app, underlying_mode, main_area);
+        draw_input_popup(f, app);
+    } else {
+        draw_screen(f, app, app.ui.mode, main_area);
     }
 
     if let Some((notification, _, minimal)) = &app.notifications.current_notification {
@@ -146,12 +176,23 @@ pub fn ui(f: &mut Frame, app: &mut App) {
     if app.ui.show_server_invite_selection {
         draw_server_invite_selection_popup(f, app);
     }
+    if app.ui.show_about {
+        crate::ui::popups::draw_about_popup(f, app);
+    }
+    if app.ui.show_shortcuts {
+        crate::ui::shortcuts::draw_shortcuts_popup(f, app);
+    }
+    if app.ui.show_debug_overlay {
+        crate::ui::popups::draw_debug_overlay(f, app);
+    }
     if app.ui.show_quit_confirm {
         crate::ui::popups::draw_quit_confirm_popup(f, app);
         return;
     }
     if app.ui.show_server_error {
         draw_cyberpunk_server_error_popup(f, app);
-        return;
+    }
+    if let Some(pending) = &app.ui.trust_prompt {
+        crate::ui::popups::draw_trust_prompt_popup(f, pending, app.ui.trust_prompt_selected);
     }
 }
@@ -0,0 +1,67 @@
+//! Notification center: the stored notification history.
+
+use ratatui::{Frame, layout::Rect, style::{Style, Color, Modifier}, text::{Line, Span}, widgets::{Block, Borders, List, ListItem, Paragraph}};
+use chrono::Local;
+use nexus_tui_common::NotificationType;
+use crate::app::App;
+use crate::ui::time_format::format_message_timestamp;
+
+fn type_label(kind: &NotificationType) -> &str {
+    match kind {
+        NotificationType::ThreadReply => "Reply",
+        NotificationType::DM => "DM",
+        NotificationType::Announcement => "Announcement",
+        NotificationType::Mention => "Mention",
+        NotificationType::Other(label) => label,
+    }
+}
+
+pub fn draw_notification_center(f: &mut Frame, app: &mut App, area: Rect) {
+    let title = format!(
+        "Notifications ({} unread) | [Enter] Open | [A] Mark All Read | [R]efresh | [Esc] Back",
+        app.notifications.unread_count()
+    );
+    let block = Block::default().borders(Borders::ALL).title(title);
+
+    if app.notifications.notifications.is_empty() {
+        let text = if app.notifications.notification_history_complete { "No notifications." } else { "Loading notifications..." };
+        f.render_widget(
+            Paragraph::new(text).style(Style::default().fg(Color::DarkGray)).block(block),
+            area,
+        );
+        return;
+    }
+
+    let now = Local::now();
+    let time_width = 20;
+    let mut items: Vec<ListItem> = app.notifications.notifications.iter().map(|n| {
+        let (marker, text_style) = if n.read {
+            ("  ", Style::default().fg(Color::Gray))
+        } else {
+            ("● ", Style::default().fg(Color::White).add_modifier(Modifier::BOLD))
+        };
+        let time = format_message_timestamp(n.created_at, now, app.prefs.clock_format());
+        ListItem::new(Line::from(vec![
+            Span::styled(marker, Style::default().fg(Color::Cyan)),
+            Span::styled(format!("{:<width$}", time, width = time_width), Style::default().fg(Color::DarkGray)),
+            Span::styled(format!("{:<14}", type_label(&n.notif_type)), Style::default().fg(Color::Yellow)),
+            Span::styled(n.extra.clone().unwrap_or_default(), text_style),
+        ]))
+    }).collect();
+
+    // Where the history stands, below the last notification
+    let footer = if app.notifications.fetching_older {
+        "   Loading older notifications..."
+    } else if app.notifications.notification_history_complete {
+        "   ── End of notifications ──"
+    } else {
+        "   ▼ More below (scroll down to load)"
+    };
+    items.push(ListItem::new(Span::styled(footer, Style::default().fg(Color::DarkGray))));
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(Style::default().bg(Color::Cyan).fg(Color::Black).add_modifier(Modifier::BOLD))
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(list, area, &mut app.notifications.list_state);
+}
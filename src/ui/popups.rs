@@ -28,7 +28,7 @@ pub fn draw_dm_input_popup(f: &mut Frame, app: &App) {
     
     // Simple estimation for height calculation
     let estimated_lines = if input_inner_width > 0 && !input_str.is_empty() {
-        let char_lines = (input_str.len() as u16 + input_inner_width - 1) / input_inner_width;
+        let char_lines = (input_str.chars().count() as u16 + input_inner_width - 1) / input_inner_width;
         let newline_count = input_str.matches('\n').count() as u16;
         (char_lines + newline_count).max(1)
     } else {
@@ -56,13 +56,12 @@ pub fn draw_dm_input_popup(f: &mut Frame, app: &App) {
     // Calculate cursor position for multiline input
     let inner_area = Block::default().borders(Borders::ALL).inner(area);
     if inner_area.width > 0 && !app.chat.dm_input.is_empty() {
-        let cursor_pos = app.chat.dm_input.len();
-        let text_up_to_cursor = &app.chat.dm_input[..cursor_pos];
+        let text_up_to_cursor = app.chat.dm_input.as_str();
         
         // Count newlines and estimate position
         let newlines = text_up_to_cursor.matches('\n').count() as u16;
         let last_line = text_up_to_cursor.split('\n').last().unwrap_or("");
-        let col_in_line = last_line.len() as u16;
+        let col_in_line = last_line.chars().count() as u16;
         let estimated_col = col_in_line % inner_area.width;
         let estimated_line = newlines + (col_in_line / inner_area.width);
         
@@ -83,19 +82,30 @@ pub fn draw_dm_input_popup(f: &mut Frame, app: &App) {
 }
 
 pub fn draw_input_popup(f: &mut Frame, app: &App) {
-    let title = match app.auth.input_mode {
-        Some(crate::state::InputMode::NewForumName) => "New Forum Name",
-        Some(crate::state::InputMode::NewForumDescription) => "New Forum Description",
-        Some(crate::state::InputMode::NewThreadTitle) => "New Thread Title",
-        Some(crate::state::InputMode::NewThreadContent) => "New Thread Content",
-        Some(crate::state::InputMode::NewPostContent) => "Reply Content",
-        Some(crate::state::InputMode::UpdatePassword) => "New Password",
-        _ => "Input"
+    let reply_parent = app.forum.reply_to_post_id.and_then(|id| app.forum.get_post(id));
+    let title = match (&app.auth.input_mode, reply_parent) {
+        (Some(crate::state::InputMode::NewPostContent), Some(parent)) => {
+            format!(
+                "Reply to {}: \"{}\"",
+                parent.author.username,
+                crate::services::MessageService::truncate_with_ellipsis(&parent.content.split_whitespace().collect::<Vec<_>>().join(" "), 40)
+            )
+        }
+        (mode, _) => match mode {
+            Some(crate::state::InputMode::NewForumName) => "New Forum Name",
+            Some(crate::state::InputMode::NewForumDescription) => "New Forum Description",
+            Some(crate::state::InputMode::NewThreadTitle) => "New Thread Title",
+            Some(crate::state::InputMode::NewThreadContent) => "New Thread Content",
+            Some(crate::state::InputMode::NewPostContent) => "Reply Content",
+            Some(crate::state::InputMode::UpdatePassword) => "New Password",
+            Some(crate::state::InputMode::SearchMessages) => "Search Messages",
+            _ => "Input"
+        }.to_string(),
     };
     
     // Calculate popup size based on content
     let input_str = if matches!(app.auth.input_mode, Some(crate::state::InputMode::UpdatePassword)) {
-        "*".repeat(app.auth.current_input.len())
+        "*".repeat(app.auth.current_input.chars().count())
     } else { 
         app.auth.current_input.clone() 
     };
@@ -105,7 +115,7 @@ pub fn draw_input_popup(f: &mut Frame, app: &App) {
     
     // Simple estimation for height calculation 
     let estimated_lines = if input_inner_width > 0 && !input_str.is_empty() {
-        let char_lines = (input_str.len() as u16 + input_inner_width - 1) / input_inner_width;
+        let char_lines = (input_str.chars().count() as u16 + input_inner_width - 1) / input_inner_width;
         let newline_count = input_str.matches('\n').count() as u16;
         (char_lines + newline_count).max(1)
     } else {
@@ -129,17 +139,16 @@ pub fn draw_input_popup(f: &mut Frame, app: &App) {
     // Calculate cursor position for multiline input
     let inner_area = Block::default().borders(Borders::ALL).inner(area);
     if inner_area.width > 0 && !app.auth.current_input.is_empty() {
-        let cursor_pos = app.auth.current_input.len();
         let display_text = if matches!(app.auth.input_mode, Some(crate::state::InputMode::UpdatePassword)) {
-            "*".repeat(cursor_pos)
+            "*".repeat(app.auth.current_input.chars().count())
         } else {
-            app.auth.current_input[..cursor_pos].to_string()
+            app.auth.current_input.clone()
         };
         
         // Count newlines and estimate position
         let newlines = display_text.matches('\n').count() as u16;
         let last_line = display_text.split('\n').last().unwrap_or("");
-        let col_in_line = last_line.len() as u16;
+        let col_in_line = last_line.chars().count() as u16;
         let estimated_col = col_in_line % inner_area.width;
         let estimated_line = newlines + (col_in_line / inner_area.width);
         
@@ -176,7 +185,7 @@ pub fn draw_notification_popup(f: &mut Frame, text: String) {
 
 pub fn draw_minimal_notification_popup(f: &mut Frame, text: String) {
     let size = f.area();
-    let width = 30u16.max(text.len() as u16 + 2).min(size.width / 2);
+    let width = 30u16.max(text.chars().count() as u16 + 2).min(size.width / 2);
     let height = 3u16;
     let x = size.x + size.width - width - 2;
     let y = size.y + 1;
@@ -245,7 +254,7 @@ pub fn draw_profile_view_popup(f: &mut Frame, app: &mut App, profile: &nexus_tui
             }
             lines.push(Line::from(vec![Span::styled("Bio: ", Style::default().fg(Color::Cyan))]));
             for line in bio_lines {
-                lines.push(Line::from(Span::raw(line)));
+                lines.push(Line::from(crate::ui::chat::linkify(line)));
             }
             lines.push(Line::from("")); // Add spacing
         }
@@ -263,8 +272,8 @@ pub fn draw_profile_view_popup(f: &mut Frame, app: &mut App, profile: &nexus_tui
     if let Some(url1) = &profile.url1 { 
         if !url1.is_empty() { 
             lines.push(Line::from(vec![
-                Span::styled("🔗 URL1: ", Style::default().fg(Color::Cyan)), 
-                Span::raw(url1)
+                Span::styled("🔗 URL1 [1]: ", Style::default().fg(Color::Cyan)), 
+                Span::styled(url1, crate::ui::chat::link_style())
             ])); 
         } 
     }
@@ -272,8 +281,8 @@ pub fn draw_profile_view_popup(f: &mut Frame, app: &mut App, profile: &nexus_tui
     if let Some(url2) = &profile.url2 { 
         if !url2.is_empty() { 
             lines.push(Line::from(vec![
-                Span::styled("🔗 URL2: ", Style::default().fg(Color::Cyan)), 
-                Span::raw(url2)
+                Span::styled("🔗 URL2 [2]: ", Style::default().fg(Color::Cyan)), 
+                Span::styled(url2, crate::ui::chat::link_style())
             ])); 
         } 
     }
@@ -281,8 +290,8 @@ pub fn draw_profile_view_popup(f: &mut Frame, app: &mut App, profile: &nexus_tui
     if let Some(url3) = &profile.url3 { 
         if !url3.is_empty() { 
             lines.push(Line::from(vec![
-                Span::styled("🔗 URL3: ", Style::default().fg(Color::Cyan)), 
-                Span::raw(url3)
+                Span::styled("🔗 URL3 [3]: ", Style::default().fg(Color::Cyan)), 
+                Span::styled(url3, crate::ui::chat::link_style())
             ])); 
         } 
     }
@@ -298,9 +307,62 @@ pub fn draw_profile_view_popup(f: &mut Frame, app: &mut App, profile: &nexus_tui
     f.render_widget(content, content_area);
 }
 
-pub fn draw_user_actions_popup(f: &mut Frame, app: &App) {
+/// Compact profile preview shown next to a hovered user, without fetching the full profile.
+pub fn draw_profile_tooltip(f: &mut Frame, user: &nexus_tui_common::User, anchor: Rect) {
+    use nexus_tui_common::UserStatus;
+    let (status_symbol, status_label, status_color) = match user.status {
+        UserStatus::Connected => ("●", "Online", Color::Green),
+        UserStatus::Away => ("◐", "Away", Color::Yellow),
+        UserStatus::Busy => ("■", "Busy", Color::Red),
+        UserStatus::Offline => ("○", "Offline", Color::DarkGray),
+    };
+    let lines = vec![
+        Line::from(Span::styled(user.username.as_str(), Style::default().fg(user.color.clone().into()).add_modifier(Modifier::BOLD))),
+        Line::from(vec![
+            Span::styled("Role: ", Style::default().fg(Color::Cyan)),
+            Span::styled(format!("{:?}", user.role), Style::default().fg(Color::Yellow)),
+        ]),
+        Line::from(vec![
+            Span::styled("Status: ", Style::default().fg(Color::Cyan)),
+            Span::styled(format!("{} {}", status_symbol, status_label), Style::default().fg(status_color)),
+        ]),
+    ];
+    let content_width = user.username.chars().count().max(16) as u16;
+    let screen = f.area();
+    let width = (content_width + 2).min(screen.width);
+    let height = (lines.len() as u16 + 2).min(screen.height);
+    // Prefer the left side of the anchor (the user list sits on the right edge)
+    let x = if anchor.x >= screen.x + width {
+        anchor.x - width
+    } else {
+        (anchor.x + anchor.width).min(screen.x + screen.width - width)
+    };
+    let y = anchor.y.min((screen.y + screen.height).saturating_sub(height));
+    let area = Rect { x, y, width, height };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Color::Cyan));
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// Remember the popup's one-line entries (inside its border, after `skip` lines) for mouse clicks
+fn record_popup_rows(app: &mut App, area: Rect, skip: u16, count: usize, click: fn(usize) -> crate::state::ui::PopupClick) {
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    for i in 0..count {
+        let y = inner.y + skip + i as u16;
+        if y >= inner.y + inner.height {
+            break;
+        }
+        app.ui.click_map.popup_rows.push((Rect::new(inner.x, y, inner.width, 1), click(i)));
+    }
+}
+
+pub fn draw_user_actions_popup(f: &mut Frame, app: &mut App) {
     let area = draw_centered_rect(f.area(), 40, 20);
     f.render_widget(Clear, area);
+    record_popup_rows(app, area, 0, 3, crate::state::ui::PopupClick::UserAction);
     let user = app.profile.user_actions_target.and_then(|idx| app.chat.channel_userlist.get(idx));
     let username = user.map(|u| u.username.as_str()).unwrap_or("<unknown>");
     let actions = ["Show Profile", "Send DM", "Invite to Server"];
@@ -325,23 +387,17 @@ pub fn draw_server_actions_popup(f: &mut Frame, app: &App) {
     let area = draw_centered_rect(f.area(), 40, 20);
     f.render_widget(Clear, area);
     let server_name = app.chat.selected_server.and_then(|s| app.chat.servers.get(s)).map(|srv| srv.name.as_str()).unwrap_or("<server>");
-    let is_owner = app.chat.selected_server
-        .and_then(|s| app.chat.servers.get(s))
-        .and_then(|srv| app.auth.current_user.as_ref().map(|u| u.id == srv.owner))
-        .unwrap_or(false);
-    let mut actions = vec!["View full user list", "Send invite code"];
-    if is_owner {
-        actions.push("Server settings");
-    }
     let mut lines = vec![];
-    for (i, action) in actions.iter().enumerate() {
+    for (i, action) in app.server_actions().iter().enumerate() {
         let style = if app.ui.server_actions_selected == i {
             Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
         } else {
             Style::default()
         };
-        lines.push(Line::from(Span::styled(*action, style)));
+        lines.push(Line::from(Span::styled(action.label(), style)));
     }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("[Enter] Select | [Esc] Close", Style::default().fg(Color::DarkGray))));
     let block = Block::default()
         .title(Span::styled(server_name, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)))
         .style(Style::default())
@@ -350,7 +406,55 @@ pub fn draw_server_actions_popup(f: &mut Frame, app: &App) {
     f.render_widget(para, area);
 }
 
-pub fn draw_quit_confirm_popup(f: &mut Frame, app: &App) {
+/// Client, protocol and server version details for bug reports.
+pub fn draw_about_popup(f: &mut Frame, app: &App) {
+    let label = Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD);
+    let row = |name: &'static str, value: String| Line::from(vec![
+        Span::styled(format!("{:<10}", name), label),
+        Span::raw(value),
+    ]);
+    let transport = match (app.ui.insecure_connection, &app.ui.proxy_address) {
+        (true, None) => "Plain TCP (insecure)".to_string(),
+        (false, None) => "TLS".to_string(),
+        (true, Some(proxy)) => format!("Plain TCP (insecure) via proxy {}", proxy),
+        (false, Some(proxy)) => format!("TLS via proxy {}", proxy),
+    };
+    let images = format!(
+        "{:?} ({})",
+        app.profile.picker.protocol_type(),
+        if app.profile.protocol_forced { "forced" } else { "auto-detected" },
+    );
+    let lines = vec![
+        Line::from(Span::styled("Nexus TUI Client", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
+        Line::from(""),
+        row("Client", format!("v{}", env!("CARGO_PKG_VERSION"))),
+        row("Protocol", "nexus-tui-common 0.1".to_string()),
+        row("Server", app.ui.server_address.clone()),
+        row("Version", app.ui.server_version.clone().unwrap_or_else(|| "not reported".to_string())),
+        row("Transport", transport),
+        row("Images", images),
+        row("Status", if app.ui.show_server_error { "Disconnected".to_string() } else { "Connected".to_string() }),
+        Line::from(""),
+        Line::from(Span::styled("Press any key to close", Style::default().fg(Color::DarkGray))),
+    ];
+    let screen = f.area();
+    let width = 60.min(screen.width);
+    let height = (lines.len() as u16 + 2).min(screen.height);
+    let area = Rect::new(
+        screen.x + (screen.width - width) / 2,
+        screen.y + (screen.height - height) / 2,
+        width,
+        height,
+    );
+    let block = Block::default()
+        .title("About")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double);
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block).wrap(Wrap { trim: true }), area);
+}
+
+pub fn draw_quit_confirm_popup(f: &mut Frame, app: &mut App) {
     // Try to ensure the popup is tall enough for all content (message + buttons + paddings)
     let mut percent_y = 18u16;
     let percent_x = 40u16;
@@ -397,6 +501,14 @@ pub fn draw_quit_confirm_popup(f: &mut Frame, app: &App) {
         Span::styled("[ No ]", no_style),
     ];
     lines.push(Line::from(buttons));
+    // Button positions for mouse clicks: "[ Yes ]  [ No ]" centered on its line
+    let inner = Block::default().borders(Borders::ALL).inner(area);
+    let button_y = inner.y + pad_top + pad_above_msg + 1 + pad_between_msg_btn;
+    let buttons_x = inner.x + inner.width.saturating_sub(15) / 2;
+    if button_y < inner.y + inner.height {
+        app.ui.click_map.popup_rows.push((Rect::new(buttons_x, button_y, 7, 1), crate::state::ui::PopupClick::QuitYes));
+        app.ui.click_map.popup_rows.push((Rect::new(buttons_x + 9, button_y, 6, 1), crate::state::ui::PopupClick::QuitNo));
+    }
     for _ in 0..pad_below_btn { lines.push(Line::from("")); }
     for _ in 0..pad_bottom { lines.push(Line::from("")); }
     let para = Paragraph::new(lines)
@@ -407,9 +519,12 @@ pub fn draw_quit_confirm_popup(f: &mut Frame, app: &App) {
     f.render_widget(para, area);
 }
 
-pub fn draw_server_invite_selection_popup(f: &mut Frame, app: &App) {
+pub fn draw_server_invite_selection_popup(f: &mut Frame, app: &mut App) {
     let area = draw_centered_rect(f.area(), 50, 30);
     f.render_widget(Clear, area);
+    // Servers are listed below a heading and a blank line
+    let servers = app.ui.server_invite_target_user.map(|uid| app.invite_servers(uid)).unwrap_or_default();
+    record_popup_rows(app, area, 2, servers.len(), crate::state::ui::PopupClick::ServerInvite);
     
     let user = app.ui.server_invite_target_user
         .and_then(|uid| app.chat.channel_userlist.iter().find(|u| u.id == uid));
@@ -423,14 +538,22 @@ pub fn draw_server_invite_selection_popup(f: &mut Frame, app: &App) {
     ]));
     lines.push(Line::from(""));
     
-    for (i, server) in app.chat.servers.iter().enumerate() {
-        let style = if app.ui.server_invite_selected == i {
+    let me = app.auth.current_user.as_ref().map(|u| u.id);
+    for (row, &i) in servers.iter().enumerate() {
+        let server = &app.chat.servers[i];
+        let style = if app.ui.server_invite_selected == row {
             Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
         } else {
             Style::default()
         };
-        lines.push(Line::from(Span::styled(&server.name, style)));
+        let mut spans = vec![Span::styled(server.name.clone(), style)];
+        if me == Some(server.owner) {
+            spans.push(Span::styled(" (owner)", Style::default().fg(Color::DarkGray)));
+        }
+        lines.push(Line::from(spans));
     }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("[Enter] Send Invite | [Esc] Cancel", Style::default().fg(Color::DarkGray))));
     
     let block = Block::default()
         .title("Invite to Server")
@@ -440,6 +563,71 @@ pub fn draw_server_invite_selection_popup(f: &mut Frame, app: &App) {
     f.render_widget(para, area);
 }
 
+/// " of N" when automatic reconnects are capped
+fn attempts_of(app: &App) -> String {
+    match app.prefs.reconnect_max_attempts {
+        0 => String::new(),
+        max => format!(" of {}", max),
+    }
+}
+
+/// Ask whether to trust a server certificate that no known root vouches for
+pub fn draw_trust_prompt_popup(f: &mut Frame, pending: &crate::known_hosts::PendingTrust, selected: usize) {
+    let area = draw_centered_rect(f.area(), 70, 45);
+    let changed = pending.previous.is_some();
+    let (title, color) = if changed {
+        (" CERTIFICATE CHANGED ", Color::Red)
+    } else {
+        (" Untrusted Certificate ", Color::Yellow)
+    };
+    let mut lines = vec![Line::from("")];
+    if changed {
+        lines.push(Line::from(Span::styled(
+            format!("The certificate of {} is not the one you trusted before!", pending.host),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )));
+        lines.push(Line::from("Someone may be intercepting the connection, or the server got a new certificate."));
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("Previously trusted:", Style::default().fg(Color::DarkGray))));
+        lines.push(Line::from(pending.previous.clone().unwrap_or_default()));
+    } else {
+        lines.push(Line::from(Span::styled(
+            format!("{} presented a certificate that isn't signed by a trusted authority.", pending.host),
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+        lines.push(Line::from("Check the fingerprint with the server's operator before trusting it."));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled("SHA-256 fingerprint:", Style::default().fg(Color::DarkGray))));
+    lines.push(Line::from(Span::styled(pending.fingerprint.clone(), Style::default().fg(Color::Cyan))));
+    lines.push(Line::from(""));
+    let trust_style = if selected == 0 {
+        Style::default().fg(Color::Black).bg(Color::Green).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::Green)
+    };
+    let reject_style = if selected == 1 {
+        Style::default().fg(Color::Black).bg(Color::Red).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::Red)
+    };
+    lines.push(Line::from(vec![
+        Span::styled("[ Trust (Y) ]", trust_style),
+        Span::raw("  "),
+        Span::styled("[ Reject (N) ]", reject_style),
+    ]));
+    let block = Block::default()
+        .title(Span::styled(title, Style::default().fg(color).add_modifier(Modifier::BOLD)))
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(color));
+    f.render_widget(Clear, area);
+    f.render_widget(
+        Paragraph::new(lines).block(block).alignment(Alignment::Center).wrap(Wrap { trim: true }),
+        area,
+    );
+}
+
 /// Draw a cyberpunk-themed server error popup with glitch effects
 pub fn draw_cyberpunk_server_error_popup(f: &mut Frame, app: &App) {
     let area = draw_centered_rect(f.area(), 70, 60);
@@ -549,6 +737,31 @@ pub fn draw_cyberpunk_server_error_popup(f: &mut Frame, app: &App) {
     lines.push(Line::from(""));
     lines.push(Line::from(""));
     
+    // Attempt in progress, or the automatic reconnect countdown
+    if app.ui.connecting {
+        lines.push(Line::from(vec![
+            Span::styled("└─> ", Style::default().fg(Color::Yellow)),
+            Span::styled("Connecting...", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+        ]));
+    } else if let Some(next_tick) = app.ui.next_reconnect_tick {
+        let secs = next_tick.saturating_sub(tick) as f64 * 0.05;
+        lines.push(Line::from(vec![
+            Span::styled("└─> ", Style::default().fg(Color::Yellow)),
+            Span::styled(
+                format!("Reconnecting in {:.0}s (attempt {}{})", secs.ceil(), app.ui.reconnect_attempt, attempts_of(app)),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    } else if app.ui.auto_reconnect && app.ui.reconnect_attempt > 0 {
+        lines.push(Line::from(vec![
+            Span::styled("└─> ", Style::default().fg(Color::Yellow)),
+            Span::styled(
+                format!("Gave up after {} automatic attempts", app.ui.reconnect_attempt),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
+    
     // Instructions with cyberpunk styling
     lines.push(Line::from(vec![
         Span::styled("└─> ", Style::default().fg(Color::Green)),
@@ -578,3 +791,42 @@ pub fn draw_cyberpunk_server_error_popup(f: &mut Frame, app: &App) {
     
     f.render_widget(para, area);
 }
+
+/// Corner panel with cache, frame-rate and server query numbers (F12).
+pub fn draw_debug_overlay(f: &mut Frame, app: &App) {
+    let screen = f.area();
+    let width = 36.min(screen.width);
+    let height = 11.min(screen.height);
+    let area = Rect::new(screen.x + screen.width - width, screen.y, width, height);
+    f.render_widget(Clear, area);
+
+    let label = Style::default().fg(Color::Gray);
+    let row = |name: &str, value: String| Line::from(vec![
+        Span::styled(format!("{:<13}", name), label),
+        Span::styled(value, Style::default().fg(Color::White)),
+    ]);
+    let unknown = || "—".to_string();
+    let mut lines = vec![row("FPS", format!("{} (cap {})", app.ui.fps, app.prefs.max_fps))];
+    match app.get_cache_stats() {
+        Some(stats) => {
+            lines.push(row("Images", format!("{} ({} expired)", stats.total_entries, stats.expired_entries)));
+            lines.push(row("Image MB", format!("{:.1}", stats.total_size_mb)));
+            lines.push(row("Hit ratio", format!("{:.0}% ({}/{})", stats.hit_ratio * 100.0, stats.hits, stats.hits + stats.misses)));
+        }
+        None => lines.push(row("Images", unknown())),
+    }
+    let metrics = &app.ui.server_metrics;
+    lines.push(row("Query time", metrics.query_time_ms.map_or_else(unknown, |ms| format!("{} ms", ms))));
+    lines.push(row("Query cache", metrics.query_cache_hit_rate.map_or_else(unknown, |rate| format!("{:.0}%", rate * 100.0))));
+    lines.push(row("Server cache", metrics.cache.map_or_else(unknown, |(entries, mb, ratio, _)| {
+        format!("{} / {:.1}MB / {:.0}%", entries, mb, ratio * 100.0)
+    })));
+    lines.push(row("Query rows", metrics.message_count.map_or_else(unknown, |count| count.to_string())));
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .border_style(Style::default().fg(Color::DarkGray))
+        .title("Debug [F12]");
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
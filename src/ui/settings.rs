@@ -3,7 +3,6 @@
 use ratatui::{Frame, layout::Rect, style::{Style, Color, Modifier}, widgets::{Block, List, ListItem, Paragraph, Borders, BorderType, Wrap}, text::{Line, Span}, layout::Constraint, layout::Layout};
 use ratatui::prelude::{Alignment, Direction};
 use crate::app::{App};
-use base64::Engine;
 use crate::ui::themes::Theme;
 
 pub fn draw_settings(f: &mut Frame, app: &mut App, area: Rect) {
@@ -331,9 +330,16 @@ pub fn draw_profile_edit_page(f: &mut Frame, app: &mut App, area: Rect) {
             let style = if app.profile.profile_edit_focus == url_focus[i] {
                 Style::default().fg(Color::Black).bg(Color::LightCyan).add_modifier(Modifier::BOLD)
             } else { Style::default().bg(Color::DarkGray) };
+            // Flag malformed URLs inline with a red border before the user hits Save
+            let is_valid = crate::services::ProfileService::is_url_field_valid(url_fields[i]);
+            let (title, border_style) = if is_valid {
+                (url_titles[i].to_string(), style)
+            } else {
+                (format!("{} (invalid URL)", url_titles[i]), style.fg(Color::Red).add_modifier(Modifier::BOLD))
+            };
             f.render_widget(
                 Paragraph::new(url_fields[i].clone())
-                    .block(Block::default().borders(Borders::ALL).title(url_titles[i]).border_style(style))
+                    .block(Block::default().borders(Borders::ALL).title(title).border_style(border_style))
                     .style(style),
                 left[6 + i * 2],
             );
@@ -343,31 +349,14 @@ pub fn draw_profile_edit_page(f: &mut Frame, app: &mut App, area: Rect) {
         // Section header
         f.render_widget(Paragraph::new(Span::styled("Profile Images", Style::default().fg(Color::LightMagenta).add_modifier(Modifier::BOLD))).alignment(Alignment::Left), right[0]);
         f.render_widget(Paragraph::new(Span::styled(
-            "(i) Image: local file path, under 1MB, no spaces.",
+            "(i) Image: local file path (type, paste or drop a file), under 1MB.",
             Style::default().fg(Color::DarkGray).add_modifier(Modifier::ITALIC)
         )).alignment(Alignment::Left), right[1]);
-        // Profile Pic preview
+        // Profile Pic preview (circular, like avatars)
         let pic_style = if app.profile.profile_edit_focus == ProfilePic {
             Style::default().fg(Color::Black).bg(Color::LightMagenta).add_modifier(Modifier::BOLD)
         } else { Style::default().bg(Color::DarkGray) };
-        if !app.profile.edit_profile_pic.trim().is_empty() {
-            let mut show_placeholder = true;
-            if let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(app.profile.edit_profile_pic.trim()) {
-                if let Ok(img) = image::load_from_memory(&bytes) {
-                    let mut protocol = app.profile.picker.new_resize_protocol(img);
-                    let image_widget = ratatui_image::StatefulImage::default().resize(ratatui_image::Resize::Fit(None));
-                    f.render_stateful_widget(image_widget, right[3], &mut protocol);
-                    show_placeholder = false;
-                }
-            }
-            if show_placeholder {
-                let preview_block = Block::default().borders(Borders::ALL).title("Profile Pic Preview").style(pic_style);
-                f.render_widget(preview_block, right[3]);
-            }
-        } else {
-            let preview_block = Block::default().borders(Borders::ALL).title("Profile Pic Preview").style(pic_style);
-            f.render_widget(preview_block, right[3]);
-        }
+        draw_image_preview(f, app, ProfilePic, "Profile Pic Preview", pic_style, right[3]);
         // Profile Pic field + delete
         let row = Layout::default()
             .direction(Direction::Horizontal)
@@ -386,28 +375,11 @@ pub fn draw_profile_edit_page(f: &mut Frame, app: &mut App, area: Rect) {
             Paragraph::new(Span::styled("[ Delete ]", del_style)).alignment(Alignment::Center),
             row[1],
         );
-        // Banner preview
+        // Banner preview (wide strip)
         let banner_style = if app.profile.profile_edit_focus == CoverBanner {
             Style::default().fg(Color::Black).bg(Color::LightMagenta).add_modifier(Modifier::BOLD)
         } else { Style::default().bg(Color::DarkGray) };
-        if !app.profile.edit_cover_banner.trim().is_empty() {
-            let mut show_placeholder = true;
-            if let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(app.profile.edit_cover_banner.trim()) {
-                if let Ok(img) = image::load_from_memory(&bytes) {
-                    let mut protocol = app.profile.picker.new_resize_protocol(img);
-                    let image_widget = ratatui_image::StatefulImage::default().resize(ratatui_image::Resize::Fit(None));
-                    f.render_stateful_widget(image_widget, right[7], &mut protocol);
-                    show_placeholder = false;
-                }
-            }
-            if show_placeholder {
-                let preview_block = Block::default().borders(Borders::ALL).title("Banner Preview").style(banner_style);
-                f.render_widget(preview_block, right[7]);
-            }
-        } else {
-            let preview_block = Block::default().borders(Borders::ALL).title("Banner Preview").style(banner_style);
-            f.render_widget(preview_block, right[7]);
-        }
+        draw_image_preview(f, app, CoverBanner, "Banner Preview", banner_style, right[7]);
         // Banner field + delete
         let row = Layout::default()
             .direction(Direction::Horizontal)
@@ -452,15 +424,15 @@ pub fn draw_profile_edit_page(f: &mut Frame, app: &mut App, area: Rect) {
             Bio => {
                 let lines: Vec<&str> = app.profile.edit_bio.split('\n').collect();
                 let y = left[2].y + lines.len() as u16 - 1 + 1;
-                let x = left[2].x + lines.last().map(|l| l.len()).unwrap_or(0) as u16 + 1;
+                let x = left[2].x + lines.last().map(|l| l.chars().count()).unwrap_or(0) as u16 + 1;
                 (x, y)
             },
-            Location => (left[4].x + app.profile.edit_location.len() as u16 + 1, left[4].y + 1),
-            Url1 => (left[6].x + app.profile.edit_url1.len() as u16 + 1, left[6].y + 1),
-            Url2 => (left[8].x + app.profile.edit_url2.len() as u16 + 1, left[8].y + 1),
-            Url3 => (left[10].x + app.profile.edit_url3.len() as u16 + 1, left[10].y + 1),
-            ProfilePic => (row[0].x + app.profile.edit_profile_pic.len() as u16 + 1, row[0].y + 1),
-            CoverBanner => (row[0].x + app.profile.edit_cover_banner.len() as u16 + 1, row[0].y + 1),
+            Location => (left[4].x + app.profile.edit_location.chars().count() as u16 + 1, left[4].y + 1),
+            Url1 => (left[6].x + app.profile.edit_url1.chars().count() as u16 + 1, left[6].y + 1),
+            Url2 => (left[8].x + app.profile.edit_url2.chars().count() as u16 + 1, left[8].y + 1),
+            Url3 => (left[10].x + app.profile.edit_url3.chars().count() as u16 + 1, left[10].y + 1),
+            ProfilePic => (row[0].x + app.profile.edit_profile_pic.chars().count() as u16 + 1, row[0].y + 1),
+            CoverBanner => (row[0].x + app.profile.edit_cover_banner.chars().count() as u16 + 1, row[0].y + 1),
             _ => (0, 0),
         };
         if matches!(app.profile.profile_edit_focus, Bio|Location|Url1|Url2|Url3|ProfilePic|CoverBanner) {
@@ -471,6 +443,35 @@ pub fn draw_profile_edit_page(f: &mut Frame, app: &mut App, area: Rect) {
         let preview_block = Block::default().borders(Borders::ALL).title("Profile Edit").style(Style::default());
         f.render_widget(preview_block, padded);
     }
+    if let Some(prompt) = &app.profile.downscale_prompt {
+        draw_downscale_prompt(f, prompt);
+    }
+}
+
+fn draw_downscale_prompt(f: &mut Frame, prompt: &crate::state::DownscalePrompt) {
+    let area = crate::ui::popups::draw_centered_rect(f.area(), 50, 30);
+    let lines = vec![
+        Line::from(Span::styled(
+            format!("The server rejected your {}:", prompt.field.label()),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(prompt.reason.as_str(), Style::default().fg(Color::Gray))),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw("Downscale to at most "),
+            Span::styled(format!("{}_", prompt.max_dimension), Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::raw(" px on the longest side?"),
+        ]),
+        Line::from(""),
+        Line::from(Span::styled("Enter: downscale   Esc: keep editing", Style::default().fg(Color::DarkGray))),
+    ];
+    let block = Block::default()
+        .title(" Image Too Large ")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double)
+        .border_style(Style::default().fg(Color::Yellow));
+    f.render_widget(ratatui::widgets::Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block).wrap(Wrap { trim: true }).alignment(Alignment::Center), area);
 }
 
 pub fn draw_color_picker(f: &mut Frame, app: &mut App, area: Rect) {
@@ -528,78 +529,152 @@ pub fn draw_color_picker(f: &mut Frame, app: &mut App, area: Rect) {
     }
 }
 
+/// Decode an image field (file path, data URL or base64) for the editor preview
+fn load_image_preview(app: &App, text: &str, circular: bool) -> Result<ratatui_image::protocol::StatefulProtocol, String> {
+    let text = text.trim();
+    if text.starts_with("http") {
+        return Err("URL (no preview)".to_string());
+    }
+    crate::services::ImageService::validate_image_data(text).map_err(|_| "invalid image".to_string())?;
+    let bytes = if std::path::Path::new(text).is_file() {
+        std::fs::read(text).map_err(|e| e.to_string())?
+    } else {
+        crate::services::ImageService::decode_image_bytes(&Some(text.to_string())).ok_or("invalid image")?
+    };
+    let img = image::load_from_memory(&bytes).map_err(|_| "invalid image".to_string())?;
+    let img = if circular {
+        // Center square crop with the same mask as chat avatars
+        let side = img.width().min(img.height());
+        let mut square = img.crop_imm((img.width() - side) / 2, (img.height() - side) / 2, side, side).to_rgba8();
+        crate::ui::avatar::apply_circular_mask(&mut square);
+        image::DynamicImage::ImageRgba8(square)
+    } else {
+        img
+    };
+    Ok(app.profile.picker.new_resize_protocol(img))
+}
+
+/// Preview of the profile picture or banner field, decoded again only when the field changes
+fn draw_image_preview(f: &mut Frame, app: &mut App, field: crate::state::ProfileEditFocus, title: &str, style: Style, area: Rect) {
+    let is_pic = field == crate::state::ProfileEditFocus::ProfilePic;
+    let text = if is_pic { app.profile.edit_profile_pic.clone() } else { app.profile.edit_cover_banner.clone() };
+    let block = Block::default().borders(Borders::ALL).title(title).style(style);
+    if text.trim().is_empty() {
+        f.render_widget(block, area);
+        return;
+    }
+    let cached = if is_pic { &app.profile.edit_pic_preview } else { &app.profile.edit_banner_preview };
+    if cached.as_ref().map(|(source, _)| source) != Some(&text) {
+        let preview = load_image_preview(app, &text, is_pic);
+        let slot = if is_pic { &mut app.profile.edit_pic_preview } else { &mut app.profile.edit_banner_preview };
+        *slot = Some((text, preview));
+    }
+    let slot = if is_pic { &mut app.profile.edit_pic_preview } else { &mut app.profile.edit_banner_preview };
+    match slot.as_mut().map(|(_, preview)| preview) {
+        Some(Ok(protocol)) => {
+            let image_widget = ratatui_image::StatefulImage::default().resize(ratatui_image::Resize::Fit(None));
+            f.render_stateful_widget(image_widget, area, protocol);
+        }
+        Some(Err(note)) => {
+            let color = if note.starts_with("URL") { Color::Gray } else { Color::Red };
+            let inner = block.inner(area);
+            f.render_widget(block, area);
+            f.render_widget(
+                Paragraph::new(note.as_str()).style(Style::default().fg(color).add_modifier(Modifier::BOLD)).alignment(Alignment::Center),
+                inner,
+            );
+        }
+        None => f.render_widget(block, area),
+    }
+}
+
+/// "🔉 Volume: ▮▮▮▮▮▮▮▮▯▯ 80%", or the level kept while muted
+fn volume_label(volume: u8, muted: bool) -> String {
+    let filled = (volume as usize + 5) / 10;
+    let bar = format!("{}{}", "▮".repeat(filled), "▯".repeat(10 - filled));
+    if muted {
+        format!("🔇 Volume: {} Muted ({}%)", bar, volume)
+    } else {
+        format!("🔉 Volume: {} {}%", bar, volume)
+    }
+}
+
 pub fn draw_preferences(f: &mut Frame, app: &mut App, area: Rect) {
     let prefs = &app.prefs;
+    let on_off = |enabled: bool| if enabled { "ON" } else { "OFF" };
     
     let block = Block::default().borders(Borders::ALL).title("Preferences");
     f.render_widget(&block, area);
     let inner = block.inner(area);
     
-    // Create layout for preferences items
+    let items = [
+        format!("🔊 Sound Effects: {}", on_off(prefs.sound_effects_enabled)),
+        format!("✨ Glitch Effects: {}", on_off(prefs.minimal_banner_glitch_enabled)),
+        format!("🔔 Desktop Notifications: {}", on_off(prefs.desktop_notifications_enabled)),
+        format!("👥 User List Sort: {}", prefs.user_list_sort.label()),
+        format!("💤 Offline Users: {}", prefs.offline_users.label()),
+        format!("🔁 Auto-Reconnect on Startup: {}", on_off(prefs.auto_reconnect_on_startup)),
+        format!("🎯 Focus on Entering Chat: {}", prefs.chat_entry_focus.label()),
+        format!("😀 Wide Emoji (2 cells): {}", on_off(prefs.wide_emoji)),
+        format!("📏 Message Density: {}", prefs.message_density.label()),
+        format!("🌊 Smooth Scrolling: {}", on_off(prefs.smooth_scrolling)),
+        format!("🔃 Reverse Scroll Direction: {}", on_off(prefs.reverse_scroll)),
+        format!("🖱 Mouse Support: {}", on_off(prefs.mouse_support)),
+        format!("🖼 Image Protocol: {}", prefs.image_protocol.label()),
+        format!("🔋 Pause Background When Idle/On Battery: {}", on_off(prefs.pause_background_when_idle)),
+        format!("🕒 Timestamp Position: {}", prefs.timestamp_position.label()),
+        format!("⎋ Esc Twice on Main Menu to Quit: {}", on_off(prefs.double_esc_quit)),
+        format!("👤 Remember Username: {}", on_off(prefs.remember_username)),
+        volume_label(prefs.sound_volume, prefs.sound_muted),
+        match prefs.mark_read_mode {
+            crate::global_prefs::MarkReadMode::AfterDelay => format!("✅ Mark Conversations Read: After {}s Visible", prefs.mark_read_delay_secs),
+            mode => format!("✅ Mark Conversations Read: {}", mode.label()),
+        },
+        match (prefs.dnd_enabled, app.dnd_remaining()) {
+            (true, Some(left)) => format!("🌙 Do Not Disturb: ON ({} left)", left),
+            (enabled, _) => format!("🌙 Do Not Disturb: {}", on_off(enabled)),
+        },
+        format!("🎞 Max Frame Rate: {} FPS", prefs.max_fps),
+        format!("🖼 Avatar Size: {}px", prefs.avatar_pixel_size),
+        format!("○ Text-Only Avatars: {}", on_off(prefs.text_only_avatars)),
+        match prefs.time_format.as_deref() {
+            Some(custom) if prefs.clock_format() == custom => format!("🕐 Clock: Custom ({})", custom),
+            _ => format!("🕐 Clock: {}", if prefs.time_format_24h { "24-hour" } else { "12-hour" }),
+        },
+        "🗑 Clear Image Cache (memory and disk)".to_string(),
+    ];
+    
+    // One 3-row box per preference, with the help text in the remaining space;
+    // plain one-line rows when the boxes don't fit
+    let boxed = inner.height as usize >= items.len() * 3 + 3;
+    let row_height = if boxed { 3 } else { 1 };
+    let mut constraints: Vec<Constraint> = items.iter().map(|_| Constraint::Length(row_height)).collect();
+    constraints.push(Constraint::Min(0));
     let items_layout = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(3), // Sound Effects
-            Constraint::Length(3), // Glitch Effects
-            Constraint::Length(3), // Desktop Notifications
-            Constraint::Min(0),    // Remaining space
-        ])
+        .constraints(constraints)
         .split(inner);
     
-    // Sound Effects preference
-    let sound_status = if prefs.sound_effects_enabled { "ON" } else { "OFF" };
-    let sound_style = if app.ui.preferences_selected == 0 {
-        Style::default().fg(Color::Black).bg(Color::LightCyan).add_modifier(Modifier::BOLD)
-    } else {
-        Style::default().fg(Color::White)
-    };
-    
-    f.render_widget(
-        Paragraph::new(format!("🔊 Sound Effects: {}", sound_status))
-            .style(sound_style)
-            .block(Block::default().borders(Borders::ALL))
-            .alignment(Alignment::Center),
-        items_layout[0],
-    );
-    
-    // Glitch Effects preference
-    let glitch_status = if prefs.minimal_banner_glitch_enabled { "ON" } else { "OFF" };
-    let glitch_style = if app.ui.preferences_selected == 1 {
-        Style::default().fg(Color::Black).bg(Color::LightCyan).add_modifier(Modifier::BOLD)
-    } else {
-        Style::default().fg(Color::White)
-    };
-    
-    f.render_widget(
-        Paragraph::new(format!("✨ Glitch Effects: {}", glitch_status))
-            .style(glitch_style)
-            .block(Block::default().borders(Borders::ALL))
-            .alignment(Alignment::Center),
-        items_layout[1],
-    );
-    
-    // Desktop Notifications preference
-    let desktop_notif_status = if prefs.desktop_notifications_enabled { "ON" } else { "OFF" };
-    let desktop_notif_style = if app.ui.preferences_selected == 2 {
-        Style::default().fg(Color::Black).bg(Color::LightCyan).add_modifier(Modifier::BOLD)
-    } else {
-        Style::default().fg(Color::White)
-    };
-    
-    f.render_widget(
-        Paragraph::new(format!("🔔 Desktop Notifications: {}", desktop_notif_status))
-            .style(desktop_notif_style)
-            .block(Block::default().borders(Borders::ALL))
-            .alignment(Alignment::Center),
-        items_layout[2],
-    );
+    for (i, label) in items.iter().enumerate() {
+        let style = if app.ui.preferences_selected == i {
+            Style::default().fg(Color::Black).bg(Color::LightCyan).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let block = if boxed { Block::default().borders(Borders::ALL) } else { Block::default() };
+        f.render_widget(
+            Paragraph::new(label.as_str())
+                .style(style)
+                .block(block)
+                .alignment(Alignment::Center),
+            items_layout[i],
+        );
+    }
     
     // Help text
-    if items_layout.len() > 3 {
-        let help_text = Paragraph::new("Use [↑↓] to navigate, [Space/Enter] to toggle, [Esc] to go back")
-            .style(Style::default().fg(Color::Gray))
-            .alignment(Alignment::Center)
-            .block(Block::default().borders(Borders::ALL).title("Help"));
-        f.render_widget(help_text, items_layout[3]);
-    }
+    let help_text = Paragraph::new("Use [↑↓] to navigate, [Space/Enter] to toggle, [+/-] volume, [M] mute, [Esc] to go back")
+        .style(Style::default().fg(Color::Gray))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL).title("Help"));
+    f.render_widget(help_text, items_layout[items.len()]);
 }
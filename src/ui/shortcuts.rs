@@ -0,0 +1,255 @@
+//! Keyboard shortcut legend: every key available in the current mode/focus.
+
+use ratatui::{Frame, layout::{Rect, Constraint}, style::{Style, Color, Modifier}, widgets::{Block, Borders, BorderType, Clear, Row, Table}};
+use crate::app::{App, AppMode};
+use crate::state::ChatFocus;
+
+/// Keys that work everywhere (outside popups)
+const GLOBAL_KEYS: &[(&str, &str)] = &[
+    ("F1", "About"),
+    ("F2", "Preferences"),
+    ("F3", "Pick background / theme"),
+    ("F4", "Switch server"),
+    ("F6", "Notifications"),
+    ("F7", "Cycle background"),
+    ("F8", "Cycle theme"),
+    ("F9", "Do Not Disturb (on / 1 hour / off)"),
+    ("F12", "Debug overlay (cache, FPS)"),
+    ("?", "This legend"),
+    ("Ctrl+C", "Quit"),
+];
+
+const AUTH_KEYS: &[(&str, &str)] = &[
+    ("Tab / Shift+Tab", "Next / previous field"),
+    ("Enter", "Submit or select"),
+    ("Backspace", "Delete character"),
+    ("Ctrl+W / Ctrl+Bksp", "Delete word"),
+    ("Esc", "Quit"),
+];
+
+const SERVER_PICKER_KEYS: &[(&str, &str)] = &[
+    ("↑ / ↓", "Move selection"),
+    ("j / k", "Move selection (vim)"),
+    ("Enter", "Connect"),
+    ("Esc", "Quit"),
+];
+
+const MAIN_MENU_KEYS: &[(&str, &str)] = &[
+    ("↑ / ↓", "Move selection"),
+    ("j / k", "Move selection (vim)"),
+    ("g g / G", "Jump to top / bottom"),
+    ("Enter", "Open"),
+    ("Esc Esc", "Quit (if enabled in Preferences)"),
+];
+
+const SETTINGS_KEYS: &[(&str, &str)] = &[
+    ("↑ / ↓", "Move selection"),
+    ("j / k", "Move selection (vim)"),
+    ("g g / G", "Jump to top / bottom"),
+    ("Enter", "Open"),
+    ("P", "Preferences"),
+    ("Esc", "Main menu"),
+];
+
+const PREFERENCES_KEYS: &[(&str, &str)] = &[
+    ("↑ / ↓", "Move selection"),
+    ("Space / Enter", "Toggle or cycle option"),
+    ("+ / -", "Sound volume"),
+    ("M", "Mute / unmute sounds"),
+    ("Esc", "Back"),
+];
+
+const COLOR_PICKER_KEYS: &[(&str, &str)] = &[
+    ("← / →", "Choose color"),
+    ("Enter", "Apply"),
+    ("Esc", "Cancel"),
+];
+
+const EDIT_PROFILE_KEYS: &[(&str, &str)] = &[
+    ("Tab / ↓", "Next field"),
+    ("Shift+Tab / ↑", "Previous field"),
+    ("Enter", "Save or activate"),
+    ("Ctrl+V", "Paste"),
+    ("Ctrl+W / Ctrl+Bksp", "Delete word"),
+    ("Esc", "Back"),
+];
+
+const INPUT_KEYS: &[(&str, &str)] = &[
+    ("Enter", "Submit"),
+    ("Ctrl+V", "Paste"),
+    ("Ctrl+W / Ctrl+Bksp", "Delete word"),
+    ("Esc", "Cancel"),
+];
+
+const FORUM_LIST_KEYS: &[(&str, &str)] = &[
+    ("↑ / ↓", "Move selection"),
+    ("j / k", "Move selection (vim)"),
+    ("g g / G", "Jump to top / bottom"),
+    ("Enter", "Open forum"),
+    ("/", "Filter forums as you type"),
+    ("T", "New thread in selected forum"),
+    ("N", "New forum (admin)"),
+    ("D", "Delete forum (admin)"),
+    ("Esc", "Main menu"),
+];
+
+const THREAD_LIST_KEYS: &[(&str, &str)] = &[
+    ("↑ / ↓", "Move selection"),
+    ("j / k", "Move selection (vim)"),
+    ("g g / G", "Jump to top / bottom"),
+    ("Enter", "Open thread"),
+    ("/", "Filter threads as you type"),
+    ("N", "New thread"),
+    ("Alt+D", "Delete thread (admin)"),
+    ("Esc", "Forum list"),
+];
+
+const POST_VIEW_KEYS: &[(&str, &str)] = &[
+    ("↑ / ↓", "Select post"),
+    ("PgUp / PgDn", "Scroll"),
+    ("Home / End", "First / last post"),
+    ("← / →", "Browse replies"),
+    ("Enter", "Jump to reply or original"),
+    ("C", "Toggle reply context"),
+    ("R", "Reply to selected post"),
+    ("Alt+R", "New post"),
+    ("D", "Delete post (yours, or any as admin)"),
+    ("Esc", "Back"),
+];
+
+const CHAT_SIDEBAR_KEYS: &[(&str, &str)] = &[
+    ("↑ / ↓", "Select channel or DM"),
+    ("j / k", "Move selection (vim)"),
+    ("g g / G", "Jump to top / bottom"),
+    ("← / →", "Servers / DMs tab"),
+    ("Enter", "Focus messages"),
+    ("Tab / Shift+Tab", "Change focus"),
+    ("Ctrl+U", "Toggle user list"),
+    ("F5", "Server actions"),
+    ("Esc", "Main menu"),
+];
+
+const CHAT_MESSAGES_KEYS: &[(&str, &str)] = &[
+    ("Enter", "Send"),
+    ("↑ / ↓", "Select message"),
+    ("Y", "Copy selected message"),
+    ("R", "Reply to selected message"),
+    ("O", "Open link in selected message"),
+    ("/", "Search conversation (empty input)"),
+    ("n / N", "Older / newer search match"),
+    ("PgUp / PgDn", "Scroll"),
+    ("← / → / Home / End", "Move cursor"),
+    ("End / Ctrl+End", "Jump to latest (when scrolled up)"),
+    ("Ctrl+V", "Paste"),
+    ("Ctrl+W / Ctrl+Bksp", "Delete word"),
+    ("Tab / Shift+Tab", "Change focus"),
+    ("Ctrl+E", "Emoji picker"),
+    ("Ctrl+U", "Toggle user list"),
+    ("F5", "Server actions"),
+    ("Esc", "Clear selection or reply / main menu"),
+];
+
+const CHAT_USERS_KEYS: &[(&str, &str)] = &[
+    ("↑ / ↓", "Select user"),
+    ("j / k", "Move selection (vim)"),
+    ("g g / G", "Jump to top / bottom"),
+    ("Enter", "User actions"),
+    ("/", "Filter by name (Esc clears)"),
+    ("S", "Sort by status / name / role"),
+    ("O", "Cycle offline users display"),
+    ("Tab / Shift+Tab", "Change focus"),
+    ("Ctrl+U", "Hide user list"),
+    ("F5", "Server actions"),
+    ("Esc", "Main menu"),
+];
+
+const APPEARANCE_PICKER_KEYS: &[(&str, &str)] = &[
+    ("↑ / ↓", "Preview background / theme"),
+    ("j / k", "Move selection (vim)"),
+    ("← / → / Tab", "Switch column"),
+    ("Enter", "Apply and save"),
+    ("Esc", "Cancel"),
+];
+
+const NOTIFICATION_CENTER_KEYS: &[(&str, &str)] = &[
+    ("↑ / ↓", "Move selection (marks it read)"),
+    ("j / k", "Move selection (vim)"),
+    ("PgUp / PgDn", "Move 10 at a time"),
+    ("g g / G", "Jump to top / bottom"),
+    ("Enter", "Open thread / conversation"),
+    ("A", "Mark all read"),
+    ("R", "Refresh"),
+    ("Esc", "Back"),
+];
+
+/// Title and key list for the current mode and focus
+pub fn shortcuts_for(app: &App) -> (&'static str, &'static [(&'static str, &'static str)]) {
+    match app.ui.mode {
+        AppMode::Login | AppMode::Register => ("Login / Register", AUTH_KEYS),
+        AppMode::MainMenu => ("Main Menu", MAIN_MENU_KEYS),
+        AppMode::Settings => ("Settings", SETTINGS_KEYS),
+        AppMode::Preferences => ("Preferences", PREFERENCES_KEYS),
+        AppMode::ColorPicker => ("Color Picker", COLOR_PICKER_KEYS),
+        AppMode::EditProfile => ("Edit Profile", EDIT_PROFILE_KEYS),
+        AppMode::Input => ("Input", INPUT_KEYS),
+        AppMode::ForumList => ("Forums", FORUM_LIST_KEYS),
+        AppMode::ThreadList => ("Threads", THREAD_LIST_KEYS),
+        AppMode::PostView => ("Thread", POST_VIEW_KEYS),
+        AppMode::NotificationCenter => ("Notifications", NOTIFICATION_CENTER_KEYS),
+        AppMode::ServerPicker => ("Servers", SERVER_PICKER_KEYS),
+        AppMode::AppearancePicker => ("Appearance", APPEARANCE_PICKER_KEYS),
+        AppMode::Chat => match app.chat.chat_focus {
+            ChatFocus::Sidebar => ("Chat: Sidebar", CHAT_SIDEBAR_KEYS),
+            ChatFocus::Users => ("Chat: User List", CHAT_USERS_KEYS),
+            ChatFocus::Messages | ChatFocus::DMInput => ("Chat: Messages", CHAT_MESSAGES_KEYS),
+        },
+    }
+}
+
+pub fn draw_shortcuts_popup(f: &mut Frame, app: &App) {
+    let (title, keys) = shortcuts_for(app);
+    let key_style = Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD);
+    let section_style = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+
+    let mut rows: Vec<Row> = keys.iter()
+        .map(|(key, action)| Row::new(vec![
+            ratatui::text::Span::styled(*key, key_style),
+            ratatui::text::Span::raw(*action),
+        ]))
+        .collect();
+    rows.push(Row::new(vec![""]));
+    rows.push(Row::new(vec![ratatui::text::Span::styled("Global", section_style)]));
+    rows.extend(GLOBAL_KEYS.iter().map(|(key, action)| Row::new(vec![
+        ratatui::text::Span::styled(*key, key_style),
+        ratatui::text::Span::raw(*action),
+    ])));
+
+    let key_width = keys.iter().chain(GLOBAL_KEYS.iter())
+        .map(|(key, _)| key.chars().count())
+        .max()
+        .unwrap_or(0) as u16;
+    let action_width = keys.iter().chain(GLOBAL_KEYS.iter())
+        .map(|(_, action)| action.chars().count())
+        .max()
+        .unwrap_or(0) as u16;
+
+    let screen = f.area();
+    let width = (key_width + action_width + 2 + 4).min(screen.width);
+    let height = (rows.len() as u16 + 2).min(screen.height);
+    let area = Rect::new(
+        screen.x + (screen.width - width) / 2,
+        screen.y + (screen.height - height) / 2,
+        width,
+        height,
+    );
+    let block = Block::default()
+        .title(format!("Keys: {} ", title))
+        .title_bottom(" Esc or ? to close ")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Double);
+    let table = Table::new(rows, [Constraint::Length(key_width), Constraint::Min(0)])
+        .column_spacing(2)
+        .block(block);
+    f.render_widget(Clear, area);
+    f.render_widget(table, area);
+}
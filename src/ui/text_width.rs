@@ -0,0 +1,21 @@
+// Terminal cell widths for text, so wide glyphs (emoji, CJK) line up
+use unicode_width::UnicodeWidthChar;
+
+/// Cells a char occupies. With `wide_emoji` off, double-width glyphs count as one
+/// cell for terminals that draw emoji narrow.
+pub fn char_width(c: char, wide_emoji: bool) -> usize {
+    let width = c.width().unwrap_or(0);
+    if wide_emoji { width } else { width.min(1) }
+}
+
+/// Cells a string occupies on a single line.
+pub fn str_width(s: &str, wide_emoji: bool) -> usize {
+    s.chars().map(|c| char_width(c, wide_emoji)).sum()
+}
+
+/// Center `s` within `width` cells, padding with spaces by display width.
+pub fn pad_center(s: &str, width: usize, wide_emoji: bool) -> String {
+    let pad = width.saturating_sub(str_width(s, wide_emoji));
+    let left = pad / 2;
+    format!("{}{}{}", " ".repeat(left), s, " ".repeat(pad - left))
+}
@@ -0,0 +1,139 @@
+// A theme defined in ~/.nexus_theme.toml.
+// Colors are `#rrggbb` hex or color names; anything left out keeps the Minimal palette.
+//
+//     [colors]
+//     primary = "#89b4fa"
+//     secondary = "#6c7086"
+//     background = "#1e1e2e"
+//     text = "#cdd6f4"
+//     selected_bg = "#f5c2e7"
+//     selected_fg = "#1e1e2e"
+//
+//     [accents]
+//     success = "#a6e3a1"
+//     warning = "#f9e2af"
+//     error = "#f38ba8"
+//     info = "#89dceb"
+use std::path::PathBuf;
+use ratatui::{Frame, layout::{Constraint, Rect}, style::{Color, Modifier, Style}};
+use serde::Deserialize;
+use crate::app::App;
+use super::{AccentColors, MinimalTheme, Theme, ThemeColors, ThemeMainMenuLayout};
+use super::minimal::{draw_plain_main_menu, draw_plain_settings_menu};
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ThemeFile {
+    colors: ColorsFile,
+    accents: AccentsFile,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ColorsFile {
+    primary: Option<String>,
+    secondary: Option<String>,
+    background: Option<String>,
+    text: Option<String>,
+    selected_bg: Option<String>,
+    selected_fg: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct AccentsFile {
+    success: Option<String>,
+    warning: Option<String>,
+    error: Option<String>,
+    info: Option<String>,
+}
+
+pub struct CustomTheme {
+    colors: ThemeColors,
+    accents: AccentColors,
+}
+
+pub fn config_path() -> PathBuf {
+    crate::global_prefs::GlobalPrefs::config_file("theme.toml")
+}
+
+/// The parsed color, or `fallback` when it's missing or not a color
+fn color_or(value: &Option<String>, fallback: Color) -> Color {
+    match value {
+        Some(text) => text.trim().parse().unwrap_or_else(|_| {
+            tracing::warn!("theme.toml: '{}' is not a color", text);
+            fallback
+        }),
+        None => fallback,
+    }
+}
+
+impl CustomTheme {
+    /// The theme from the config file, if there is one and it parses
+    pub fn load() -> Option<Self> {
+        let text = std::fs::read_to_string(config_path()).ok()?;
+        let file: ThemeFile = match toml::from_str(&text) {
+            Ok(file) => file,
+            Err(e) => {
+                tracing::warn!("Ignoring invalid {}: {}", config_path().display(), e.message());
+                return None;
+            }
+        };
+        let base = MinimalTheme.colors();
+        let base_accents = MinimalTheme.accents();
+        Some(Self {
+            colors: ThemeColors {
+                primary: color_or(&file.colors.primary, base.primary),
+                secondary: color_or(&file.colors.secondary, base.secondary),
+                background: color_or(&file.colors.background, base.background),
+                text: color_or(&file.colors.text, base.text),
+                selected_bg: color_or(&file.colors.selected_bg, base.selected_bg),
+                selected_fg: color_or(&file.colors.selected_fg, base.selected_fg),
+            },
+            accents: AccentColors {
+                success: color_or(&file.accents.success, base_accents.success),
+                warning: color_or(&file.accents.warning, base_accents.warning),
+                error: color_or(&file.accents.error, base_accents.error),
+                info: color_or(&file.accents.info, base_accents.info),
+            },
+        })
+    }
+}
+
+impl Theme for CustomTheme {
+    fn name(&self) -> &'static str { "Custom" }
+    fn colors(&self) -> ThemeColors {
+        self.colors.clone()
+    }
+    fn accents(&self) -> AccentColors {
+        self.accents.clone()
+    }
+    fn border_color(&self, _tick: u64) -> Color {
+        self.colors.primary
+    }
+    fn selected_style(&self) -> Style {
+        Style::default().fg(self.colors.selected_fg).bg(self.colors.selected_bg).add_modifier(Modifier::BOLD)
+    }
+    fn text_style(&self) -> Style {
+        Style::default().fg(self.colors.text)
+    }
+    fn draw_top_banner(&self, _f: &mut Frame, _app: &App, _area: Rect) {}
+    fn draw_bottom_banner(&self, _f: &mut Frame, _app: &App, _area: Rect) {}
+    fn draw_main_menu(&self, f: &mut Frame, main_menu_state: &mut ratatui::widgets::ListState, _tick: u64, area: Rect) {
+        draw_plain_main_menu(f, main_menu_state, &self.colors, Style::default().fg(self.colors.primary), area);
+    }
+    fn draw_settings_menu(&self, f: &mut Frame, settings_list_state: &mut ratatui::widgets::ListState, _tick: u64, area: Rect) {
+        draw_plain_settings_menu(f, settings_list_state, &self.colors, Style::default().fg(self.colors.primary), area);
+    }
+    fn draw_floating_elements(&self, _f: &mut Frame, _app: &App, _area: Rect) {}
+    fn main_menu_layout(&self, _area: Rect) -> ThemeMainMenuLayout {
+        ThemeMainMenuLayout {
+            constraints: vec![
+                Constraint::Min(12),
+                Constraint::Length(3),
+            ],
+            show_top_banner: false,
+            show_status: true,
+        }
+    }
+}
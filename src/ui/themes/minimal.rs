@@ -45,91 +45,11 @@ impl Theme for MinimalTheme {
     fn draw_bottom_banner(&self, _f: &mut ratatui::Frame, _app: &crate::app::App, _area: ratatui::layout::Rect) {
         // Minimal: no bottom banner
     }
-    fn draw_main_menu(&self, f: &mut ratatui::Frame, main_menu_state: &mut ratatui::widgets::ListState, tick: u64, area: ratatui::layout::Rect) {
-        use ratatui::{widgets::{Block, List, ListItem, Borders}, style::{Style, Color}, text::{Line, Span}, layout::{Layout, Constraint, Direction}};
-        let menu_items = ["Forums", "Chat", "Settings", "Logout"];
-        let items: Vec<ListItem> = menu_items.iter().enumerate().map(|(i, &name)| {
-            let is_selected = Some(i) == main_menu_state.selected();
-            let style = if is_selected {
-                Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(ratatui::style::Modifier::BOLD)
-            } else {
-                Style::default()
-            };
-            ListItem::new(Line::from(Span::styled(name, style)))
-        }).collect();
-        // Add horizontal margin using a layout
-        let layout = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Min(0)])
-            .margin(2)
-            .split(area);
-        let list_block = Block::default()
-            .borders(Borders::ALL)
-            .title("Menu")
-            .border_style(Style::default());
-        let list = List::new(items).block(list_block);
-        f.render_stateful_widget(list, layout[0], main_menu_state);
+    fn draw_main_menu(&self, f: &mut ratatui::Frame, main_menu_state: &mut ratatui::widgets::ListState, _tick: u64, area: ratatui::layout::Rect) {
+        draw_plain_main_menu(f, main_menu_state, &self.colors(), Style::default(), area);
     }
-    fn draw_settings_menu(&self, f: &mut ratatui::Frame, settings_list_state: &mut ratatui::widgets::ListState, tick: u64, area: ratatui::layout::Rect) {
-        use ratatui::{widgets::{Block, List, ListItem, Borders, Paragraph}, style::{Style, Color}, text::{Line, Span}, layout::{Layout, Constraint, Direction}};
-        let settings_items = ["Change Password", "Change Color", "Edit Profile", "Preferences"];
-        let items: Vec<ListItem> = settings_items.iter().enumerate().map(|(i, &name)| {
-            let is_selected = Some(i) == settings_list_state.selected();
-            let style = if is_selected {
-                Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(ratatui::style::Modifier::BOLD)
-            } else {
-                Style::default()
-            };
-            ListItem::new(Line::from(Span::styled(name, style)))
-        }).collect();
-        // Layout: left = list, right = info panel
-        let layout = if area.width >= 60 {
-            Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-                .horizontal_margin(2)
-                .split(area)
-        } else {
-            Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([Constraint::Min(6), Constraint::Length(6)])
-                .split(area)
-        };
-        let list_block = Block::default()
-            .borders(Borders::ALL)
-            .title("Settings")
-            .border_style(Style::default());
-        let list = List::new(items).block(list_block);
-        f.render_stateful_widget(list, layout[0], settings_list_state);
-        // Info panel
-        let selected = settings_list_state.selected().unwrap_or(0);
-        let info_lines = match selected {
-            0 => vec![
-                Line::from("Change your password for better security."),
-                Line::from("Recommended: Use a strong, unique password."),
-            ],
-            1 => vec![
-                Line::from("Change the color theme of the app."),
-                Line::from("Try different palettes for accessibility."),
-            ],
-            2 => vec![
-                Line::from("Edit your profile information."),
-                Line::from("Update your bio, avatar, and links."),
-            ],
-            3 => vec![
-                Line::from("Configure app preferences."),
-                Line::from("Sound, notifications, and more."),
-            ],
-            _ => vec![Line::from("")],
-        };
-        let info_block = Block::default()
-            .borders(Borders::ALL)
-            .title("Info")
-            .border_style(Style::default().fg(Color::Gray));
-        let mut info_content = info_lines;
-        info_content.push(Line::from(""));
-        info_content.push(Line::from("[↑↓] Select  [Enter] Edit  [Esc] Back"));
-        f.render_widget(Paragraph::new(info_content).block(info_block).alignment(ratatui::layout::Alignment::Left), layout[1]);
+    fn draw_settings_menu(&self, f: &mut ratatui::Frame, settings_list_state: &mut ratatui::widgets::ListState, _tick: u64, area: ratatui::layout::Rect) {
+        draw_plain_settings_menu(f, settings_list_state, &self.colors(), Style::default(), area);
     }
     fn draw_floating_elements(&self, _f: &mut Frame, _app: &App, _area: Rect) {
         // Minimal: no floating elements
@@ -144,4 +64,94 @@ impl Theme for MinimalTheme {
             show_status: true,
         }
     }
-}
\ No newline at end of file
+}
+
+/// Plain bordered menu list, colored from a palette (shared with the custom theme)
+pub(super) fn draw_plain_main_menu(f: &mut Frame, main_menu_state: &mut ratatui::widgets::ListState, colors: &ThemeColors, border: Style, area: Rect) {
+    use ratatui::{widgets::{Block, List, ListItem, Borders}, text::{Line, Span}, layout::{Layout, Direction}};
+    let menu_items = ["Forums", "Chat", "Settings", "Logout"];
+    let items: Vec<ListItem> = menu_items.iter().enumerate().map(|(i, &name)| {
+        let is_selected = Some(i) == main_menu_state.selected();
+        let style = if is_selected {
+            Style::default().fg(colors.selected_fg).bg(colors.selected_bg).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        ListItem::new(Line::from(Span::styled(name, style)))
+    }).collect();
+    // Add horizontal margin using a layout
+    let layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0)])
+        .margin(2)
+        .split(area);
+    let list_block = Block::default()
+        .borders(Borders::ALL)
+        .title("Menu")
+        .border_style(border);
+    let list = List::new(items).block(list_block);
+    f.render_stateful_widget(list, layout[0], main_menu_state);
+}
+
+/// Plain settings list with an info panel, colored from a palette (shared with the custom theme)
+pub(super) fn draw_plain_settings_menu(f: &mut Frame, settings_list_state: &mut ratatui::widgets::ListState, colors: &ThemeColors, border: Style, area: Rect) {
+    use ratatui::{widgets::{Block, List, ListItem, Borders}, text::{Line, Span}, layout::{Layout, Direction}};
+    let settings_items = ["Change Password", "Change Color", "Edit Profile", "Preferences"];
+    let items: Vec<ListItem> = settings_items.iter().enumerate().map(|(i, &name)| {
+        let is_selected = Some(i) == settings_list_state.selected();
+        let style = if is_selected {
+            Style::default().fg(colors.selected_fg).bg(colors.selected_bg).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        ListItem::new(Line::from(Span::styled(name, style)))
+    }).collect();
+    // Layout: left = list, right = info panel
+    let layout = if area.width >= 60 {
+        Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .horizontal_margin(2)
+            .split(area)
+    } else {
+        Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(6), Constraint::Length(6)])
+            .split(area)
+    };
+    let list_block = Block::default()
+        .borders(Borders::ALL)
+        .title("Settings")
+        .border_style(border);
+    let list = List::new(items).block(list_block);
+    f.render_stateful_widget(list, layout[0], settings_list_state);
+    // Info panel
+    let selected = settings_list_state.selected().unwrap_or(0);
+    let info_lines = match selected {
+        0 => vec![
+            Line::from("Change your password for better security."),
+            Line::from("Recommended: Use a strong, unique password."),
+        ],
+        1 => vec![
+            Line::from("Change the color theme of the app."),
+            Line::from("Try different palettes for accessibility."),
+        ],
+        2 => vec![
+            Line::from("Edit your profile information."),
+            Line::from("Update your bio, avatar, and links."),
+        ],
+        3 => vec![
+            Line::from("Configure app preferences."),
+            Line::from("Sound, notifications, and more."),
+        ],
+        _ => vec![Line::from("")],
+    };
+    let info_block = Block::default()
+        .borders(Borders::ALL)
+        .title("Info")
+        .border_style(Style::default().fg(colors.secondary));
+    let mut info_content = info_lines;
+    info_content.push(Line::from(""));
+    info_content.push(Line::from("[↑↓] Select  [Enter] Edit  [Esc] Back"));
+    f.render_widget(Paragraph::new(info_content).block(info_block).alignment(ratatui::layout::Alignment::Left), layout[1]);
+}
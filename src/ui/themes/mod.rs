@@ -3,9 +3,11 @@ use ratatui::layout::{Constraint, Rect};
 
 mod cyberpunk;
 mod minimal;
+mod custom;
 
 pub use cyberpunk::CyberpunkTheme;
 pub use minimal::MinimalTheme;
+pub use custom::CustomTheme;
 
 #[derive(Clone)]
 pub struct ThemeColors {
@@ -67,10 +69,14 @@ pub struct ThemeManager {
 
 impl ThemeManager {
     pub fn new() -> Self {
-        let themes: Vec<Box<dyn Theme>> = vec![
+        let mut themes: Vec<Box<dyn Theme>> = vec![
             Box::new(CyberpunkTheme),
             Box::new(MinimalTheme),
         ];
+        // User palette from theme.toml, when present
+        if let Some(custom) = CustomTheme::load() {
+            themes.push(Box::new(custom));
+        }
         Self { themes, current_index: 0 }
     }
     pub fn get_current_theme(&self) -> &dyn Theme {
@@ -82,9 +88,14 @@ impl ThemeManager {
     pub fn get_theme_name(&self) -> &str {
         self.get_current_theme().name()
     }
+    pub fn names(&self) -> Vec<&str> {
+        self.themes.iter().map(|t| t.name()).collect()
+    }
     pub fn set_theme_by_name(&mut self, name: &str) {
-        if let Some(idx) = self.themes.iter().position(|t| t.name().eq_ignore_ascii_case(name)) {
-            self.current_index = idx;
-        }
+        // A saved name that no longer exists (e.g. a removed theme file) falls back to the default
+        self.current_index = self.themes.iter().position(|t| t.name().eq_ignore_ascii_case(name)).unwrap_or_else(|| {
+            tracing::warn!("Unknown theme '{}', using the default", name);
+            0
+        });
     }
 }
\ No newline at end of file
@@ -2,29 +2,50 @@ use chrono::{DateTime, Local, Duration, Datelike};
 use chrono_humanize::HumanTime;
 use chrono::TimeZone;
 
-/// Format a message timestamp for chat display, Discord-style.
+/// Format a message timestamp for chat display, Discord-style, with the time of day
+/// written using the strftime pattern `clock` (see `GlobalPrefs::clock_format`).
 /// - <5min: humanized ("just now", "2 minutes ago")
 /// - Today: "9:39 PM"
 /// - Yesterday: "Yesterday, 9:39 PM"
 /// - Older: "6/16/25, 8:30 AM"
-pub fn format_message_timestamp(ts: i64, now: DateTime<Local>) -> String {
+pub fn format_message_timestamp(ts: i64, now: DateTime<Local>, clock: &str) -> String {
     let dt = Local.timestamp_opt(ts, 0).single();
     if let Some(dt) = dt {
         let duration = now.signed_duration_since(dt);
         if duration < Duration::minutes(5) {
             HumanTime::from(dt).to_string()
         } else if dt.date_naive() == now.date_naive() {
-            dt.format("%-I:%M %p").to_string()
+            dt.format(clock).to_string()
         } else if dt.date_naive() == (now - Duration::days(1)).date_naive() {
-            format!("Yesterday, {}", dt.format("%-I:%M %p"))
+            format!("Yesterday, {}", dt.format(clock))
         } else {
-            dt.format("%-m/%-d/%y, %-I:%M %p").to_string()
+            format!("{}, {}", dt.format("%-m/%-d/%y"), dt.format(clock))
         }
     } else {
         "?".to_string()
     }
 }
 
+/// Short relative age for lists: "just now", "12m ago", "3h ago", "yesterday", "4d ago",
+/// then the date ("6/16/25") past a week.
+pub fn format_relative_time(ts: i64, now: DateTime<Local>) -> String {
+    let Some(dt) = Local.timestamp_opt(ts, 0).single() else { return "?".to_string() };
+    let duration = now.signed_duration_since(dt);
+    if duration < Duration::minutes(1) {
+        "just now".to_string()
+    } else if duration < Duration::hours(1) {
+        format!("{}m ago", duration.num_minutes())
+    } else if dt.date_naive() == now.date_naive() {
+        format!("{}h ago", duration.num_hours())
+    } else if dt.date_naive() == (now - Duration::days(1)).date_naive() {
+        "yesterday".to_string()
+    } else if duration < Duration::days(7) {
+        format!("{}d ago", (now.date_naive() - dt.date_naive()).num_days())
+    } else {
+        dt.format("%-m/%-d/%y").to_string()
+    }
+}
+
 /// Format a date for a date delimiter (e.g., "June 16th, 2025")
 pub fn format_date_delimiter(ts: i64) -> String {
     let dt = Local.timestamp_opt(ts, 0).single();
@@ -41,3 +62,67 @@ pub fn format_date_delimiter(ts: i64) -> String {
         "?".to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::global_prefs::GlobalPrefs;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(y, m, d, h, min, 0).single().unwrap()
+    }
+
+    fn prefs(time_format_24h: bool, time_format: Option<&str>) -> GlobalPrefs {
+        GlobalPrefs { time_format_24h, time_format: time_format.map(str::to_string), ..GlobalPrefs::default() }
+    }
+
+    /// Messages at 00:00 today, 23:59 and 00:00 yesterday, and 23:59 two days ago
+    fn format_all(clock: &str) -> [String; 4] {
+        let now = at(2025, 6, 17, 0, 10);
+        [
+            at(2025, 6, 17, 0, 0),
+            at(2025, 6, 16, 23, 59),
+            at(2025, 6, 16, 0, 0),
+            at(2025, 6, 15, 23, 59),
+        ].map(|dt| format_message_timestamp(dt.timestamp(), now, clock))
+    }
+
+    #[test]
+    fn twenty_four_hour_clock() {
+        assert_eq!(
+            format_all(prefs(true, None).clock_format()),
+            ["00:00", "Yesterday, 23:59", "Yesterday, 00:00", "6/15/25, 23:59"],
+        );
+    }
+
+    #[test]
+    fn twelve_hour_clock() {
+        assert_eq!(
+            format_all(prefs(false, None).clock_format()),
+            ["12:00 AM", "Yesterday, 11:59 PM", "Yesterday, 12:00 AM", "6/15/25, 11:59 PM"],
+        );
+    }
+
+    #[test]
+    fn custom_clock_overrides_both() {
+        for twenty_four in [true, false] {
+            assert_eq!(
+                format_all(prefs(twenty_four, Some("%Hh%M")).clock_format()),
+                ["00h00", "Yesterday, 23h59", "Yesterday, 00h00", "6/15/25, 23h59"],
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_custom_clock_falls_back() {
+        assert_eq!(prefs(true, Some("%Q")).clock_format(), "%H:%M");
+        assert_eq!(prefs(false, Some("")).clock_format(), "%-I:%M %p");
+    }
+
+    #[test]
+    fn recent_messages_are_humanized() {
+        let now = Local::now();
+        let formatted = format_message_timestamp((now - Duration::minutes(2)).timestamp(), now, "%H:%M");
+        assert!(!formatted.contains(':'), "{}", formatted);
+    }
+}
@@ -0,0 +1,65 @@
+// Unread tracking persisted between sessions, per account
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::fs;
+use serde::{Serialize, Deserialize};
+use uuid::Uuid;
+
+/// One account's unread conversations and the newest message seen in each
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct UnreadSnapshot {
+    /// Unread message counts per channel / DM partner
+    #[serde(deserialize_with = "unread_counts")]
+    pub channels: HashMap<Uuid, usize>,
+    #[serde(deserialize_with = "unread_counts")]
+    pub dms: HashMap<Uuid, usize>,
+    /// Conversation (channel id or DM partner id) -> newest message id seen
+    pub last_read: HashMap<Uuid, Uuid>,
+    /// Forum thread id -> newest post id seen
+    pub threads: HashMap<Uuid, Uuid>,
+}
+
+/// Read unread counts, also accepting the older format that stored only a list of
+/// unread ids (each counts as one unread message)
+fn unread_counts<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<HashMap<Uuid, usize>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Counts {
+        Map(HashMap<Uuid, usize>),
+        List(Vec<Uuid>),
+    }
+    Ok(match Counts::deserialize(deserializer)? {
+        Counts::Map(map) => map,
+        Counts::List(ids) => ids.into_iter().map(|id| (id, 1)).collect(),
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct UnreadStore {
+    pub accounts: HashMap<Uuid, UnreadSnapshot>,
+}
+
+impl UnreadStore {
+    pub fn config_path() -> PathBuf {
+        crate::global_prefs::GlobalPrefs::config_file("unread.json")
+    }
+
+    pub fn load() -> Self {
+        let path = Self::config_path();
+        if let Ok(data) = fs::read_to_string(&path) {
+            if let Ok(store) = serde_json::from_str(&data) {
+                return store;
+            }
+        }
+        Self::default()
+    }
+
+    pub fn save(&self) {
+        let path = Self::config_path();
+        if let Ok(data) = serde_json::to_string_pretty(self) {
+            let _ = fs::write(path, data);
+        }
+    }
+}